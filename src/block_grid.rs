@@ -1,41 +1,55 @@
-// FIXME: Fix and remove eventally
-#![allow(clippy::result_unit_err)]
-
 use alloc::{vec, vec::Vec};
+use core::any::TypeId;
+use core::hash::{Hash, Hasher};
 use core::marker::PhantomData;
-use core::ops::{Index, IndexMut};
+use core::ops::{Index, IndexMut, Range};
+use core::ptr;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::iters::{BlockIter, BlockIterMut, EachIter, EachIterMut, RowMajorIter, RowMajorIterMut};
-use crate::{BlockDim, Coords};
+use crate::iters::{
+    BlockIter, BlockIterMut, ColMajorIter, ColMajorIterMut, ColsIter, ColsIterMut, EachIter,
+    EachIterMut, HilbertIter, HilbertIterMut, Line, LineMut, MortonIter, MortonIterMut,
+    RowMajorIter, RowMajorIterMut, RowsIter, RowsIterMut,
+};
+use crate::{BlockShape, Blocked, Coords, Dims, DynDim, Error, Grid, Layout, U1};
 
-/// A fixed-size 2D array with a blocked memory representation.
+/// A 2D array with a blocked memory representation.
 ///
 /// See [crate-level documentation][crate] for general usage info.
 ///
-/// If your dimensions are not a multiple of the block size, you can use the helper function
-/// [`BlockDim::round_up_to_valid`] to generate larger, valid dimensions.
+/// The dimensions do not have to be a multiple of the block size. If they aren't, the memory is
+/// internally padded up to the next multiple (see [`BlockShape::round_up_to_valid`]), and the edge
+/// blocks are only partially valid. The padding is never visible through indexing or the element
+/// iterators, only through the raw memory accessors.
+///
+/// The order of the memory is given by `L`, which defaults to row-major blocks of row-major
+/// elements. See [`Layout`] for the others, and what they support.
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[cfg_attr(feature = "serde", serde(bound(serialize = "T: Clone + Serialize")))]
 #[cfg_attr(feature = "serde", serde(try_from = "serde_hack::ShadowBlockGrid<T>"))]
 #[cfg_attr(feature = "serde", serde(into = "serde_hack::ShadowBlockGrid<T>"))]
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
-pub struct BlockGrid<T, B: BlockDim> {
+#[derive(Clone, Debug)]
+pub struct BlockGrid<T, B: BlockShape, L: Layout = Blocked> {
     rows: usize,
     cols: usize,
     col_blocks: usize,
     buf: Vec<T>,
-    _phantom: PhantomData<B>,
+    _phantom: PhantomData<(B, L)>,
 }
 
 /// A view of a 2D block contiguous in memory.
 ///
-/// Can be obtained via [`BlockIter`], which is created by calling [`BlockGrid::block_iter`].
+/// Can be obtained via [`BlockIter`], which is created by calling [`BlockGrid::block_iter`]. The
+/// blocks of a [`DynBlockGrid`] are the same, with [`DynDim`] for `B`.
+///
+/// [`DynBlockGrid`]: crate::DynBlockGrid
 #[derive(Clone, Copy, Debug)]
-pub struct Block<'a, T, B: BlockDim> {
+pub struct Block<'a, T, B: Dims> {
     block_coords: Coords,
+    extent: Coords,
+    dims: B::Value,
     arr: &'a [T],
     _phantom: PhantomData<B>,
 }
@@ -43,36 +57,42 @@ pub struct Block<'a, T, B: BlockDim> {
 /// A mutable view of a 2D block contiguous in memory.
 ///
 /// Can be obtained via [`BlockIterMut`], which is created by calling [`BlockGrid::block_iter_mut`].
+/// The blocks of a [`DynBlockGrid`] are the same, with [`DynDim`] for `B`.
+///
+/// [`DynBlockGrid`]: crate::DynBlockGrid
 #[derive(Debug)]
-pub struct BlockMut<'a, T, B: BlockDim> {
+pub struct BlockMut<'a, T, B: Dims> {
     block_coords: Coords,
+    extent: Coords,
+    dims: B::Value,
     arr: &'a mut [T],
     _phantom: PhantomData<B>,
 }
 
-impl<T, B: BlockDim> BlockGrid<T, B> {
+impl<T, B: BlockShape, L: Layout> BlockGrid<T, B, L> {
     /// Constructs a `BlockGrid<T, B>` by consuming a [`Vec<T>`].
     ///
-    /// The ordering of the memory is taken as is in the vector.
+    /// The ordering of the memory is taken as is in the vector. This includes any padding, so
+    /// `elems` must have the length of the dimensions rounded up to the block size.
     ///
     /// # Errors
     ///
-    /// If invalid dimensions, either because `rows` and `cols` do not divide evenly into the block
-    /// size `B` or the length of `elems` does not match `rows * cols`.
-    pub fn from_raw_vec(rows: usize, cols: usize, elems: Vec<T>) -> Result<Self, ()> {
-        if !Self::valid_size(rows, cols) || rows * cols != elems.len() {
-            return Err(());
-        }
+    /// If invalid dimensions, either because `rows` or `cols` is zero or the length of `elems`
+    /// does not match the padded size. See [`Error`] for details.
+    pub fn from_raw_vec(rows: usize, cols: usize, elems: Vec<T>) -> Result<Self, Error> {
+        Self::check_len(Self::check_size(rows, cols)?, elems.len())?;
         Ok(Self {
             rows,
             cols,
-            col_blocks: cols / B::WIDTH,
+            col_blocks: calc_blocks(cols, B::WIDTH),
             buf: elems,
             _phantom: PhantomData,
         })
     }
 
     /// Converts a `BlockGrid<T, B>` to a [`Vec<T>`] in memory order.
+    ///
+    /// This includes any padding elements.
     #[inline]
     pub fn take_raw_vec(self) -> Vec<T> {
         self.buf
@@ -99,7 +119,7 @@ impl<T, B: BlockDim> BlockGrid<T, B> {
     /// Returns the number of blocks in the vertical direction.
     #[inline]
     pub fn row_blocks(&self) -> usize {
-        self.rows / B::WIDTH
+        calc_blocks(self.rows, B::HEIGHT)
     }
 
     /// Returns the number of blocks in the horizontal direction.
@@ -168,17 +188,274 @@ impl<T, B: BlockDim> BlockGrid<T, B> {
     }
 
     /// Returns all elements as a slice in memory order.
+    ///
+    /// This includes any padding elements.
     #[inline]
     pub fn raw(&self) -> &[T] {
         &self.buf
     }
 
     /// Returns all elements as a mutable slice in memory order.
+    ///
+    /// This includes any padding elements.
     #[inline]
     pub fn raw_mut(&mut self) -> &mut [T] {
         &mut self.buf
     }
 
+    /// Returns an iterator over all the elements in [row-major order][row_major].
+    ///
+    /// This ordering is what you're probably used to with usual 2D arrays. This method may be
+    /// useful for converting between array types or general IO. If you also need the coordinates
+    /// while iterating, follow up with a chained [`.coords()`][coords] call.
+    ///
+    /// [row_major]: https://en.wikipedia.org/wiki/Row-_and_column-major_order
+    /// [coords]: crate::CoordsIterator::coords()
+    #[inline]
+    pub fn row_major_iter(&self) -> RowMajorIter<'_, T, B, L> {
+        RowMajorIter::new((self.rows, self.cols), self.block_dims(), (), &self.buf)
+    }
+
+    /// Returns an mutable iterator over all the elements in [row-major order][row_major].
+    ///
+    /// If you also need the coordinates while iterating, follow up with a chained
+    /// [`.coords()`][coords] call.
+    ///
+    /// [row_major]: https://en.wikipedia.org/wiki/Row-_and_column-major_order
+    /// [coords]: crate::CoordsIterator::coords()
+    #[inline]
+    pub fn row_major_iter_mut(&mut self) -> RowMajorIterMut<'_, T, B, L> {
+        let (size, blocks) = ((self.rows, self.cols), self.block_dims());
+        RowMajorIterMut::new(size, blocks, (), &mut self.buf)
+    }
+
+    /// Returns an iterator over all the elements in [column-major order][col_major].
+    ///
+    /// This goes down each column in turn, which is the order used by Fortran and many linear
+    /// algebra libraries. If you also need the coordinates while iterating, follow up with a
+    /// chained [`.coords()`][coords] call.
+    ///
+    /// [col_major]: https://en.wikipedia.org/wiki/Row-_and_column-major_order
+    /// [coords]: crate::CoordsIterator::coords()
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, U2};
+    ///
+    /// let data: Vec<_> = (0..(2 * 3)).collect();
+    /// let grid = BlockGrid::<usize, U2>::from_row_major(2, 3, &data)?;
+    ///
+    /// assert!(grid.col_major_iter().copied().eq([0, 3, 1, 4, 2, 5]));
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    #[inline]
+    pub fn col_major_iter(&self) -> ColMajorIter<'_, T, B, L> {
+        ColMajorIter::new(self)
+    }
+
+    /// Returns an mutable iterator over all the elements in [column-major order][col_major].
+    ///
+    /// If you also need the coordinates while iterating, follow up with a chained
+    /// [`.coords()`][coords] call.
+    ///
+    /// [col_major]: https://en.wikipedia.org/wiki/Row-_and_column-major_order
+    /// [coords]: crate::CoordsIterator::coords()
+    #[inline]
+    pub fn col_major_iter_mut(&mut self) -> ColMajorIterMut<'_, T, B, L> {
+        ColMajorIterMut::new(self)
+    }
+
+    /// Returns an immutable iterator over all the elements along a [Morton curve][morton].
+    ///
+    /// Each quadrant is visited in turn, top-left, top-right, bottom-left, then bottom-right, and
+    /// likewise within each quadrant. A grid that isn't square is split into square tiles along
+    /// its longer side, the smallest power-of-two that covers the shorter side, and each tile is
+    /// visited in turn. For square power-of-two blocks, as long as the grid is at least a block
+    /// in each dimension, this visits each block in one go.
+    ///
+    /// If you also need the coordinates while iterating, follow up with a chained
+    /// [`.coords()`][coords] call.
+    ///
+    /// [morton]: https://en.wikipedia.org/wiki/Z-order_curve
+    /// [coords]: crate::CoordsIterator::coords()
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, U2};
+    ///
+    /// let data: Vec<_> = (0..(2 * 4)).collect();
+    /// let grid = BlockGrid::<usize, U2>::from_row_major(2, 4, &data)?;
+    ///
+    /// assert!(grid.morton_iter().copied().eq([0, 1, 4, 5, 2, 3, 6, 7]));
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    #[inline]
+    pub fn morton_iter(&self) -> MortonIter<'_, T, B, L> {
+        MortonIter::new(self)
+    }
+
+    /// Returns a mutable iterator over all the elements along a [Morton curve][morton].
+    ///
+    /// See [`morton_iter`][Self::morton_iter] for the exact order. If you also need the
+    /// coordinates while iterating, follow up with a chained [`.coords()`][coords] call.
+    ///
+    /// [morton]: https://en.wikipedia.org/wiki/Z-order_curve
+    /// [coords]: crate::CoordsIterator::coords()
+    #[inline]
+    pub fn morton_iter_mut(&mut self) -> MortonIterMut<'_, T, B, L> {
+        MortonIterMut::new(self)
+    }
+
+    /// Returns an immutable iterator over all the elements along a [Hilbert curve][hilbert].
+    ///
+    /// The curve starts at the top-left, and each element is next to the one before it, which
+    /// keeps nearby elements closer together than the [Morton curve][Self::morton_iter]. A grid
+    /// that isn't square is split into square tiles along its longer side, the smallest
+    /// power-of-two that covers the shorter side, and each tile is visited in turn. Within a
+    /// tile, elements outside the grid are skipped over, so the curve may jump around near the
+    /// edges.
+    ///
+    /// If you also need the coordinates while iterating, follow up with a chained
+    /// [`.coords()`][coords] call.
+    ///
+    /// [hilbert]: https://en.wikipedia.org/wiki/Hilbert_curve
+    /// [coords]: crate::CoordsIterator::coords()
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, CoordsIterator, U2};
+    ///
+    /// let grid = BlockGrid::<u8, U2>::new(2, 2)?;
+    ///
+    /// let coords: Vec<_> = grid.hilbert_iter().coords().map(|(c, _)| c).collect();
+    /// assert_eq!(coords, [(0, 0), (1, 0), (1, 1), (0, 1)]);
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    #[inline]
+    pub fn hilbert_iter(&self) -> HilbertIter<'_, T, B, L> {
+        HilbertIter::new(self)
+    }
+
+    /// Returns a mutable iterator over all the elements along a [Hilbert curve][hilbert].
+    ///
+    /// See [`hilbert_iter`][Self::hilbert_iter] for the exact order. If you also need the
+    /// coordinates while iterating, follow up with a chained [`.coords()`][coords] call.
+    ///
+    /// [hilbert]: https://en.wikipedia.org/wiki/Hilbert_curve
+    /// [coords]: crate::CoordsIterator::coords()
+    #[inline]
+    pub fn hilbert_iter_mut(&mut self) -> HilbertIterMut<'_, T, B, L> {
+        HilbertIterMut::new(self)
+    }
+
+    /// Converts to a grid with a different memory layout, `L2`, moving every element without
+    /// cloning.
+    ///
+    /// The padding is moved along with the rest. If the layout is the same, the memory is reused
+    /// as is.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, Blocked, Morton, U2};
+    ///
+    /// let data: Vec<_> = (0..(4 * 4)).collect();
+    /// let grid = BlockGrid::<usize, U2>::from_row_major(4, 4, &data)?;
+    ///
+    /// let morton = grid.clone().relayout::<Blocked<Morton, Morton>>();
+    /// assert_eq!(&morton.raw()[..8], &[0, 1, 4, 5, 2, 3, 6, 7]);
+    /// assert!(morton.relayout() == grid);
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn relayout<L2: Layout>(mut self) -> BlockGrid<T, B, L2> {
+        let (rows, cols, col_blocks) = (self.rows, self.cols, self.col_blocks);
+        if TypeId::of::<L>() == TypeId::of::<L2>() {
+            // Same memory layout, so just reuse the allocation
+            return BlockGrid {
+                rows,
+                cols,
+                col_blocks,
+                buf: self.take_raw_vec(),
+                _phantom: PhantomData,
+            };
+        }
+        let len = self.buf.len();
+        let blocks = self.block_dims();
+        let mut buf: Vec<T> = Vec::with_capacity(len);
+        // Elements are moved out by reading, so `self.buf` must not drop them again. If a layout
+        // panics, all the elements are leaked, which is safe.
+        // SAFETY: All elements are moved out below
+        unsafe { self.buf.set_len(0) };
+        let (src, dst) = (self.buf.as_ptr(), buf.as_mut_ptr());
+        let (pad_rows, pad_cols) = B::round_up_to_valid(rows, cols);
+        for row in 0..pad_rows {
+            for col in 0..pad_cols {
+                let coords = (row, col);
+                // SAFETY: Both layouts map each padded coordinate to a distinct index below
+                //         `len`, so every element is moved exactly once
+                unsafe {
+                    let (from, to) = (
+                        L::offset::<B>(blocks, coords),
+                        L2::offset::<B>(blocks, coords),
+                    );
+                    ptr::copy_nonoverlapping(src.add(from), dst.add(to), 1);
+                }
+            }
+        }
+        // SAFETY: Every index below `len` was written to exactly once
+        unsafe { buf.set_len(len) };
+        BlockGrid {
+            rows,
+            cols,
+            col_blocks,
+            buf,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns `true` if the grid has any padding elements.
+    #[inline]
+    pub(crate) fn is_padded(&self) -> bool {
+        self.buf.len() != self.size()
+    }
+
+    /// Checks if `rows` and `cols` form a valid sized `BlockGrid<T, B>`, returning the number
+    /// of elements in memory (including padding) if so.
+    fn check_size(rows: usize, cols: usize) -> Result<usize, Error> {
+        if rows == 0 || cols == 0 {
+            return Err(Error::EmptyDimensions);
+        }
+        calc_blocks(rows, B::HEIGHT)
+            .checked_mul(calc_blocks(cols, B::WIDTH))
+            .and_then(|blocks| blocks.checked_mul(B::AREA))
+            .ok_or(Error::Overflow)
+    }
+
+    /// Checks that the number of elements given matches the number expected.
+    fn check_len(expected: usize, actual: usize) -> Result<(), Error> {
+        if expected != actual {
+            return Err(Error::LengthMismatch { expected, actual });
+        }
+        Ok(())
+    }
+
+    /// Returns the number of blocks in each direction, as `(row_blocks, col_blocks)`.
+    #[inline]
+    pub(crate) fn block_dims(&self) -> Coords {
+        (self.row_blocks(), self.col_blocks())
+    }
+
+    /// Returns the 1D memory index calculated from 2D coordinates.
+    #[inline]
+    fn calc_index(&self, coords: Coords) -> usize {
+        L::offset::<B>(self.block_dims(), coords)
+    }
+}
+
+impl<T, B: BlockShape> BlockGrid<T, B> {
     /// Returns an iterator over all the elements in memory order.
     ///
     /// If you wanna visit each element arbitrarily, this would be the best way. If you also need
@@ -187,7 +464,7 @@ impl<T, B: BlockDim> BlockGrid<T, B> {
     /// [coords]: crate::CoordsIterator::coords()
     #[inline]
     pub fn each_iter(&self) -> EachIter<'_, T, B> {
-        EachIter::new(self)
+        EachIter::new((self.rows, self.cols), self.col_blocks, (), &self.buf)
     }
 
     /// Returns a mutable iterator over all the elements in memory order.
@@ -198,7 +475,7 @@ impl<T, B: BlockDim> BlockGrid<T, B> {
     /// [coords]: crate::CoordsIterator::coords()
     #[inline]
     pub fn each_iter_mut(&mut self) -> EachIterMut<'_, T, B> {
-        EachIterMut::new(self)
+        EachIterMut::new((self.rows, self.cols), self.col_blocks, (), &mut self.buf)
     }
 
     /// Returns an iterator over all blocks in memory order, yielding [`Block`]s.
@@ -211,7 +488,7 @@ impl<T, B: BlockDim> BlockGrid<T, B> {
     /// [coords]: crate::CoordsIterator::coords()
     #[inline]
     pub fn block_iter(&self) -> BlockIter<'_, T, B> {
-        BlockIter::new(self)
+        BlockIter::new((self.rows, self.cols), self.block_dims(), (), &self.buf)
     }
 
     /// Returns a mutable iterator over all blocks in memory order, yielding [`BlockMut`]s.
@@ -224,66 +501,410 @@ impl<T, B: BlockDim> BlockGrid<T, B> {
     /// [coords]: crate::CoordsIterator::coords()
     #[inline]
     pub fn block_iter_mut(&mut self) -> BlockIterMut<'_, T, B> {
-        BlockIterMut::new(self)
+        let (size, blocks) = ((self.rows, self.cols), self.block_dims());
+        BlockIterMut::new(size, blocks, (), &mut self.buf)
     }
 
-    /// Returns an iterator over all the elements in [row-major order][row_major].
+    /// Returns a view of the `i`-th row, which can also be iterated over.
     ///
-    /// This ordering is what you're probably used to with usual 2D arrays. This method may be
-    /// useful for converting between array types or general IO. If you also need the coordinates
-    /// while iterating, follow up with a chained [`.coords()`][coords] call.
+    /// The elements of a row are contiguous within each block, but not across blocks. The
+    /// returned [`Line`] steps across them internally, and can be indexed as well.
     ///
-    /// [row_major]: https://en.wikipedia.org/wiki/Row-_and_column-major_order
-    /// [coords]: crate::CoordsIterator::coords()
+    /// # Panics
+    ///
+    /// If `i` is out-of-bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, U2};
+    ///
+    /// let data: Vec<_> = (0..(4 * 6)).collect();
+    /// let grid = BlockGrid::<usize, U2>::from_row_major(4, 6, &data)?;
+    ///
+    /// let row = grid.row(2);
+    /// assert_eq!(row[1], 13);
+    /// assert!(row.rev().copied().eq([17, 16, 15, 14, 13, 12]));
+    /// assert!(grid.col(5).copied().eq([5, 11, 17, 23]));
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
     #[inline]
-    pub fn row_major_iter(&self) -> RowMajorIter<'_, T, B> {
-        RowMajorIter::new(self)
+    pub fn row(&self, i: usize) -> Line<'_, T> {
+        assert!(i < self.rows, "Row out of bounds");
+        Line::row(self, i)
     }
 
-    /// Returns an mutable iterator over all the elements in [row-major order][row_major].
+    /// Returns a mutable view of the `i`-th row, which can also be iterated over.
     ///
-    /// If you also need the coordinates while iterating, follow up with a chained
-    /// [`.coords()`][coords] call.
+    /// See [`row`][Self::row] for details.
     ///
-    /// [row_major]: https://en.wikipedia.org/wiki/Row-_and_column-major_order
-    /// [coords]: crate::CoordsIterator::coords()
+    /// # Panics
+    ///
+    /// If `i` is out-of-bounds.
     #[inline]
-    pub fn row_major_iter_mut(&mut self) -> RowMajorIterMut<'_, T, B> {
-        RowMajorIterMut::new(self)
+    pub fn row_mut(&mut self, i: usize) -> LineMut<'_, T> {
+        assert!(i < self.rows, "Row out of bounds");
+        LineMut::row(self, i)
     }
 
-    /// Returns `true` if `rows` and `cols` form a valid sized `BlockGrid<T, B>`.
-    fn valid_size(rows: usize, cols: usize) -> bool {
-        rows > 0 && cols > 0 && rows % B::WIDTH == 0 && cols % B::WIDTH == 0
+    /// Returns a view of the `j`-th column, which can also be iterated over.
+    ///
+    /// See [`row`][Self::row] for details. The elements of a column are always in different
+    /// rows of memory, so this is just as quick as a row only when the blocks are tall.
+    ///
+    /// # Panics
+    ///
+    /// If `j` is out-of-bounds.
+    #[inline]
+    pub fn col(&self, j: usize) -> Line<'_, T> {
+        assert!(j < self.cols, "Column out of bounds");
+        Line::col(self, j)
     }
 
-    /// Returns the 1D memory index calculated from 2D coordinates.
-    fn calc_index(&self, (row, col): Coords) -> usize {
-        // Get block
-        let (b_row, b_col) = (row / B::WIDTH, col / B::WIDTH);
-        let block_ind = B::AREA * (self.col_blocks() * b_row + b_col);
-        // Offset within block
-        let (s_row, s_col) = (row % B::WIDTH, col % B::WIDTH);
-        let sub_ind = B::WIDTH * s_row + s_col;
-        block_ind + sub_ind
+    /// Returns a mutable view of the `j`-th column, which can also be iterated over.
+    ///
+    /// See [`col`][Self::col] for details.
+    ///
+    /// # Panics
+    ///
+    /// If `j` is out-of-bounds.
+    #[inline]
+    pub fn col_mut(&mut self, j: usize) -> LineMut<'_, T> {
+        assert!(j < self.cols, "Column out of bounds");
+        LineMut::col(self, j)
+    }
+
+    /// Returns an iterator over all the rows, yielding a view of each.
+    ///
+    /// See [`row`][Self::row] for details.
+    #[inline]
+    pub fn rows_iter(&self) -> RowsIter<'_, T, B> {
+        RowsIter::new(self)
+    }
+
+    /// Returns a mutable iterator over all the rows, yielding a mutable view of each.
+    ///
+    /// See [`row`][Self::row] for details.
+    #[inline]
+    pub fn rows_iter_mut(&mut self) -> RowsIterMut<'_, T, B> {
+        RowsIterMut::new(self)
+    }
+
+    /// Returns an iterator over all the columns, yielding a view of each.
+    ///
+    /// See [`col`][Self::col] for details.
+    #[inline]
+    pub fn cols_iter(&self) -> ColsIter<'_, T, B> {
+        ColsIter::new(self)
+    }
+
+    /// Returns a mutable iterator over all the columns, yielding a mutable view of each.
+    ///
+    /// See [`col`][Self::col] for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, U4};
+    ///
+    /// let mut grid = BlockGrid::<usize, U4>::new(5, 3)?;
+    /// for (j, col) in grid.cols_iter_mut().enumerate() {
+    ///     col.for_each(|x| *x = j);
+    /// }
+    /// assert!(grid.row(4).copied().eq([0, 1, 2]));
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    #[inline]
+    pub fn cols_iter_mut(&mut self) -> ColsIterMut<'_, T, B> {
+        ColsIterMut::new(self)
+    }
+
+    /// Transposes the grid in place, so that element `(i, j)` moves to `(j, i)`.
+    ///
+    /// This is done block-wise. Whole blocks are swapped into their transposed positions, and
+    /// then each is transposed in place, so no element is moved more than twice. The grid can be
+    /// any shape, but the blocks have to be square. See [`transposed`][Self::transposed] for a
+    /// version that works with any block shape.
+    ///
+    /// # Panics
+    ///
+    /// If the blocks aren't square.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, U2};
+    ///
+    /// let data: Vec<_> = (0..(2 * 3)).collect();
+    /// let mut grid = BlockGrid::<usize, U2>::from_row_major(2, 3, &data)?;
+    ///
+    /// grid.transpose();
+    /// assert_eq!((grid.rows(), grid.cols()), (3, 2));
+    /// assert_eq!(grid.to_row_major_vec(), [0, 3, 1, 4, 2, 5]);
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn transpose(&mut self) {
+        assert!(
+            B::HEIGHT == B::WIDTH,
+            "Can't transpose non-square blocks in place"
+        );
+        let (row_blocks, col_blocks) = self.block_dims();
+        // Follow each cycle of the block permutation, swapping the next block into the first
+        let dest = |i: usize| row_blocks * (i % col_blocks) + i / col_blocks;
+        let mut done = vec![false; row_blocks * col_blocks];
+        for start in 0..done.len() {
+            let mut i = start;
+            while !done[start] {
+                let next = dest(i);
+                done[next] = true;
+                if next != start {
+                    swap_ranges(&mut self.buf, B::AREA * start, B::AREA * next, B::AREA);
+                }
+                i = next;
+            }
+        }
+        for block in self.buf.chunks_exact_mut(B::AREA) {
+            for si in 0..B::WIDTH {
+                for sj in (si + 1)..B::WIDTH {
+                    block.swap(B::WIDTH * si + sj, B::WIDTH * sj + si);
+                }
+            }
+        }
+        core::mem::swap(&mut self.rows, &mut self.cols);
+        self.col_blocks = row_blocks;
+    }
+
+    /// Rotates the grid in place by 90 degrees clockwise.
+    ///
+    /// This is a [`transpose`][Self::transpose] followed by a
+    /// [`flip_horizontal`][Self::flip_horizontal].
+    ///
+    /// # Panics
+    ///
+    /// If the blocks aren't square.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, U2};
+    ///
+    /// let data: Vec<_> = (0..(2 * 3)).collect();
+    /// let mut grid = BlockGrid::<usize, U2>::from_row_major(2, 3, &data)?;
+    ///
+    /// grid.rotate90();
+    /// assert_eq!((grid.rows(), grid.cols()), (3, 2));
+    /// assert_eq!(grid.to_row_major_vec(), [3, 0, 4, 1, 5, 2]);
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn rotate90(&mut self) {
+        self.transpose();
+        self.flip_horizontal();
+    }
+
+    /// Rotates the grid in place by 180 degrees.
+    ///
+    /// This works for any shape of grid and block, since the dimensions stay the same.
+    pub fn rotate180(&mut self) {
+        self.flip_vertical();
+        self.flip_horizontal();
+    }
+
+    /// Rotates the grid in place by 270 degrees clockwise, or 90 degrees counter-clockwise.
+    ///
+    /// This is a [`transpose`][Self::transpose] followed by a
+    /// [`flip_vertical`][Self::flip_vertical].
+    ///
+    /// # Panics
+    ///
+    /// If the blocks aren't square.
+    pub fn rotate270(&mut self) {
+        self.transpose();
+        self.flip_vertical();
+    }
+
+    /// Flips the grid in place from left to right, so each row is reversed.
+    ///
+    /// If the number of columns is a multiple of the block width, whole blocks are swapped
+    /// across the middle, and then the rows within each are reversed. Otherwise, the elements
+    /// don't line up with the blocks and are swapped one at a time.
+    pub fn flip_horizontal(&mut self) {
+        let (rows, cols, col_blocks) = (self.rows, self.cols, self.col_blocks);
+        if cols & B::COL_MASK == 0 {
+            for b_row in 0..self.row_blocks() {
+                let base = col_blocks * b_row;
+                for b_col in 0..(col_blocks / 2) {
+                    let (a, b) = (base + b_col, base + col_blocks - 1 - b_col);
+                    swap_ranges(&mut self.buf, B::AREA * a, B::AREA * b, B::AREA);
+                }
+            }
+            // Padding rows are reversed too, which is harmless
+            self.buf
+                .chunks_exact_mut(B::WIDTH)
+                .for_each(|line| line.reverse());
+        } else {
+            for row in 0..rows {
+                for col in 0..(cols / 2) {
+                    let a = self.calc_index((row, col));
+                    let b = self.calc_index((row, cols - 1 - col));
+                    self.buf.swap(a, b);
+                }
+            }
+        }
+    }
+
+    /// Flips the grid in place from top to bottom, so each column is reversed.
+    ///
+    /// Each row is contiguous within a block, so rows are swapped a block width at a time.
+    pub fn flip_vertical(&mut self) {
+        let (rows, col_blocks) = (self.rows, self.col_blocks);
+        for row in 0..(rows / 2) {
+            for b_col in 0..col_blocks {
+                let col = B::WIDTH * b_col;
+                let a = self.calc_index((row, col));
+                let b = self.calc_index((rows - 1 - row, col));
+                swap_ranges(&mut self.buf, a, b, B::WIDTH);
+            }
+        }
+    }
+
+    /// Converts to a plain row-major [`Grid<T>`], moving every element without cloning.
+    ///
+    /// A [`Grid<T>`] never has padding, so any padding elements are dropped. See
+    /// [`reblock`][Self::reblock] for converting to other block sizes.
+    pub fn into_grid(self) -> Grid<T> {
+        // A block size of 1 never needs padding, so `fill` is never called
+        self.reblock_with::<U1>(|| unreachable!())
+    }
+
+    /// Converts to a grid with a different block size, `B2`, moving every element without
+    /// cloning.
+    ///
+    /// Elements are moved a contiguous run at a time, which is much quicker than going through
+    /// [`row_major_iter`][Self::row_major_iter] and [`from_row_major`][Self::from_row_major].
+    /// If the block size is the same, the memory is reused as is. Any padding of the old grid is
+    /// dropped, and each padding element of the new one is the result of calling `fill`. See
+    /// [`reblock`][Self::reblock] to fill with the default value of `T` instead, and
+    /// [`reblock_cloned`][Self::reblock_cloned] for a borrowing version.
+    ///
+    /// # Panics
+    ///
+    /// If the number of elements including the new padding overflows a `usize`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, Grid, U4};
+    /// use std::fmt::Debug;
+    ///
+    /// // Neither `Clone` nor `Default`
+    /// let data: Vec<Box<dyn Debug>> = (0..(5 * 7)).map(|x| Box::new(x) as _).collect();
+    /// let grid = Grid::from_raw_vec(5, 7, data)?;
+    ///
+    /// let grid: BlockGrid<_, U4> = grid.reblock_with(|| Box::new("padding"));
+    /// assert_eq!(format!("{:?}", grid[(3, 2)]), "23");
+    /// // Past the last column of the first row
+    /// assert_eq!(format!("{:?}", grid.raw()[19]), "\"padding\"");
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn reblock_with<B2: BlockShape>(mut self, mut fill: impl FnMut() -> T) -> BlockGrid<T, B2> {
+        let (rows, cols) = (self.rows, self.cols);
+        if (B::HEIGHT, B::WIDTH) == (B2::HEIGHT, B2::WIDTH) {
+            // Same memory layout, so just reuse the allocation
+            return BlockGrid::from_raw_vec(rows, cols, self.take_raw_vec())
+                .expect("Same block dimensions are always valid");
+        }
+        let size = BlockGrid::<T, B2>::check_size(rows, cols)
+            .expect("Number of reblocked elements overflows a `usize`");
+        let mut out = BlockGrid::<T, B2> {
+            rows,
+            cols,
+            col_blocks: calc_blocks(cols, B2::WIDTH),
+            buf: Vec::with_capacity(size),
+            _phantom: PhantomData,
+        };
+        // Elements are moved out by reading, so `self.buf` must not drop them again. If `fill`
+        // panics, elements not yet moved are leaked, which is safe.
+        let len = self.buf.len();
+        // SAFETY: All elements are either moved out or dropped below
+        unsafe { self.buf.set_len(0) };
+        let src = self.buf.as_mut_ptr();
+        self.for_each_reblock_run::<B2>(|run| match run {
+            ReblockRun::Valid(run) => {
+                let n = run.len();
+                // SAFETY: `run` is in bounds of the source allocation and each element is only
+                //         moved once, while `out.buf` has `size` capacity in total
+                unsafe {
+                    let dst = out.buf.as_mut_ptr().add(out.buf.len());
+                    ptr::copy_nonoverlapping(src.add(run.start), dst, n);
+                    out.buf.set_len(out.buf.len() + n);
+                }
+            }
+            ReblockRun::Padding(_) => out.buf.push(fill()),
+        });
+        // Drop any source padding, which wasn't moved
+        if len != self.size() {
+            for (b_ind, (b_row, b_col)) in
+                (0..self.blocks()).map(|i| (i, (i / self.col_blocks, i % self.col_blocks)))
+            {
+                let (h, w) = block_extent::<B>(rows, cols, (), (b_row, b_col));
+                for si in 0..B::HEIGHT {
+                    for sj in 0..B::WIDTH {
+                        if si >= h || sj >= w {
+                            // SAFETY: Padding elements are in bounds and were never moved
+                            unsafe {
+                                ptr::drop_in_place(src.add(B::AREA * b_ind + B::WIDTH * si + sj))
+                            };
+                        }
+                    }
+                }
+            }
+        }
+        debug_assert_eq!(out.buf.len(), size);
+        out
+    }
+
+    /// Walks the memory order of a grid of the same dimensions, but block size `B2`.
+    ///
+    /// Valid elements are given in runs that are also contiguous in `self`, as ranges of indices
+    /// into `self.buf`. Each padding element is given one at a time.
+    fn for_each_reblock_run<B2: BlockShape>(&self, mut f: impl FnMut(ReblockRun)) {
+        let (rows, cols) = (self.rows, self.cols);
+        for bi in (0..rows).step_by(B2::HEIGHT) {
+            for bj in (0..cols).step_by(B2::WIDTH) {
+                for row in bi..(bi + B2::HEIGHT) {
+                    let end = bj + B2::WIDTH;
+                    let mut col = bj;
+                    while col < end {
+                        if row >= rows || col >= cols {
+                            f(ReblockRun::Padding((row.min(rows - 1), col.min(cols - 1))));
+                            col += 1;
+                            continue;
+                        }
+                        // Elements are contiguous up to the edge of the source block
+                        let n = (B::WIDTH - col % B::WIDTH).min(end - col).min(cols - col);
+                        let start = self.calc_index((row, col));
+                        f(ReblockRun::Valid(start..(start + n)));
+                        col += n;
+                    }
+                }
+            }
+        }
     }
 }
 
-impl<T: Clone, B: BlockDim> BlockGrid<T, B> {
+impl<T: Clone, B: BlockShape, L: Layout> BlockGrid<T, B, L> {
     /// Constructs a `BlockGrid<T, B>` by filling with a single element.
     ///
     /// # Errors
     ///
-    /// If  `rows` and `cols` do not divide evenly into the block size `B`.
-    pub fn filled(rows: usize, cols: usize, elem: T) -> Result<Self, ()> {
-        if !Self::valid_size(rows, cols) {
-            return Err(());
-        }
+    /// If either `rows` or `cols` is zero. See [`Error`] for details.
+    pub fn filled(rows: usize, cols: usize, elem: T) -> Result<Self, Error> {
+        let size = Self::check_size(rows, cols)?;
         Ok(Self {
             rows,
             cols,
-            col_blocks: cols / B::WIDTH,
-            buf: vec![elem; rows * cols],
+            col_blocks: calc_blocks(cols, B::WIDTH),
+            buf: vec![elem; size],
             _phantom: PhantomData,
         })
     }
@@ -292,13 +913,15 @@ impl<T: Clone, B: BlockDim> BlockGrid<T, B> {
     ///
     /// This method may be useful for converting from a typical 2D array.
     ///
+    /// Any padding is filled with clones of the nearest valid element.
+    ///
     /// # Errors
     ///
-    /// If invalid dimensions, either because `rows` and `cols` do not divide evenly into the block
-    /// size `B` or the length of `elems` does not match `rows * cols`.
+    /// If invalid dimensions, either because `rows` or `cols` is zero or the length of `elems`
+    /// does not match `rows * cols`. See [`Error`] for details.
     ///
     /// [row_major]: https://en.wikipedia.org/wiki/Row-_and_column-major_order
-    pub fn from_row_major(rows: usize, cols: usize, elems: &[T]) -> Result<Self, ()> {
+    pub fn from_row_major(rows: usize, cols: usize, elems: &[T]) -> Result<Self, Error> {
         Self::from_array_index_helper(rows, cols, elems, |row, col| cols * row + col)
     }
 
@@ -306,64 +929,475 @@ impl<T: Clone, B: BlockDim> BlockGrid<T, B> {
     ///
     /// 2D arrays are not usually stored like this, but occasionally they are.
     ///
+    /// Any padding is filled with clones of the nearest valid element.
+    ///
     /// # Errors
     ///
-    /// If invalid dimensions, either because `rows` and `cols` do not divide evenly into the block
-    /// size `B` or the length of `elems` does not match `rows * cols`.
+    /// If invalid dimensions, either because `rows` or `cols` is zero or the length of `elems`
+    /// does not match `rows * cols`. See [`Error`] for details.
     ///
     /// [col_major]: https://en.wikipedia.org/wiki/Row-_and_column-major_order
-    pub fn from_col_major(rows: usize, cols: usize, elems: &[T]) -> Result<Self, ()> {
+    pub fn from_col_major(rows: usize, cols: usize, elems: &[T]) -> Result<Self, Error> {
         Self::from_array_index_helper(rows, cols, elems, |row, col| rows * col + row)
     }
 
+    /// Returns a copy of all the elements in [row-major order][row_major].
+    ///
+    /// This is the inverse of [`from_row_major`][Self::from_row_major], and may be useful for
+    /// converting to a typical 2D array.
+    ///
+    /// [row_major]: https://en.wikipedia.org/wiki/Row-_and_column-major_order
+    pub fn to_row_major_vec(&self) -> Vec<T> {
+        self.row_major_iter().cloned().collect()
+    }
+
+    /// Returns a copy of all the elements in [column-major order][col_major].
+    ///
+    /// This is the inverse of [`from_col_major`][Self::from_col_major].
+    ///
+    /// [col_major]: https://en.wikipedia.org/wiki/Row-_and_column-major_order
+    pub fn to_col_major_vec(&self) -> Vec<T> {
+        self.col_major_iter().cloned().collect()
+    }
+
     /// Helper method to convert from a differently ordered array to a `BlockGrid<T, B>`.
     fn from_array_index_helper(
         rows: usize,
         cols: usize,
         elems: &[T],
         calc_index: impl Fn(usize, usize) -> usize,
-    ) -> Result<Self, ()> {
-        if !Self::valid_size(rows, cols) || rows * cols != elems.len() {
-            return Err(());
-        }
+    ) -> Result<Self, Error> {
+        let size = Self::check_size(rows, cols)?;
+        Self::check_len(rows * cols, elems.len())?;
         let mut grid = Self {
             rows,
             cols,
-            col_blocks: cols / B::WIDTH,
-            buf: Vec::with_capacity(rows * cols),
+            col_blocks: calc_blocks(cols, B::WIDTH),
+            buf: Vec::with_capacity(size),
             _phantom: PhantomData,
         };
-        // Iterate in memory order by index and pull values from `elems`
-        for bi in (0..grid.rows()).step_by(B::WIDTH) {
-            for bj in (0..grid.cols()).step_by(B::WIDTH) {
-                for si in 0..B::WIDTH {
+        // Write each element of the padded grid to its index in memory, pulling values from
+        // `elems`. If `clone` panics, the elements written so far are leaked, which is safe.
+        let (pad_rows, pad_cols) = B::round_up_to_valid(rows, cols);
+        let blocks = grid.block_dims();
+        let dst = grid.buf.as_mut_ptr();
+        for row in 0..pad_rows {
+            for col in 0..pad_cols {
+                // Clamp to the nearest valid element for padding
+                let ind = calc_index(row.min(rows - 1), col.min(cols - 1));
+                // There's no 'simple' way to do this without `Clone`,
+                // because `elems` can't be easily drained out of order.
+                let elem = elems[ind].clone();
+                // SAFETY: The layout maps each padded coordinate to a distinct index below `size`
+                unsafe { ptr::write(dst.add(L::offset::<B>(blocks, (row, col))), elem) };
+            }
+        }
+        // SAFETY: Every index below `size` was written to exactly once
+        unsafe { grid.buf.set_len(size) };
+        Ok(grid)
+    }
+}
+
+impl<T: Clone, B: BlockShape> BlockGrid<T, B> {
+    /// Constructs a copy of the grid with a different block size, `B2`.
+    ///
+    /// Elements are copied a contiguous run at a time, which is much quicker than going through
+    /// [`row_major_iter`][Self::row_major_iter] and [`from_row_major`][Self::from_row_major].
+    /// Any new padding is filled with clones of the nearest valid element. See
+    /// [`reblock`][Self::reblock] for a version that consumes the grid instead.
+    ///
+    /// # Panics
+    ///
+    /// If the number of elements including the new padding overflows a `usize`.
+    pub fn reblock_cloned<B2: BlockShape>(&self) -> BlockGrid<T, B2> {
+        let size = BlockGrid::<T, B2>::check_size(self.rows, self.cols)
+            .expect("Number of reblocked elements overflows a `usize`");
+        let mut buf = Vec::with_capacity(size);
+        self.for_each_reblock_run::<B2>(|run| match run {
+            ReblockRun::Valid(run) => buf.extend_from_slice(&self.buf[run]),
+            // SAFETY: Padding coordinates are clamped to a valid element
+            ReblockRun::Padding(coords) => buf.push(unsafe { self.get_unchecked(coords) }.clone()),
+        });
+        BlockGrid::from_raw_vec(self.rows, self.cols, buf).expect("Reblocked size is always valid")
+    }
+
+    /// Resizes the grid to `rows` by `cols`, filling any new elements with clones of `fill`.
+    ///
+    /// Elements keep their coordinates, and any outside the new dimensions are dropped. Each row
+    /// of blocks is contiguous in memory, so if the number of block columns stays the same, this
+    /// just truncates or extends the memory. Otherwise, every kept block is moved once.
+    ///
+    /// # Errors
+    ///
+    /// If either `rows` or `cols` is zero, or the number of elements including padding overflows
+    /// a `usize`, in which case the grid is left as is. See [`Error`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, U2};
+    ///
+    /// let data: Vec<_> = (0..(2 * 3)).collect();
+    /// let mut grid = BlockGrid::<usize, U2>::from_row_major(2, 3, &data)?;
+    ///
+    /// grid.resize(3, 2, 9)?;
+    /// assert_eq!(grid.to_row_major_vec(), [0, 1, 3, 4, 9, 9]);
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn resize(&mut self, rows: usize, cols: usize, fill: T) -> Result<(), Error> {
+        let size = Self::check_size(rows, cols)?;
+        let (old_rows, old_cols) = (self.rows, self.cols);
+        let (old_row_blocks, old_col_blocks) = self.block_dims();
+        let col_blocks = calc_blocks(cols, B::WIDTH);
+        if col_blocks == old_col_blocks {
+            self.buf.truncate(size);
+        } else {
+            let old = core::mem::replace(&mut self.buf, Vec::with_capacity(size));
+            let mut old = old.into_iter();
+            let keep = B::AREA * col_blocks.min(old_col_blocks);
+            for _ in 0..old_row_blocks.min(calc_blocks(rows, B::HEIGHT)) {
+                self.buf.extend(old.by_ref().take(keep));
+                if col_blocks < old_col_blocks {
+                    // Drop the blocks past the new right edge
+                    let n = B::AREA * (old_col_blocks - col_blocks);
+                    old.by_ref().take(n).for_each(drop);
+                } else {
+                    let n = self.buf.len() + B::AREA * (col_blocks - old_col_blocks);
+                    self.buf.resize(n, fill.clone());
+                }
+            }
+        }
+        self.buf.resize(size, fill.clone());
+        self.rows = rows;
+        self.cols = cols;
+        self.col_blocks = col_blocks;
+        // Old padding that's now valid has to be filled as well
+        let pad_rows = (B::HEIGHT * old_row_blocks).min(rows);
+        let pad_cols = (B::WIDTH * old_col_blocks).min(cols);
+        for row in old_rows..pad_rows {
+            for col in 0..pad_cols {
+                self[(row, col)] = fill.clone();
+            }
+        }
+        for row in 0..old_rows.min(rows) {
+            for col in old_cols..pad_cols {
+                self[(row, col)] = fill.clone();
+            }
+        }
+        Ok(())
+    }
+
+    /// Appends a row of blocks to the bottom, filled with clones of `fill`.
+    ///
+    /// This adds `B::HEIGHT` rows, and is just an extension of the memory. See
+    /// [`insert_block_row`][Self::insert_block_row] for details.
+    ///
+    /// # Panics
+    ///
+    /// If the number of elements including padding overflows a `usize`.
+    #[inline]
+    pub fn push_block_row(&mut self, fill: T) {
+        self.insert_block_row(self.row_blocks(), fill);
+    }
+
+    /// Appends a column of blocks to the right, filled with clones of `fill`.
+    ///
+    /// This adds `B::WIDTH` columns. See [`insert_block_col`][Self::insert_block_col] for
+    /// details.
+    ///
+    /// # Panics
+    ///
+    /// If the number of elements including padding overflows a `usize`.
+    #[inline]
+    pub fn push_block_col(&mut self, fill: T) {
+        self.insert_block_col(self.col_blocks(), fill);
+    }
+
+    /// Inserts a row of blocks before the `at`-th one, filled with clones of `fill`.
+    ///
+    /// This adds `B::HEIGHT` rows, shifting any after row `at * B::HEIGHT` down. Each row of
+    /// blocks is contiguous in memory, so this only moves the memory after it.
+    ///
+    /// # Panics
+    ///
+    /// If `at` is greater than the number of block rows, or the number of elements including
+    /// padding overflows a `usize`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, U2};
+    ///
+    /// let data: Vec<_> = (1..=(3 * 2)).collect();
+    /// let mut grid = BlockGrid::<usize, U2>::from_row_major(3, 2, &data)?;
+    ///
+    /// grid.insert_block_row(1, 0);
+    /// assert_eq!(grid.rows(), 5);
+    /// assert_eq!(grid.to_row_major_vec(), [1, 2, 3, 4, 0, 0, 0, 0, 5, 6]);
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn insert_block_row(&mut self, at: usize, fill: T) {
+        let row_blocks = self.row_blocks();
+        assert!(at <= row_blocks, "Block row out of bounds");
+        let rows = self.rows.checked_add(B::HEIGHT).expect(OVERFLOW);
+        if at == row_blocks {
+            // The new rows may start within the padding of the last block row
+            return self.resize(rows, self.cols, fill).expect(OVERFLOW);
+        }
+        Self::check_size(rows, self.cols).expect(OVERFLOW);
+        let n = B::AREA * self.col_blocks;
+        self.buf
+            .splice((n * at)..(n * at), core::iter::repeat(fill).take(n));
+        self.rows = rows;
+    }
+
+    /// Inserts a column of blocks before the `at`-th one, filled with clones of `fill`.
+    ///
+    /// This adds `B::WIDTH` columns, shifting any after column `at * B::WIDTH` right. The
+    /// blocks of each row of blocks are moved once.
+    ///
+    /// # Panics
+    ///
+    /// If `at` is greater than the number of block columns, or the number of elements including
+    /// padding overflows a `usize`.
+    pub fn insert_block_col(&mut self, at: usize, fill: T) {
+        let col_blocks = self.col_blocks;
+        assert!(at <= col_blocks, "Block column out of bounds");
+        let cols = self.cols.checked_add(B::WIDTH).expect(OVERFLOW);
+        if at == col_blocks {
+            // The new columns may start within the padding of the last block column
+            return self.resize(self.rows, cols, fill).expect(OVERFLOW);
+        }
+        let size = Self::check_size(self.rows, cols).expect(OVERFLOW);
+        let old = core::mem::replace(&mut self.buf, Vec::with_capacity(size));
+        let mut old = old.into_iter();
+        for _ in 0..self.row_blocks() {
+            self.buf.extend(old.by_ref().take(B::AREA * at));
+            self.buf.resize(self.buf.len() + B::AREA, fill.clone());
+            self.buf
+                .extend(old.by_ref().take(B::AREA * (col_blocks - at)));
+        }
+        self.cols = cols;
+        self.col_blocks += 1;
+    }
+
+    /// Removes the `at`-th row of blocks.
+    ///
+    /// This removes `B::HEIGHT` rows, shifting any after them up, or just the rows left if it's
+    /// the last row of blocks. Each row of blocks is contiguous in memory, so this only moves the
+    /// memory after it.
+    ///
+    /// # Panics
+    ///
+    /// If `at` is out-of-bounds, or it's the only row of blocks.
+    pub fn remove_block_row(&mut self, at: usize) {
+        let row_blocks = self.row_blocks();
+        assert!(at < row_blocks, "Block row out of bounds");
+        assert!(row_blocks > 1, "Can't remove the only block row");
+        let n = B::AREA * self.col_blocks;
+        self.buf.drain((n * at)..(n * (at + 1)));
+        self.rows = if at + 1 == row_blocks {
+            B::HEIGHT * at
+        } else {
+            self.rows - B::HEIGHT
+        };
+    }
+
+    /// Removes the `at`-th column of blocks.
+    ///
+    /// This removes `B::WIDTH` columns, shifting any after them left, or just the columns left
+    /// if it's the last column of blocks. The memory is compacted in place.
+    ///
+    /// # Panics
+    ///
+    /// If `at` is out-of-bounds, or it's the only column of blocks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, U2};
+    ///
+    /// let data: Vec<_> = (0..(2 * 5)).collect();
+    /// let mut grid = BlockGrid::<usize, U2>::from_row_major(2, 5, &data)?;
+    ///
+    /// grid.remove_block_col(1);
+    /// assert_eq!(grid.cols(), 3);
+    /// assert_eq!(grid.to_row_major_vec(), [0, 1, 4, 5, 6, 9]);
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn remove_block_col(&mut self, at: usize) {
+        let col_blocks = self.col_blocks;
+        assert!(at < col_blocks, "Block column out of bounds");
+        assert!(col_blocks > 1, "Can't remove the only block column");
+        let mut i = 0;
+        self.buf.retain(|_| {
+            let keep = (i / B::AREA) % col_blocks != at;
+            i += 1;
+            keep
+        });
+        self.cols = if at + 1 == col_blocks {
+            B::WIDTH * at
+        } else {
+            self.cols - B::WIDTH
+        };
+        self.col_blocks -= 1;
+    }
+
+    /// Returns a transposed copy of the grid, so that element `(i, j)` is at `(j, i)`.
+    ///
+    /// The copy is built in memory order, so each of its blocks is read from a single block of
+    /// the source if the blocks are square. Unlike [`transpose`][Self::transpose], this works
+    /// for any block shape.
+    ///
+    /// # Panics
+    ///
+    /// If the number of elements including the new padding overflows a `usize`.
+    pub fn transposed(&self) -> Self {
+        self.remap((self.cols, self.rows), |(row, col)| (col, row))
+    }
+
+    /// Returns a copy of the grid rotated by 90 degrees clockwise.
+    ///
+    /// See [`transposed`][Self::transposed] for details.
+    ///
+    /// # Panics
+    ///
+    /// If the number of elements including the new padding overflows a `usize`.
+    pub fn rotated90(&self) -> Self {
+        let rows = self.rows;
+        self.remap((self.cols, rows), |(row, col)| (rows - 1 - col, row))
+    }
+
+    /// Returns a copy of the grid rotated by 180 degrees.
+    pub fn rotated180(&self) -> Self {
+        let (rows, cols) = (self.rows, self.cols);
+        self.remap((rows, cols), |(row, col)| (rows - 1 - row, cols - 1 - col))
+    }
+
+    /// Returns a copy of the grid rotated by 270 degrees clockwise, or 90 degrees
+    /// counter-clockwise.
+    ///
+    /// See [`transposed`][Self::transposed] for details.
+    ///
+    /// # Panics
+    ///
+    /// If the number of elements including the new padding overflows a `usize`.
+    pub fn rotated270(&self) -> Self {
+        let cols = self.cols;
+        self.remap((cols, self.rows), |(row, col)| (col, cols - 1 - row))
+    }
+
+    /// Returns a copy of the grid flipped from left to right.
+    pub fn flipped_horizontal(&self) -> Self {
+        let cols = self.cols;
+        self.remap((self.rows, cols), |(row, col)| (row, cols - 1 - col))
+    }
+
+    /// Returns a copy of the grid flipped from top to bottom.
+    pub fn flipped_vertical(&self) -> Self {
+        let rows = self.rows;
+        self.remap((rows, self.cols), |(row, col)| (rows - 1 - row, col))
+    }
+
+    /// Constructs a grid of `(rows, cols)` in memory order, cloning each element from the
+    /// coordinates given by `src`. Padding is cloned from the nearest valid element.
+    fn remap(&self, (rows, cols): Coords, src: impl Fn(Coords) -> Coords) -> Self {
+        let size = Self::check_size(rows, cols).expect(OVERFLOW);
+        let mut buf = Vec::with_capacity(size);
+        for b_row in 0..calc_blocks(rows, B::HEIGHT) {
+            for b_col in 0..calc_blocks(cols, B::WIDTH) {
+                for si in 0..B::HEIGHT {
                     for sj in 0..B::WIDTH {
-                        let (row, col) = (bi + si, bj + sj);
-                        let ind = calc_index(row, col);
-                        // There's no 'simple' way to do this without `Clone`,
-                        // because `elems` can't be easily drained out of order.
-                        grid.buf.push(elems[ind].clone());
+                        let row = (B::HEIGHT * b_row + si).min(rows - 1);
+                        let col = (B::WIDTH * b_col + sj).min(cols - 1);
+                        buf.push(self.buf[self.calc_index(src((row, col)))].clone());
                     }
                 }
             }
         }
-        debug_assert_eq!(grid.buf.len(), grid.size());
-        Ok(grid)
+        Self::from_raw_vec(rows, cols, buf).expect("Remapped size is always valid")
     }
 }
 
-impl<T: Clone + Default, B: BlockDim> BlockGrid<T, B> {
+impl<T: Clone + Default, B: BlockShape, L: Layout> BlockGrid<T, B, L> {
     /// Constructs a `BlockGrid<T, B>` by filling with the default value of `T`.
     ///
     /// # Errors
     ///
-    /// If  `rows` and `cols` do not divide evenly into the block size `B`.
-    pub fn new(rows: usize, cols: usize) -> Result<Self, ()> {
+    /// If either `rows` or `cols` is zero. See [`Error`] for details.
+    pub fn new(rows: usize, cols: usize) -> Result<Self, Error> {
         Self::filled(rows, cols, T::default())
     }
 }
 
-impl<T, B: BlockDim> Index<Coords> for BlockGrid<T, B> {
+impl<T: Default, B: BlockShape> BlockGrid<T, B> {
+    /// Converts to a grid with a different block size, `B2`, moving every element without
+    /// cloning, and filling any new padding with the default value of `T`.
+    ///
+    /// This is a shorthand for [`reblock_with`][Self::reblock_with]`(T::default)`, which also
+    /// works for types without a default value.
+    ///
+    /// # Panics
+    ///
+    /// If the number of elements including the new padding overflows a `usize`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, Grid, U2, U4};
+    ///
+    /// let data: Vec<_> = (0..(5 * 7)).collect();
+    /// let grid = BlockGrid::<usize, U2>::from_row_major(5, 7, &data)?;
+    /// let grid = grid.reblock::<U4>();
+    /// assert_eq!(grid[(3, 2)], 23);
+    ///
+    /// let grid: Grid<_> = grid.into_grid();
+    /// assert_eq!(grid.raw(), &data[..]);
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn reblock<B2: BlockShape>(self) -> BlockGrid<T, B2> {
+        self.reblock_with(T::default)
+    }
+
+    /// Constructs a `BlockGrid<T, B>` from a plain row-major [`Grid<T>`], moving every element
+    /// without cloning.
+    ///
+    /// This is the same as calling [`reblock`][Self::reblock] on `grid`.
+    ///
+    /// # Panics
+    ///
+    /// If the number of elements including the new padding overflows a `usize`.
+    pub fn from_grid(grid: Grid<T>) -> Self {
+        grid.reblock()
+    }
+}
+
+impl<T: PartialEq, B: BlockShape, L: Layout> PartialEq for BlockGrid<T, B, L> {
+    fn eq(&self, other: &Self) -> bool {
+        if (self.rows, self.cols) != (other.rows, other.cols) {
+            return false;
+        }
+        if !self.is_padded() {
+            return self.buf == other.buf;
+        }
+        // Padding is ignored, so compare only valid elements
+        self.row_major_iter().eq(other.row_major_iter())
+    }
+}
+
+impl<T: Eq, B: BlockShape, L: Layout> Eq for BlockGrid<T, B, L> {}
+
+impl<T: Hash, B: BlockShape, L: Layout> Hash for BlockGrid<T, B, L> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.rows.hash(state);
+        self.cols.hash(state);
+        for x in self.row_major_iter() {
+            x.hash(state);
+        }
+    }
+}
+
+impl<T, B: BlockShape, L: Layout> Index<Coords> for BlockGrid<T, B, L> {
     type Output = T;
 
     #[inline]
@@ -372,23 +1406,32 @@ impl<T, B: BlockDim> Index<Coords> for BlockGrid<T, B> {
     }
 }
 
-impl<T, B: BlockDim> IndexMut<Coords> for BlockGrid<T, B> {
+impl<T, B: BlockShape, L: Layout> IndexMut<Coords> for BlockGrid<T, B, L> {
     #[inline]
     fn index_mut(&mut self, coords: Coords) -> &mut Self::Output {
         self.get_mut(coords).expect("Index out of bounds")
     }
 }
 
-impl<'a, T, B: BlockDim> Block<'a, T, B> {
+impl<'a, T, B: Dims> Block<'a, T, B> {
     /// Constructs a `Block<'a, T, B>` from an array slice.
     ///
     /// # Safety
     ///
-    /// `block_coords` *must* be valid and `arr` *must* be of length `B::AREA`.
-    pub(crate) unsafe fn new(block_coords: Coords, arr: &'a [T]) -> Self {
-        debug_assert_eq!(arr.len(), B::AREA);
+    /// `block_coords` *must* be valid, `extent` *must* be within the block size, and `arr`
+    /// *must* be the length of a block.
+    pub(crate) unsafe fn new(
+        block_coords: Coords,
+        extent: Coords,
+        dims: B::Value,
+        arr: &'a [T],
+    ) -> Self {
+        debug_assert_eq!(arr.len(), B::area(dims));
+        debug_assert!(extent.0 <= B::height(dims) && extent.1 <= B::width(dims));
         Self {
             block_coords,
+            extent,
+            dims,
             arr,
             _phantom: PhantomData,
         }
@@ -410,13 +1453,32 @@ impl<'a, T, B: BlockDim> Block<'a, T, B> {
     #[inline]
     pub fn starts_at(&self) -> Coords {
         let (b_row, b_col) = self.block_coords;
-        (B::WIDTH * b_row, B::WIDTH * b_col)
+        (
+            b_row << B::row_shift(self.dims),
+            b_col << B::col_shift(self.dims),
+        )
+    }
+
+    /// Returns the number of valid rows in the block.
+    ///
+    /// This is the block height, unless the block lies on the bottom edge of a padded grid.
+    #[inline]
+    pub fn rows(&self) -> usize {
+        self.extent.0
+    }
+
+    /// Returns the number of valid columns in the block.
+    ///
+    /// This is the block width, unless the block lies on the right edge of a padded grid.
+    #[inline]
+    pub fn cols(&self) -> usize {
+        self.extent.1
     }
 
     /// Returns `true` if the given coordinates are valid.
     #[inline]
     pub fn contains(&self, (row, col): Coords) -> bool {
-        row < B::WIDTH && col < B::WIDTH
+        row < self.extent.0 && col < self.extent.1
     }
 
     /// Returns a reference to the element at the given coordinates, or [`None`] if they are
@@ -442,6 +1504,8 @@ impl<'a, T, B: BlockDim> Block<'a, T, B> {
     }
 
     /// Returns all elements in block as a slice in memory order.
+    ///
+    /// This includes any padding elements.
     #[inline]
     pub fn raw(&self) -> &[T] {
         self.arr
@@ -449,11 +1513,11 @@ impl<'a, T, B: BlockDim> Block<'a, T, B> {
 
     /// Returns the 1D memory index calculated from 2D coordinates.
     fn calc_index(&self, (row, col): Coords) -> usize {
-        B::WIDTH * row + col
+        (row << B::col_shift(self.dims)) + col
     }
 }
 
-impl<'a, T, B: BlockDim> Index<Coords> for Block<'a, T, B> {
+impl<'a, T, B: Dims> Index<Coords> for Block<'a, T, B> {
     type Output = T;
 
     #[inline]
@@ -462,16 +1526,25 @@ impl<'a, T, B: BlockDim> Index<Coords> for Block<'a, T, B> {
     }
 }
 
-impl<'a, T, B: BlockDim> BlockMut<'a, T, B> {
+impl<'a, T, B: Dims> BlockMut<'a, T, B> {
     /// Constructs a `BlockMut<'a, T, B>` from an array slice.
     ///
     /// # Safety
     ///
-    /// `block_coords` *must* be valid and `arr` *must* be of length `B::AREA`.
-    pub(crate) unsafe fn new(block_coords: Coords, arr: &'a mut [T]) -> Self {
-        debug_assert_eq!(arr.len(), B::AREA);
+    /// `block_coords` *must* be valid, `extent` *must* be within the block size, and `arr`
+    /// *must* be the length of a block.
+    pub(crate) unsafe fn new(
+        block_coords: Coords,
+        extent: Coords,
+        dims: B::Value,
+        arr: &'a mut [T],
+    ) -> Self {
+        debug_assert_eq!(arr.len(), B::area(dims));
+        debug_assert!(extent.0 <= B::height(dims) && extent.1 <= B::width(dims));
         Self {
             block_coords,
+            extent,
+            dims,
             arr,
             _phantom: PhantomData,
         }
@@ -493,13 +1566,32 @@ impl<'a, T, B: BlockDim> BlockMut<'a, T, B> {
     #[inline]
     pub fn starts_at(&self) -> Coords {
         let (b_row, b_col) = self.block_coords;
-        (B::WIDTH * b_row, B::WIDTH * b_col)
+        (
+            b_row << B::row_shift(self.dims),
+            b_col << B::col_shift(self.dims),
+        )
+    }
+
+    /// Returns the number of valid rows in the block.
+    ///
+    /// This is the block height, unless the block lies on the bottom edge of a padded grid.
+    #[inline]
+    pub fn rows(&self) -> usize {
+        self.extent.0
+    }
+
+    /// Returns the number of valid columns in the block.
+    ///
+    /// This is the block width, unless the block lies on the right edge of a padded grid.
+    #[inline]
+    pub fn cols(&self) -> usize {
+        self.extent.1
     }
 
     /// Returns `true` if the given coordinates are valid.
     #[inline]
     pub fn contains(&self, (row, col): Coords) -> bool {
-        row < B::WIDTH && col < B::WIDTH
+        row < self.extent.0 && col < self.extent.1
     }
 
     /// Returns a reference to the element at the given coordinates, or [`None`] if they are
@@ -548,12 +1640,16 @@ impl<'a, T, B: BlockDim> BlockMut<'a, T, B> {
     }
 
     /// Returns all elements in block as a slice in memory order.
+    ///
+    /// This includes any padding elements.
     #[inline]
     pub fn raw(&self) -> &[T] {
         self.arr
     }
 
     /// Returns all elements in block as a mutable slice in memory order.
+    ///
+    /// This includes any padding elements.
     #[inline]
     pub fn raw_mut(&mut self) -> &mut [T] {
         self.arr
@@ -561,11 +1657,11 @@ impl<'a, T, B: BlockDim> BlockMut<'a, T, B> {
 
     /// Returns the 1D memory index calculated from 2D coordinates.
     fn calc_index(&self, (row, col): Coords) -> usize {
-        B::WIDTH * row + col
+        (row << B::col_shift(self.dims)) + col
     }
 }
 
-impl<'a, T, B: BlockDim> Index<Coords> for BlockMut<'a, T, B> {
+impl<'a, T, B: Dims> Index<Coords> for BlockMut<'a, T, B> {
     type Output = T;
 
     #[inline]
@@ -574,60 +1670,119 @@ impl<'a, T, B: BlockDim> Index<Coords> for BlockMut<'a, T, B> {
     }
 }
 
-impl<'a, T, B: BlockDim> IndexMut<Coords> for BlockMut<'a, T, B> {
+impl<'a, T, B: Dims> IndexMut<Coords> for BlockMut<'a, T, B> {
     #[inline]
     fn index_mut(&mut self, coords: Coords) -> &mut Self::Output {
         self.get_mut(coords).expect("Coordinates out of bounds")
     }
 }
 
+impl<T> Block<'_, T, DynDim> {
+    /// Returns the width (and height) of the block.
+    #[inline]
+    pub fn block_width(&self) -> usize {
+        1 << self.dims
+    }
+}
+
+impl<T> BlockMut<'_, T, DynDim> {
+    /// Returns the width (and height) of the block.
+    #[inline]
+    pub fn block_width(&self) -> usize {
+        1 << self.dims
+    }
+}
+
+/// Panic message for when growing a grid overflows its size.
+const OVERFLOW: &str = "Number of elements overflows a `usize`";
+
+/// A piece of memory visited while reblocking, see `BlockGrid::for_each_reblock_run`.
+enum ReblockRun {
+    /// A run of valid elements, contiguous in both the old and new memory.
+    Valid(Range<usize>),
+    /// A single padding element, with the coordinates of the nearest valid element.
+    Padding(Coords),
+}
+
+/// Returns the valid extent of the block at `block_coords` in a grid of `rows` and `cols`.
+#[inline]
+pub(crate) fn block_extent<B: Dims>(
+    rows: usize,
+    cols: usize,
+    dims: B::Value,
+    (b_row, b_col): Coords,
+) -> Coords {
+    let (height, width) = (B::height(dims), B::width(dims));
+    let (row, col) = (height * b_row, width * b_col);
+    ((rows - row).min(height), (cols - col).min(width))
+}
+
+/// Returns the 1D memory index calculated from 2D coordinates, in a grid with `col_blocks`.
+#[inline]
+pub(crate) fn calc_index<B: Dims>(col_blocks: usize, dims: B::Value, (row, col): Coords) -> usize {
+    let (row_shift, col_shift) = (B::row_shift(dims), B::col_shift(dims));
+    // Get block
+    let (b_row, b_col) = (row >> row_shift, col >> col_shift);
+    let block_ind = (col_blocks * b_row + b_col) << (row_shift + col_shift);
+    // Offset within block
+    let (s_row, s_col) = (row & ((1 << row_shift) - 1), col & ((1 << col_shift) - 1));
+    let sub_ind = (s_row << col_shift) + s_col;
+    block_ind + sub_ind
+}
+
+/// Swaps the disjoint ranges of `len` elements starting at `a` and `b`.
+#[inline]
+fn swap_ranges<T>(buf: &mut [T], a: usize, b: usize, len: usize) {
+    let (lo, hi) = (a.min(b), a.max(b));
+    let (head, tail) = buf.split_at_mut(hi);
+    head[lo..(lo + len)].swap_with_slice(&mut tail[..len]);
+}
+
+/// Returns the number of blocks of length `size` needed to cover `len` elements.
+#[inline]
+pub(crate) fn calc_blocks(len: usize, size: usize) -> usize {
+    len / size + (len % size != 0) as usize
+}
+
 #[cfg(feature = "serde")]
-mod serde_hack {
+pub(crate) mod serde_hack {
     use super::*;
     use core::convert::{From, TryFrom};
-    use core::fmt;
-
-    /// Error if invalid dimensions are passed in or deserialized.
-    ///
-    /// Currently, only used for `serde` deserialization, but in the future, this should be used
-    /// for the [`BlockGrid<T, B>`] constructors as well.
-    #[derive(Debug)]
-    pub(super) struct InvalidSizeError;
-
-    impl fmt::Display for InvalidSizeError {
-        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            write!(f, "Dimensions are invalid")
-        }
-    }
 
     /// A "trick" to avoid writing (de)serialization code with validation.
     ///
-    /// See PR for details.
+    /// See PR for details. Note that `rows` and `cols` are the valid dimensions, but `buf` is
+    /// the raw memory including any padding. The block height is only written for non-square
+    /// blocks, so square ones keep the original format.
     #[derive(Deserialize, Serialize)]
-    pub(super) struct ShadowBlockGrid<T> {
-        rows: usize,
-        cols: usize,
+    pub(crate) struct ShadowBlockGrid<T> {
+        pub(crate) rows: usize,
+        pub(crate) cols: usize,
         #[serde(rename = "b")]
-        bwidth: usize,
-        buf: Vec<T>,
+        pub(crate) bwidth: usize,
+        pub(crate) buf: Vec<T>,
+        #[serde(rename = "bh", default, skip_serializing_if = "Option::is_none")]
+        pub(crate) bheight: Option<usize>,
     }
 
     // Serialization
-    impl<T, B: BlockDim> From<BlockGrid<T, B>> for ShadowBlockGrid<T> {
-        fn from(bgrid: BlockGrid<T, B>) -> Self {
-            // Assumes `bgrid` is in valid state
+    impl<T, B: BlockShape, L: Layout> From<BlockGrid<T, B, L>> for ShadowBlockGrid<T> {
+        fn from(bgrid: BlockGrid<T, B, L>) -> Self {
+            // Assumes `bgrid` is in valid state. The memory is always written in the default
+            // layout, so it can be read back in any other.
             Self {
                 rows: bgrid.rows(),
                 cols: bgrid.cols(),
                 bwidth: B::WIDTH,
-                buf: bgrid.take_raw_vec(),
+                buf: bgrid.relayout::<Blocked>().take_raw_vec(),
+                bheight: Some(B::HEIGHT).filter(|&h| h != B::WIDTH),
             }
         }
     }
 
     // Deserialization
-    impl<T, B: BlockDim> TryFrom<ShadowBlockGrid<T>> for BlockGrid<T, B> {
-        type Error = InvalidSizeError;
+    impl<T, B: BlockShape, L: Layout> TryFrom<ShadowBlockGrid<T>> for BlockGrid<T, B, L> {
+        type Error = Error;
 
         fn try_from(shadow: ShadowBlockGrid<T>) -> Result<Self, Self::Error> {
             let ShadowBlockGrid {
@@ -635,12 +1790,23 @@ mod serde_hack {
                 cols,
                 bwidth,
                 buf,
+                bheight,
             } = shadow;
             // Check that deserialized data is a valid state
             if bwidth != B::WIDTH {
-                return Err(InvalidSizeError);
+                return Err(Error::BlockWidthMismatch {
+                    expected: B::WIDTH,
+                    actual: bwidth,
+                });
+            }
+            let bheight = bheight.unwrap_or(bwidth);
+            if bheight != B::HEIGHT {
+                return Err(Error::BlockHeightMismatch {
+                    expected: B::HEIGHT,
+                    actual: bheight,
+                });
             }
-            Self::from_raw_vec(rows, cols, buf).map_err(|_| InvalidSizeError)
+            BlockGrid::<T, B>::from_raw_vec(rows, cols, buf).map(BlockGrid::relayout)
         }
     }
 }
@@ -1,4 +1,63 @@
-/// A type that represents compile time block dimensions.
+use core::any::TypeId;
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::block_grid::calc_index;
+use crate::{Blocked, Coords, Layout};
+
+/// A type that represents compile time block shapes, with independent height and width.
+///
+/// Every [`BlockDim`] is a square block shape. For non-square blocks, see [`Rect`].
+pub trait BlockShape: Clone {
+    /// Number of left shifts of 1 for the height.
+    const ROW_SHIFT: usize;
+    /// Number of left shifts of 1 for the width.
+    const COL_SHIFT: usize;
+
+    /// Height of 2D block.
+    const HEIGHT: usize = 1 << Self::ROW_SHIFT;
+    /// Width of 2D block.
+    const WIDTH: usize = 1 << Self::COL_SHIFT;
+    /// Number of elements in 2D block.
+    const AREA: usize = Self::HEIGHT * Self::WIDTH;
+    /// Bitmask for the height.
+    const ROW_MASK: usize = Self::HEIGHT - 1;
+    /// Bitmask for the width.
+    const COL_MASK: usize = Self::WIDTH - 1;
+
+    /// Rounds up dimensions to the next multiple of the block size. Returns `(rows, cols)`.
+    ///
+    /// A [`BlockGrid`] accepts any dimensions, but internally pads its memory up to these.
+    ///
+    /// [`BlockGrid`]: crate::BlockGrid
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockShape, Rect, U2, U4};
+    ///
+    /// // Blocks that are 2 rows high and 4 columns wide
+    /// assert_eq!(Rect::<U2, U4>::round_up_to_valid(3, 10), (4, 12));
+    /// assert_eq!(Rect::<U2, U4>::round_up_to_valid(5, 3), (6, 4));
+    /// ```
+    fn round_up_to_valid(rows: usize, cols: usize) -> (usize, usize) {
+        let round_up = |i: usize, size: usize| {
+            let mut i = i.max(1);
+            let rem = i % size;
+            if rem != 0 {
+                i += size - rem;
+            }
+            i
+        };
+        (round_up(rows, Self::HEIGHT), round_up(cols, Self::WIDTH))
+    }
+}
+
+/// A type that represents compile time square block dimensions.
+///
+/// Every [`BlockDim`] is also a [`BlockShape`] with both shifts set to [`SHIFT`][Self::SHIFT], so
+/// only this trait needs implementing. If both traits are in scope, such as with a glob import,
+/// name the trait for the constants they share, like `<U4 as BlockDim>::WIDTH`.
 pub trait BlockDim: Clone {
     /// Number of left shifts of 1 for value.
     const SHIFT: usize;
@@ -10,54 +69,209 @@ pub trait BlockDim: Clone {
     /// Bitmask for value.
     const MASK: usize = Self::WIDTH - 1;
 
-    /// Rounds up dimensions to next valid size. Returns `(rows, cols)`.
+    /// Rounds up dimensions to the next multiple of the block size. Returns `(rows, cols)`.
+    ///
+    /// A [`BlockGrid`] accepts any dimensions, but internally pads its memory up to these.
+    ///
+    /// [`BlockGrid`]: crate::BlockGrid
     ///
     /// # Example
     ///
     /// ```
     /// use block_grid::{BlockDim, U4};
     ///
-    /// // (3, 10) are not valid dimensions for a block size of 4
+    /// // (3, 10) are not a multiple of a block size of 4
     /// let new_valid = U4::round_up_to_valid(3, 10);
-    /// // (4, 12) are the returned valid dimensions
+    /// // (4, 12) are the returned padded dimensions
     /// assert_eq!(new_valid, (4, 12));
     /// ```
     fn round_up_to_valid(rows: usize, cols: usize) -> (usize, usize) {
-        let round_up = |i: usize| {
-            let mut i = i.max(1);
-            let rem = i % Self::WIDTH;
-            if rem != 0 {
-                i += Self::WIDTH - rem;
-            }
-            i
-        };
-        (round_up(rows), round_up(cols))
+        <Self as BlockShape>::round_up_to_valid(rows, cols)
     }
 }
 
-macro_rules! make_block_width [
-    ($($name: ident, $shift: literal);*) => {
-        $(
-            #[allow(missing_docs)]
-            #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-            pub struct $name;
+impl<D: BlockDim> BlockShape for D {
+    const ROW_SHIFT: usize = D::SHIFT;
+    const COL_SHIFT: usize = D::SHIFT;
+}
 
-            impl BlockDim for $name {
-                const SHIFT: usize = $shift;
-            }
-        )*
-    }
-];
-
-make_block_width![
-    U1,   0;
-    U2,   1;
-    U4,   2;
-    U8,   3;
-    U16,  4;
-    U32,  5;
-    U64,  6;
-    U128, 7;
-    U256, 8;
-    U512, 9
-];
+/// A rectangular block shape, `H` rows high and `W` columns wide.
+///
+/// For example, `Rect<U4, U64>` is a wide and short block of 4 rows and 64 columns, which suits
+/// scanline-heavy workloads.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Rect<H: BlockDim, W: BlockDim> {
+    _phantom: PhantomData<(H, W)>,
+}
+
+impl<H: BlockDim, W: BlockDim> BlockShape for Rect<H, W> {
+    const ROW_SHIFT: usize = H::SHIFT;
+    const COL_SHIFT: usize = W::SHIFT;
+}
+
+/// The largest valid shift for a square block size, so that [`BlockShape::AREA`] fits in a
+/// `usize`.
+pub const MAX_SHIFT: usize = (usize::BITS as usize - 1) / 2;
+
+/// A square block size with a width of `1 << N`.
+///
+/// All the `U*` types are aliases of this, so `U8` is the same type as `Shift<3>`. Any `N` up to
+/// [`MAX_SHIFT`] can be used, and larger ones are rejected at compile-time.
+///
+/// # Example
+///
+/// ```
+/// use block_grid::{BlockDim, BlockGrid, Shift, U8};
+///
+/// let grid: BlockGrid<u8, Shift<3>> = BlockGrid::<u8, U8>::new(16, 16)?;
+/// assert_eq!(Shift::<10>::WIDTH, 1024);
+///
+/// // The aliases work as before with only `BlockDim` in scope
+/// assert_eq!((U8::WIDTH, U8::AREA, U8::MASK), (8, 64, 7));
+/// assert_eq!(U8::round_up_to_valid(3, 10), (8, 16));
+/// # Ok::<(), block_grid::Error>(())
+/// ```
+///
+/// ```compile_fail
+/// use block_grid::{BlockGrid, Shift};
+///
+/// // Way too big for a `usize`
+/// let grid = BlockGrid::<u8, Shift<40>>::new(1, 1);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Shift<const N: usize>;
+
+impl<const N: usize> BlockDim for Shift<N> {
+    const SHIFT: usize = {
+        assert!(N <= MAX_SHIFT, "Block shift is too large");
+        N
+    };
+}
+
+/// Block size of 1, meaning no blocks at all (see [`Grid`][crate::Grid]).
+pub type U1 = Shift<0>;
+/// Square block size of 2.
+pub type U2 = Shift<1>;
+/// Square block size of 4.
+pub type U4 = Shift<2>;
+/// Square block size of 8.
+pub type U8 = Shift<3>;
+/// Square block size of 16.
+pub type U16 = Shift<4>;
+/// Square block size of 32.
+pub type U32 = Shift<5>;
+/// Square block size of 64.
+pub type U64 = Shift<6>;
+/// Square block size of 128.
+pub type U128 = Shift<7>;
+/// Square block size of 256.
+pub type U256 = Shift<8>;
+/// Square block size of 512.
+pub type U512 = Shift<9>;
+
+/// A source of block dimensions, either a compile time [`BlockShape`] or [`DynDim`] for a
+/// square block width chosen at runtime.
+///
+/// The block iterators and views are generic over this, so the same types serve both a
+/// [`BlockGrid`] and a [`DynBlockGrid`]. Every [`BlockShape`] is one, and otherwise this trait is
+/// sealed, meaning it cannot be implemented by downstream crates.
+///
+/// [`BlockGrid`]: crate::BlockGrid
+/// [`DynBlockGrid`]: crate::DynBlockGrid
+pub trait Dims: Clone + private::Sealed {
+    /// The part of the dimensions only known at runtime, which is `()` for a [`BlockShape`].
+    #[doc(hidden)]
+    type Value: Copy + fmt::Debug + Send + Sync;
+
+    /// Returns the number of left shifts of 1 for the height.
+    #[doc(hidden)]
+    fn row_shift(dims: Self::Value) -> usize;
+
+    /// Returns the number of left shifts of 1 for the width.
+    #[doc(hidden)]
+    fn col_shift(dims: Self::Value) -> usize;
+
+    /// Returns the offset into memory of `coords` with layout `L`, in a padded grid of `blocks`
+    /// blocks.
+    #[doc(hidden)]
+    fn offset<L: Layout>(dims: Self::Value, blocks: Coords, coords: Coords) -> usize;
+
+    /// Returns the height of a block.
+    #[doc(hidden)]
+    #[inline]
+    fn height(dims: Self::Value) -> usize {
+        1 << Self::row_shift(dims)
+    }
+
+    /// Returns the width of a block.
+    #[doc(hidden)]
+    #[inline]
+    fn width(dims: Self::Value) -> usize {
+        1 << Self::col_shift(dims)
+    }
+
+    /// Returns the number of elements in a block.
+    #[doc(hidden)]
+    #[inline]
+    fn area(dims: Self::Value) -> usize {
+        1 << (Self::row_shift(dims) + Self::col_shift(dims))
+    }
+}
+
+/// Square block dimensions chosen at runtime, as used by a [`DynBlockGrid`].
+///
+/// This only appears as the parameter of the iterators and block views of a [`DynBlockGrid`],
+/// such as [`DynBlock`], which is an alias of `Block<'a, T, DynDim>`.
+///
+/// [`DynBlockGrid`]: crate::DynBlockGrid
+/// [`DynBlock`]: crate::DynBlock
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DynDim;
+
+impl<B: BlockShape> Dims for B {
+    type Value = ();
+
+    #[inline]
+    fn row_shift(_: ()) -> usize {
+        B::ROW_SHIFT
+    }
+
+    #[inline]
+    fn col_shift(_: ()) -> usize {
+        B::COL_SHIFT
+    }
+
+    #[inline]
+    fn offset<L: Layout>(_: (), blocks: Coords, coords: Coords) -> usize {
+        L::offset::<B>(blocks, coords)
+    }
+}
+
+impl Dims for DynDim {
+    /// The shift of the block width.
+    type Value = usize;
+
+    #[inline]
+    fn row_shift(shift: usize) -> usize {
+        shift
+    }
+
+    #[inline]
+    fn col_shift(shift: usize) -> usize {
+        shift
+    }
+
+    #[inline]
+    fn offset<L: Layout>(shift: usize, (_, col_blocks): Coords, coords: Coords) -> usize {
+        // A `DynBlockGrid` only has the default layout
+        debug_assert!(TypeId::of::<L>() == TypeId::of::<Blocked>());
+        calc_index::<Self>(col_blocks, shift, coords)
+    }
+}
+
+mod private {
+    use super::*;
+    pub trait Sealed {}
+    impl<B: BlockShape> Sealed for B {}
+    impl Sealed for DynDim {}
+}
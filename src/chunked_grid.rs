@@ -0,0 +1,318 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use core::marker::PhantomData;
+use core::ops::{Index, IndexMut};
+
+use crate::block_grid::block_extent;
+use crate::iters::{ChunkedBlockIter, ChunkedBlockIterMut};
+use crate::{Block, BlockGrid, BlockMut, BlockShape, Coords, CoordsIterator, Error, SignedCoords};
+
+/// An unbounded 2D array, with blocks that are only allocated once written to.
+///
+/// Blocks are stored in a map keyed by their signed block coordinates, so the grid extends in
+/// every direction, including negative coordinates. Every element of a block that was never
+/// written to reads as the fill value, given to the constructor. Writing through [`entry`] or
+/// indexing allocates the whole block, filled with clones of it.
+///
+/// Signed coordinates work just like [`Coords`], but the block coordinates of `(row, col)` are
+/// rounded down, so `(-1, -1)` is in the bottom-right corner of block `(-1, -1)`. A rectangle of
+/// the grid can be copied to a dense [`BlockGrid`] with [`to_block_grid`], and back with
+/// [`from_block_grid`].
+///
+/// [`entry`]: Self::entry
+/// [`to_block_grid`]: Self::to_block_grid
+/// [`from_block_grid`]: Self::from_block_grid
+///
+/// # Example
+///
+/// ```
+/// use block_grid::{ChunkedGrid, U4};
+///
+/// let mut grid = ChunkedGrid::<u8, U4>::new();
+/// grid[(-3, 100)] = 7;
+/// *grid.entry((2, -5)) += 1;
+///
+/// assert_eq!(grid.blocks(), 2);
+/// assert_eq!(grid[(-3, 100)], 7);
+/// assert_eq!(grid.get((-3, 101)), Some(&0));
+/// assert_eq!(grid.get((50, 50)), None);
+/// assert_eq!(grid[(50, 50)], 0);
+///
+/// let (origin, dims) = grid.bounds().unwrap();
+/// assert_eq!((origin, dims), ((-4, -8), (8, 112)));
+/// let dense = grid.to_block_grid(origin, dims)?;
+/// assert_eq!(dense[(1, 108)], 7);
+/// # Ok::<(), block_grid::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct ChunkedGrid<T, B: BlockShape> {
+    fill: T,
+    chunks: BTreeMap<SignedCoords, Box<[T]>>,
+    _phantom: PhantomData<B>,
+}
+
+impl<T, B: BlockShape> ChunkedGrid<T, B> {
+    /// Constructs an empty `ChunkedGrid<T, B>`, where every element reads as `fill`.
+    pub fn filled(fill: T) -> Self {
+        Self {
+            fill,
+            chunks: BTreeMap::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the value of every element that was never written to.
+    #[inline]
+    pub fn fill(&self) -> &T {
+        &self.fill
+    }
+
+    /// Returns the number of allocated blocks.
+    #[inline]
+    pub fn blocks(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Returns a reference to the element at the given coordinates, or [`None`] if its block
+    /// isn't allocated.
+    ///
+    /// Indexing instead gives the fill value for blocks that aren't allocated.
+    #[inline]
+    pub fn get(&self, coords: SignedCoords) -> Option<&T> {
+        let (block_coords, sub_coords) = split::<B>(coords);
+        let chunk = self.chunks.get(&block_coords)?;
+        Some(&chunk[calc_sub_index::<B>(sub_coords)])
+    }
+
+    /// Returns a mutable reference to the element at the given coordinates, or [`None`] if its
+    /// block isn't allocated.
+    ///
+    /// See [`entry`][Self::entry] for a version that allocates the block instead.
+    #[inline]
+    pub fn get_mut(&mut self, coords: SignedCoords) -> Option<&mut T> {
+        let (block_coords, sub_coords) = split::<B>(coords);
+        let chunk = self.chunks.get_mut(&block_coords)?;
+        Some(&mut chunk[calc_sub_index::<B>(sub_coords)])
+    }
+
+    /// Returns a view of the block at the given block coordinates, or [`None`] if it isn't
+    /// allocated.
+    ///
+    /// The [`Block`] is a view of just that block, so its own coordinates are always `(0, 0)`.
+    #[inline]
+    pub fn block(&self, block_coords: SignedCoords) -> Option<Block<'_, T, B>> {
+        let chunk = self.chunks.get(&block_coords)?;
+        // SAFETY: Every chunk is allocated with exactly `B::AREA` elements
+        Some(unsafe { Block::new((0, 0), (B::HEIGHT, B::WIDTH), (), chunk) })
+    }
+
+    /// Returns a mutable view of the block at the given block coordinates, or [`None`] if it
+    /// isn't allocated.
+    ///
+    /// See [`block`][Self::block] for details.
+    #[inline]
+    pub fn block_mut(&mut self, block_coords: SignedCoords) -> Option<BlockMut<'_, T, B>> {
+        let chunk = self.chunks.get_mut(&block_coords)?;
+        // SAFETY: Every chunk is allocated with exactly `B::AREA` elements
+        Some(unsafe { BlockMut::new((0, 0), (B::HEIGHT, B::WIDTH), (), chunk) })
+    }
+
+    /// Frees the block at the given block coordinates, so it reads as the fill value again.
+    ///
+    /// Returns `true` if the block was allocated.
+    pub fn remove_block(&mut self, block_coords: SignedCoords) -> bool {
+        self.chunks.remove(&block_coords).is_some()
+    }
+
+    /// Frees all the blocks.
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+    }
+
+    /// Returns an iterator over all the allocated blocks, in row-major order of their block
+    /// coordinates, yielding them along with a [`Block`] of each.
+    ///
+    /// See [`block`][Self::block] for details.
+    #[inline]
+    pub fn block_iter(&self) -> ChunkedBlockIter<'_, T, B> {
+        ChunkedBlockIter::new(&self.chunks)
+    }
+
+    /// Returns a mutable iterator over all the allocated blocks, in row-major order of their
+    /// block coordinates, yielding them along with a [`BlockMut`] of each.
+    ///
+    /// See [`block`][Self::block] for details.
+    #[inline]
+    pub fn block_iter_mut(&mut self) -> ChunkedBlockIterMut<'_, T, B> {
+        ChunkedBlockIterMut::new(&mut self.chunks)
+    }
+
+    /// Returns the smallest rectangle that covers all the allocated blocks, as the coordinates
+    /// of its top-left element and its `(rows, cols)`, or [`None`] if there are none.
+    pub fn bounds(&self) -> Option<(SignedCoords, Coords)> {
+        // Keys are sorted by row first, so only the columns need a search
+        let (&(min_row, _), &(max_row, _)) =
+            (self.chunks.keys().next()?, self.chunks.keys().last()?);
+        let (min_col, max_col) = self
+            .chunks
+            .keys()
+            .fold((isize::MAX, isize::MIN), |(lo, hi), &(_, col)| {
+                (lo.min(col), hi.max(col))
+            });
+        let origin = (min_row << B::ROW_SHIFT, min_col << B::COL_SHIFT);
+        let rows = B::HEIGHT * (max_row - min_row + 1) as usize;
+        let cols = B::WIDTH * (max_col - min_col + 1) as usize;
+        Some((origin, (rows, cols)))
+    }
+}
+
+impl<T: Clone, B: BlockShape> ChunkedGrid<T, B> {
+    /// Returns a mutable reference to the element at the given coordinates, allocating its
+    /// block filled with clones of the fill value if needed.
+    #[inline]
+    pub fn entry(&mut self, coords: SignedCoords) -> &mut T {
+        let (block_coords, sub_coords) = split::<B>(coords);
+        let fill = &self.fill;
+        let chunk = self
+            .chunks
+            .entry(block_coords)
+            .or_insert_with(|| vec![fill.clone(); B::AREA].into_boxed_slice());
+        &mut chunk[calc_sub_index::<B>(sub_coords)]
+    }
+
+    /// Constructs a dense [`BlockGrid`] of `(rows, cols)` elements, copied from the rectangle
+    /// with its top-left element at `origin`.
+    ///
+    /// Elements of blocks that aren't allocated are the fill value. If `origin` is at the start
+    /// of a block, the blocks line up and each is copied in one go. See [`bounds`][Self::bounds]
+    /// for the rectangle that covers every allocated block.
+    ///
+    /// # Errors
+    ///
+    /// If either `rows` or `cols` is zero. See [`Error`] for details.
+    pub fn to_block_grid(
+        &self,
+        origin: SignedCoords,
+        (rows, cols): Coords,
+    ) -> Result<BlockGrid<T, B>, Error> {
+        let mut grid = BlockGrid::filled(rows, cols, self.fill.clone())?;
+        match aligned_base::<B>(origin) {
+            Some((base_row, base_col)) => {
+                for mut block in grid.block_iter_mut() {
+                    let (b_row, b_col) = block.coords();
+                    let key = (base_row + b_row as isize, base_col + b_col as isize);
+                    if let Some(chunk) = self.chunks.get(&key) {
+                        block.raw_mut().clone_from_slice(chunk);
+                    }
+                }
+            }
+            None => {
+                for ((row, col), x) in grid.each_iter_mut().coords() {
+                    if let Some(y) = self.get(offset(origin, (row, col))) {
+                        x.clone_from(y);
+                    }
+                }
+            }
+        }
+        Ok(grid)
+    }
+
+    /// Constructs a `ChunkedGrid<T, B>` from a dense [`BlockGrid`], with its top-left element at
+    /// `origin`, and `fill` for every other element.
+    ///
+    /// Every block that covers the grid is allocated. If `origin` is at the start of a block, the
+    /// blocks line up and each is moved over in one go.
+    pub fn from_block_grid(grid: BlockGrid<T, B>, origin: SignedCoords, fill: T) -> Self {
+        let mut chunked = Self::filled(fill);
+        let (base_row, base_col) = match aligned_base::<B>(origin) {
+            Some(base) => base,
+            None => {
+                for ((row, col), x) in grid.each_iter().coords() {
+                    chunked.entry(offset(origin, (row, col))).clone_from(x);
+                }
+                return chunked;
+            }
+        };
+        let (rows, cols) = (grid.rows(), grid.cols());
+        let (row_blocks, col_blocks) = grid.block_dims();
+        let mut elems = grid.take_raw_vec().into_iter();
+        for i in 0..(row_blocks * col_blocks) {
+            let (b_row, b_col) = (i / col_blocks, i % col_blocks);
+            let mut chunk: Box<[T]> = elems.by_ref().take(B::AREA).collect();
+            // Padding was never part of the grid, so it's the fill value like any other element
+            let (h, w) = block_extent::<B>(rows, cols, (), (b_row, b_col));
+            for si in 0..B::HEIGHT {
+                for sj in 0..B::WIDTH {
+                    if si >= h || sj >= w {
+                        chunk[calc_sub_index::<B>((si, sj))].clone_from(&chunked.fill);
+                    }
+                }
+            }
+            let key = (base_row + b_row as isize, base_col + b_col as isize);
+            chunked.chunks.insert(key, chunk);
+        }
+        chunked
+    }
+}
+
+impl<T: Default, B: BlockShape> ChunkedGrid<T, B> {
+    /// Constructs an empty `ChunkedGrid<T, B>`, where every element reads as the default value
+    /// of `T`.
+    pub fn new() -> Self {
+        Self::filled(T::default())
+    }
+}
+
+impl<T: Default, B: BlockShape> Default for ChunkedGrid<T, B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, B: BlockShape> Index<SignedCoords> for ChunkedGrid<T, B> {
+    type Output = T;
+
+    /// Returns the element at the given coordinates, or the fill value if its block isn't
+    /// allocated.
+    #[inline]
+    fn index(&self, coords: SignedCoords) -> &Self::Output {
+        self.get(coords).unwrap_or(&self.fill)
+    }
+}
+
+impl<T: Clone, B: BlockShape> IndexMut<SignedCoords> for ChunkedGrid<T, B> {
+    /// Returns the element at the given coordinates, allocating its block if needed.
+    #[inline]
+    fn index_mut(&mut self, coords: SignedCoords) -> &mut Self::Output {
+        self.entry(coords)
+    }
+}
+
+/// Splits signed coordinates into the coordinates of their block, and those within it.
+#[inline]
+fn split<B: BlockShape>((row, col): SignedCoords) -> (SignedCoords, Coords) {
+    // Arithmetic shifts round down, so negative coordinates work too
+    let block_coords = (row >> B::ROW_SHIFT, col >> B::COL_SHIFT);
+    let sub_coords = (row as usize & B::ROW_MASK, col as usize & B::COL_MASK);
+    (block_coords, sub_coords)
+}
+
+/// Returns the block coordinates of `origin` if it's at the start of a block.
+#[inline]
+fn aligned_base<B: BlockShape>(origin: SignedCoords) -> Option<SignedCoords> {
+    let (block_coords, sub_coords) = split::<B>(origin);
+    Some(block_coords).filter(|_| sub_coords == (0, 0))
+}
+
+/// Returns the signed coordinates of `coords` offset by `origin`.
+#[inline]
+fn offset(origin: SignedCoords, (row, col): Coords) -> SignedCoords {
+    (origin.0 + row as isize, origin.1 + col as isize)
+}
+
+/// Returns the 1D memory index of coordinates within a block.
+#[inline]
+fn calc_sub_index<B: BlockShape>((s_row, s_col): Coords) -> usize {
+    B::WIDTH * s_row + s_col
+}
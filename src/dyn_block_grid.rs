@@ -0,0 +1,550 @@
+use alloc::{vec, vec::Vec};
+use core::convert::TryFrom;
+use core::hash::{Hash, Hasher};
+use core::ops::{Index, IndexMut};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::block_grid::{calc_blocks, calc_index};
+use crate::iters::{
+    DynBlockIter, DynBlockIterMut, DynEachIter, DynEachIterMut, DynRowMajorIter, DynRowMajorIterMut,
+};
+use crate::{Block, BlockDim, BlockGrid, BlockMut, Coords, DynDim, Error, MAX_SHIFT};
+
+/// A fixed-size 2D array with a blocked memory representation, and a block size chosen at
+/// runtime.
+///
+/// This is the same as a [`BlockGrid<T, B>`], except that the square block width is a field
+/// instead of a generic parameter. It's useful when the block width is only known from the input
+/// data or from some tuning step. Indexing is a bit slower, because the block width isn't known
+/// at compile-time. If the block width matches, it can be converted to and from a
+/// [`BlockGrid<T, B>`] without copying any elements.
+///
+/// When deserializing with `serde`, any block width in the data is accepted.
+///
+/// # Example
+///
+/// ```
+/// use block_grid::{BlockGrid, DynBlockGrid, U4};
+/// use core::convert::TryFrom;
+///
+/// let data: Vec<_> = (0..(5 * 7)).collect();
+/// let grid = DynBlockGrid::from_row_major(5, 7, 4, &data)?;
+/// assert_eq!(grid.block_width(), 4);
+/// assert_eq!(grid[(2, 3)], 17);
+///
+/// // Convert to a compile-time block size
+/// let grid = BlockGrid::<usize, U4>::try_from(grid)?;
+/// assert_eq!(grid[(2, 3)], 17);
+/// # Ok::<(), block_grid::Error>(())
+/// ```
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(bound(serialize = "T: Clone + Serialize")))]
+#[cfg_attr(
+    feature = "serde",
+    serde(try_from = "crate::block_grid::serde_hack::ShadowBlockGrid<T>")
+)]
+#[cfg_attr(
+    feature = "serde",
+    serde(into = "crate::block_grid::serde_hack::ShadowBlockGrid<T>")
+)]
+#[derive(Clone, Debug)]
+pub struct DynBlockGrid<T> {
+    rows: usize,
+    cols: usize,
+    col_blocks: usize,
+    shift: usize,
+    buf: Vec<T>,
+}
+
+/// A view of a 2D block contiguous in memory, with a block size chosen at runtime.
+///
+/// Can be obtained via [`DynBlockIter`], which is created by calling
+/// [`DynBlockGrid::block_iter`].
+pub type DynBlock<'a, T> = Block<'a, T, DynDim>;
+
+/// A mutable view of a 2D block contiguous in memory, with a block size chosen at runtime.
+///
+/// Can be obtained via [`DynBlockIterMut`], which is created by calling
+/// [`DynBlockGrid::block_iter_mut`].
+pub type DynBlockMut<'a, T> = BlockMut<'a, T, DynDim>;
+
+impl<T> DynBlockGrid<T> {
+    /// Constructs a `DynBlockGrid<T>` by consuming a [`Vec<T>`].
+    ///
+    /// The ordering of the memory is taken as is in the vector. This includes any padding, so
+    /// `elems` must have the length of the dimensions rounded up to the block size.
+    ///
+    /// # Errors
+    ///
+    /// If invalid dimensions, either because `rows` or `cols` is zero or the length of `elems`
+    /// does not match the padded size, or if `block_width` is invalid. See [`Error`] for details.
+    pub fn from_raw_vec(
+        rows: usize,
+        cols: usize,
+        block_width: usize,
+        elems: Vec<T>,
+    ) -> Result<Self, Error> {
+        let shift = Self::check_block_width(block_width)?;
+        let size = Self::check_size(rows, cols, shift)?;
+        if size != elems.len() {
+            return Err(Error::LengthMismatch {
+                expected: size,
+                actual: elems.len(),
+            });
+        }
+        Ok(Self {
+            rows,
+            cols,
+            col_blocks: calc_blocks(cols, 1 << shift),
+            shift,
+            buf: elems,
+        })
+    }
+
+    /// Converts a `DynBlockGrid<T>` to a [`Vec<T>`] in memory order.
+    ///
+    /// This includes any padding elements.
+    #[inline]
+    pub fn take_raw_vec(self) -> Vec<T> {
+        self.buf
+    }
+
+    /// Returns the number of rows.
+    #[inline]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns.
+    #[inline]
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the number of elements.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.rows() * self.cols()
+    }
+
+    /// Returns the width (and height) of the blocks.
+    #[inline]
+    pub fn block_width(&self) -> usize {
+        1 << self.shift
+    }
+
+    /// Returns the number of blocks in the vertical direction.
+    #[inline]
+    pub fn row_blocks(&self) -> usize {
+        calc_blocks(self.rows, self.block_width())
+    }
+
+    /// Returns the number of blocks in the horizontal direction.
+    #[inline]
+    pub fn col_blocks(&self) -> usize {
+        self.col_blocks
+    }
+
+    /// Returns the total number of blocks.
+    #[inline]
+    pub fn blocks(&self) -> usize {
+        self.row_blocks() * self.col_blocks()
+    }
+
+    /// Returns `true` if the given coordinates are valid.
+    #[inline]
+    pub fn contains(&self, (row, col): Coords) -> bool {
+        row < self.rows && col < self.cols
+    }
+
+    /// Returns a reference to the element at the given coordinates, or [`None`] if they are
+    /// out-of-bounds.
+    #[inline]
+    pub fn get(&self, coords: Coords) -> Option<&T> {
+        if !self.contains(coords) {
+            return None;
+        }
+        // SAFETY: `coords` is a valid index
+        Some(unsafe { self.get_unchecked(coords) })
+    }
+
+    /// Returns a mutable reference to the element at the given coordinates, or [`None`] if they
+    /// are out-of-bounds.
+    #[inline]
+    pub fn get_mut(&mut self, coords: Coords) -> Option<&mut T> {
+        if !self.contains(coords) {
+            return None;
+        }
+        // SAFETY: `coords` is a valid index
+        Some(unsafe { self.get_unchecked_mut(coords) })
+    }
+
+    /// Returns a reference to the element at the given coordinates, without bounds checking.
+    ///
+    /// # Safety
+    ///
+    /// Calling this method with out-of-bounds coordinates is *undefined-behaviour*.
+    #[inline]
+    pub unsafe fn get_unchecked(&self, coords: Coords) -> &T {
+        debug_assert!(self.contains(coords));
+        let ind = self.calc_index(coords);
+        self.buf.get_unchecked(ind)
+    }
+
+    /// Returns a mutable reference to the element at the given coordinates, without bounds
+    /// checking.
+    ///
+    /// # Safety
+    ///
+    /// Calling this method with out-of-bounds coordinates is *undefined-behaviour*.
+    #[inline]
+    pub unsafe fn get_unchecked_mut(&mut self, coords: Coords) -> &mut T {
+        debug_assert!(self.contains(coords));
+        let ind = self.calc_index(coords);
+        self.buf.get_unchecked_mut(ind)
+    }
+
+    /// Returns all elements as a slice in memory order.
+    ///
+    /// This includes any padding elements.
+    #[inline]
+    pub fn raw(&self) -> &[T] {
+        &self.buf
+    }
+
+    /// Returns all elements as a mutable slice in memory order.
+    ///
+    /// This includes any padding elements.
+    #[inline]
+    pub fn raw_mut(&mut self) -> &mut [T] {
+        &mut self.buf
+    }
+
+    /// Returns an iterator over all the elements in memory order.
+    ///
+    /// See [`BlockGrid::each_iter`] for details.
+    #[inline]
+    pub fn each_iter(&self) -> DynEachIter<'_, T> {
+        DynEachIter::new(
+            (self.rows, self.cols),
+            self.col_blocks,
+            self.shift,
+            &self.buf,
+        )
+    }
+
+    /// Returns a mutable iterator over all the elements in memory order.
+    ///
+    /// See [`BlockGrid::each_iter_mut`] for details.
+    #[inline]
+    pub fn each_iter_mut(&mut self) -> DynEachIterMut<'_, T> {
+        let (size, col_blocks) = ((self.rows, self.cols), self.col_blocks);
+        DynEachIterMut::new(size, col_blocks, self.shift, &mut self.buf)
+    }
+
+    /// Returns an iterator over all blocks in memory order, yielding [`DynBlock`]s.
+    ///
+    /// See [`BlockGrid::block_iter`] for details.
+    #[inline]
+    pub fn block_iter(&self) -> DynBlockIter<'_, T> {
+        DynBlockIter::new(
+            (self.rows, self.cols),
+            self.block_dims(),
+            self.shift,
+            &self.buf,
+        )
+    }
+
+    /// Returns a mutable iterator over all blocks in memory order, yielding [`DynBlockMut`]s.
+    ///
+    /// See [`BlockGrid::block_iter_mut`] for details.
+    #[inline]
+    pub fn block_iter_mut(&mut self) -> DynBlockIterMut<'_, T> {
+        let (size, blocks) = ((self.rows, self.cols), self.block_dims());
+        DynBlockIterMut::new(size, blocks, self.shift, &mut self.buf)
+    }
+
+    /// Returns an iterator over all the elements in row-major order.
+    ///
+    /// See [`BlockGrid::row_major_iter`] for details.
+    #[inline]
+    pub fn row_major_iter(&self) -> DynRowMajorIter<'_, T> {
+        DynRowMajorIter::new(
+            (self.rows, self.cols),
+            self.block_dims(),
+            self.shift,
+            &self.buf,
+        )
+    }
+
+    /// Returns a mutable iterator over all the elements in row-major order.
+    ///
+    /// See [`BlockGrid::row_major_iter_mut`] for details.
+    #[inline]
+    pub fn row_major_iter_mut(&mut self) -> DynRowMajorIterMut<'_, T> {
+        let (size, blocks) = ((self.rows, self.cols), self.block_dims());
+        DynRowMajorIterMut::new(size, blocks, self.shift, &mut self.buf)
+    }
+
+    /// Returns the number of blocks in each dimension, as `(row_blocks, col_blocks)`.
+    #[inline]
+    fn block_dims(&self) -> Coords {
+        (self.row_blocks(), self.col_blocks())
+    }
+
+    /// Returns `true` if the grid has any padding elements.
+    #[inline]
+    fn is_padded(&self) -> bool {
+        self.buf.len() != self.size()
+    }
+
+    /// Checks if `block_width` is a valid power-of-two, returning its shift if so.
+    fn check_block_width(block_width: usize) -> Result<usize, Error> {
+        let shift = block_width.trailing_zeros() as usize;
+        if !block_width.is_power_of_two() || shift > MAX_SHIFT {
+            return Err(Error::InvalidBlockWidth { block_width });
+        }
+        Ok(shift)
+    }
+
+    /// Checks if `rows` and `cols` form a valid sized `DynBlockGrid<T>`, returning the number
+    /// of elements in memory (including padding) if so.
+    fn check_size(rows: usize, cols: usize, shift: usize) -> Result<usize, Error> {
+        if rows == 0 || cols == 0 {
+            return Err(Error::EmptyDimensions);
+        }
+        calc_blocks(rows, 1 << shift)
+            .checked_mul(calc_blocks(cols, 1 << shift))
+            .and_then(|blocks| blocks.checked_mul(1 << (2 * shift)))
+            .ok_or(Error::Overflow)
+    }
+
+    /// Returns the 1D memory index calculated from 2D coordinates.
+    fn calc_index(&self, coords: Coords) -> usize {
+        calc_index::<DynDim>(self.col_blocks(), self.shift, coords)
+    }
+}
+
+impl<T: Clone> DynBlockGrid<T> {
+    /// Constructs a `DynBlockGrid<T>` by filling with a single element.
+    ///
+    /// # Errors
+    ///
+    /// If either `rows` or `cols` is zero, or if `block_width` is invalid. See [`Error`] for
+    /// details.
+    pub fn filled(rows: usize, cols: usize, block_width: usize, elem: T) -> Result<Self, Error> {
+        let shift = Self::check_block_width(block_width)?;
+        let size = Self::check_size(rows, cols, shift)?;
+        Ok(Self {
+            rows,
+            cols,
+            col_blocks: calc_blocks(cols, block_width),
+            shift,
+            buf: vec![elem; size],
+        })
+    }
+
+    /// Constructs a `DynBlockGrid<T>` from a slice in row-major order.
+    ///
+    /// See [`BlockGrid::from_row_major`] for details.
+    ///
+    /// # Errors
+    ///
+    /// If invalid dimensions, either because `rows` or `cols` is zero or the length of `elems`
+    /// does not match `rows * cols`, or if `block_width` is invalid. See [`Error`] for details.
+    pub fn from_row_major(
+        rows: usize,
+        cols: usize,
+        block_width: usize,
+        elems: &[T],
+    ) -> Result<Self, Error> {
+        Self::from_array_index_helper(rows, cols, block_width, elems, |row, col| cols * row + col)
+    }
+
+    /// Constructs a `DynBlockGrid<T>` from a slice in column-major order.
+    ///
+    /// See [`BlockGrid::from_col_major`] for details.
+    ///
+    /// # Errors
+    ///
+    /// If invalid dimensions, either because `rows` or `cols` is zero or the length of `elems`
+    /// does not match `rows * cols`, or if `block_width` is invalid. See [`Error`] for details.
+    pub fn from_col_major(
+        rows: usize,
+        cols: usize,
+        block_width: usize,
+        elems: &[T],
+    ) -> Result<Self, Error> {
+        Self::from_array_index_helper(rows, cols, block_width, elems, |row, col| rows * col + row)
+    }
+
+    /// Helper method to convert from a differently ordered array to a `DynBlockGrid<T>`.
+    fn from_array_index_helper(
+        rows: usize,
+        cols: usize,
+        block_width: usize,
+        elems: &[T],
+        calc_index: impl Fn(usize, usize) -> usize,
+    ) -> Result<Self, Error> {
+        let shift = Self::check_block_width(block_width)?;
+        let size = Self::check_size(rows, cols, shift)?;
+        if rows * cols != elems.len() {
+            return Err(Error::LengthMismatch {
+                expected: rows * cols,
+                actual: elems.len(),
+            });
+        }
+        let mut grid = Self {
+            rows,
+            cols,
+            col_blocks: calc_blocks(cols, block_width),
+            shift,
+            buf: Vec::with_capacity(size),
+        };
+        // Iterate in memory order by index and pull values from `elems`
+        for bi in (0..rows).step_by(block_width) {
+            for bj in (0..cols).step_by(block_width) {
+                for si in 0..block_width {
+                    for sj in 0..block_width {
+                        // Clamp to the nearest valid element for padding
+                        let (row, col) = ((bi + si).min(rows - 1), (bj + sj).min(cols - 1));
+                        grid.buf.push(elems[calc_index(row, col)].clone());
+                    }
+                }
+            }
+        }
+        debug_assert_eq!(grid.buf.len(), size);
+        Ok(grid)
+    }
+}
+
+impl<T: Clone + Default> DynBlockGrid<T> {
+    /// Constructs a `DynBlockGrid<T>` by filling with the default value of `T`.
+    ///
+    /// # Errors
+    ///
+    /// If either `rows` or `cols` is zero, or if `block_width` is invalid. See [`Error`] for
+    /// details.
+    pub fn new(rows: usize, cols: usize, block_width: usize) -> Result<Self, Error> {
+        Self::filled(rows, cols, block_width, T::default())
+    }
+}
+
+impl<T, B: BlockDim> From<BlockGrid<T, B>> for DynBlockGrid<T> {
+    fn from(grid: BlockGrid<T, B>) -> Self {
+        let (rows, cols, col_blocks) = (grid.rows(), grid.cols(), grid.col_blocks());
+        Self {
+            rows,
+            cols,
+            col_blocks,
+            shift: B::SHIFT,
+            buf: grid.take_raw_vec(),
+        }
+    }
+}
+
+impl<T, B: BlockDim> TryFrom<DynBlockGrid<T>> for BlockGrid<T, B> {
+    type Error = Error;
+
+    fn try_from(grid: DynBlockGrid<T>) -> Result<Self, Self::Error> {
+        if grid.block_width() != B::WIDTH {
+            return Err(Error::BlockWidthMismatch {
+                expected: B::WIDTH,
+                actual: grid.block_width(),
+            });
+        }
+        let (rows, cols) = (grid.rows(), grid.cols());
+        Self::from_raw_vec(rows, cols, grid.take_raw_vec())
+    }
+}
+
+impl<T: PartialEq> PartialEq for DynBlockGrid<T> {
+    fn eq(&self, other: &Self) -> bool {
+        if (self.rows, self.cols) != (other.rows, other.cols) {
+            return false;
+        }
+        if self.shift == other.shift {
+            if !self.is_padded() {
+                return self.buf == other.buf;
+            }
+            // Padding is ignored, so compare only valid elements
+            return self.each_iter().eq(other.each_iter());
+        }
+        self.row_major_iter().eq(other.row_major_iter())
+    }
+}
+
+impl<T: Eq> Eq for DynBlockGrid<T> {}
+
+impl<T: Hash> Hash for DynBlockGrid<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Grids with different block widths can be equal, so hash in row-major order
+        self.rows.hash(state);
+        self.cols.hash(state);
+        for x in self.row_major_iter() {
+            x.hash(state);
+        }
+    }
+}
+
+impl<T> Index<Coords> for DynBlockGrid<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, coords: Coords) -> &Self::Output {
+        self.get(coords).expect("Index out of bounds")
+    }
+}
+
+impl<T> IndexMut<Coords> for DynBlockGrid<T> {
+    #[inline]
+    fn index_mut(&mut self, coords: Coords) -> &mut Self::Output {
+        self.get_mut(coords).expect("Index out of bounds")
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_hack {
+    use super::*;
+    use crate::block_grid::serde_hack::ShadowBlockGrid;
+    use core::convert::From;
+
+    // Serialization
+    impl<T> From<DynBlockGrid<T>> for ShadowBlockGrid<T> {
+        fn from(grid: DynBlockGrid<T>) -> Self {
+            // Assumes `grid` is in valid state
+            Self {
+                rows: grid.rows(),
+                cols: grid.cols(),
+                bwidth: grid.block_width(),
+                buf: grid.take_raw_vec(),
+                bheight: None,
+            }
+        }
+    }
+
+    // Deserialization
+    impl<T> TryFrom<ShadowBlockGrid<T>> for DynBlockGrid<T> {
+        type Error = Error;
+
+        fn try_from(shadow: ShadowBlockGrid<T>) -> Result<Self, Self::Error> {
+            let ShadowBlockGrid {
+                rows,
+                cols,
+                bwidth,
+                buf,
+                bheight,
+            } = shadow;
+            // Only square blocks are supported
+            if let Some(bheight) = bheight.filter(|&h| h != bwidth) {
+                return Err(Error::BlockHeightMismatch {
+                    expected: bwidth,
+                    actual: bheight,
+                });
+            }
+            Self::from_raw_vec(rows, cols, bwidth, buf)
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use core::fmt;
+
+use crate::Coords;
+
+/// An error describing why a [`BlockGrid`] or [`DynBlockGrid`] could not be constructed, or why
+/// two grids could not be combined.
+///
+/// Returned by all the fallible constructors and the `checked_*` arithmetic methods, as well as
+/// when deserializing with `serde`.
+///
+/// [`BlockGrid`]: crate::BlockGrid
+/// [`DynBlockGrid`]: crate::DynBlockGrid
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum Error {
+    /// Either `rows` or `cols` is zero.
+    EmptyDimensions,
+    /// The number of elements given does not match the dimensions.
+    ///
+    /// For raw memory, the expected number of elements includes any padding.
+    LengthMismatch {
+        /// Number of elements required by the dimensions.
+        expected: usize,
+        /// Number of elements actually given.
+        actual: usize,
+    },
+    /// Computing the number of elements overflows a `usize`.
+    Overflow,
+    /// The block width does not match the one of the block size.
+    ///
+    /// Only occurs when deserializing data that was serialized with a different block size.
+    BlockWidthMismatch {
+        /// Width of the block size being deserialized to.
+        expected: usize,
+        /// Width found in the serialized data.
+        actual: usize,
+    },
+    /// The block height does not match the one of the block size.
+    ///
+    /// Only occurs when deserializing data that was serialized with a different block size.
+    BlockHeightMismatch {
+        /// Height of the block size being deserialized to.
+        expected: usize,
+        /// Height found in the serialized data.
+        actual: usize,
+    },
+    /// The dimensions of two grids do not match.
+    ///
+    /// Only occurs in element-wise operations, like [`BlockGrid::checked_add`].
+    ///
+    /// [`BlockGrid::checked_add`]: crate::BlockGrid::checked_add
+    DimensionsMismatch {
+        /// Dimensions of the left-hand grid, as `(rows, cols)`.
+        expected: Coords,
+        /// Dimensions of the right-hand grid, as `(rows, cols)`.
+        actual: Coords,
+    },
+    /// The runtime block width is not a power of two, or is too large.
+    ///
+    /// Only occurs when constructing a [`DynBlockGrid`].
+    ///
+    /// [`DynBlockGrid`]: crate::DynBlockGrid
+    InvalidBlockWidth {
+        /// The block width given.
+        block_width: usize,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::EmptyDimensions => write!(f, "Dimensions must be non-zero"),
+            Self::LengthMismatch { expected, actual } => write!(
+                f,
+                "Expected {} elements, but {} were given",
+                expected, actual
+            ),
+            Self::Overflow => write!(f, "Number of elements overflows a `usize`"),
+            Self::BlockWidthMismatch { expected, actual } => write!(
+                f,
+                "Expected a block width of {}, but found {}",
+                expected, actual
+            ),
+            Self::BlockHeightMismatch { expected, actual } => write!(
+                f,
+                "Expected a block height of {}, but found {}",
+                expected, actual
+            ),
+            Self::DimensionsMismatch { expected, actual } => write!(
+                f,
+                "Expected dimensions of {:?}, but found {:?}",
+                expected, actual
+            ),
+            Self::InvalidBlockWidth { block_width } => write!(
+                f,
+                "Block width must be a power of two up to `1 << MAX_SHIFT`, but {} was given",
+                block_width
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
@@ -0,0 +1,674 @@
+use core::marker::PhantomData;
+use core::ops::{Index, IndexMut};
+use core::ptr::NonNull;
+
+use crate::block_grid::calc_index;
+use crate::iters::{
+    SplitBlocksMut, ViewBlockIter, ViewBlockIterMut, ViewEachIter, ViewEachIterMut,
+    ViewRowMajorIter, ViewRowMajorIterMut,
+};
+use crate::{BlockGrid, BlockShape, Coords};
+
+/// A borrowed rectangular window into a [`BlockGrid`].
+///
+/// Created by the [`BlockGrid::view`] method. All coordinates are relative to the window, so
+/// `(0, 0)` is its top-left element. The window does not have to be aligned to the blocks, but
+/// if it is, there are faster ways of iterating over it (see [`is_block_aligned`]).
+///
+/// [`is_block_aligned`]: Self::is_block_aligned
+///
+/// # Example
+///
+/// ```
+/// use block_grid::{BlockGrid, U2};
+///
+/// let data: Vec<_> = (0..(4 * 6)).collect();
+/// let grid = BlockGrid::<usize, U2>::from_row_major(4, 6, &data)?;
+///
+/// let view = grid.view((1, 2), (2, 3));
+/// assert_eq!(view[(0, 0)], 8);
+/// assert!(view.row_major_iter().copied().eq([8, 9, 10, 14, 15, 16]));
+///
+/// // Views can be sliced further
+/// let sub = view.view((1, 1), (1, 2));
+/// assert!(sub.row_major_iter().copied().eq([15, 16]));
+/// # Ok::<(), block_grid::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct GridView<'a, T, B: BlockShape> {
+    ptr: NonNull<T>,
+    col_blocks: usize,
+    start: Coords,
+    rows: usize,
+    cols: usize,
+    grid_size: Coords,
+    _phantom: PhantomData<(&'a T, B)>,
+}
+
+/// A mutable borrowed rectangular window into a [`BlockGrid`].
+///
+/// Created by the [`BlockGrid::view_mut`] method. See [`GridView`] for details.
+#[derive(Debug)]
+pub struct GridViewMut<'a, T, B: BlockShape> {
+    ptr: NonNull<T>,
+    col_blocks: usize,
+    start: Coords,
+    rows: usize,
+    cols: usize,
+    grid_size: Coords,
+    _phantom: PhantomData<(&'a mut T, B)>,
+}
+
+// SAFETY: A `GridView` is the same as a `&[T]`, and a `GridViewMut` the same as a `&mut [T]`
+unsafe impl<T: Sync, B: BlockShape> Send for GridView<'_, T, B> {}
+unsafe impl<T: Sync, B: BlockShape> Sync for GridView<'_, T, B> {}
+unsafe impl<T: Send, B: BlockShape> Send for GridViewMut<'_, T, B> {}
+unsafe impl<T: Sync, B: BlockShape> Sync for GridViewMut<'_, T, B> {}
+
+impl<T, B: BlockShape> Clone for GridView<'_, T, B> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, B: BlockShape> Copy for GridView<'_, T, B> {}
+
+impl<T, B: BlockShape> BlockGrid<T, B> {
+    /// Returns a view of the rectangular window of `size` elements, `(rows, cols)`, starting at
+    /// `start`.
+    ///
+    /// # Panics
+    ///
+    /// If the window does not fit in the grid.
+    pub fn view(&self, start: Coords, size: Coords) -> GridView<'_, T, B> {
+        check_window((self.rows(), self.cols()), start, size);
+        GridView {
+            ptr: NonNull::from(self.raw()).cast(),
+            col_blocks: self.col_blocks(),
+            start,
+            rows: size.0,
+            cols: size.1,
+            grid_size: (self.rows(), self.cols()),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns a mutable view of the rectangular window of `size` elements, `(rows, cols)`,
+    /// starting at `start`.
+    ///
+    /// # Panics
+    ///
+    /// If the window does not fit in the grid.
+    pub fn view_mut(&mut self, start: Coords, size: Coords) -> GridViewMut<'_, T, B> {
+        check_window((self.rows(), self.cols()), start, size);
+        let parts = ViewParts {
+            start,
+            size,
+            ..self.view_parts_mut()
+        };
+        // SAFETY: The window fits in the grid, which is mutably borrowed
+        unsafe { GridViewMut::from_parts(parts) }
+    }
+
+    /// Splits the grid into two disjoint mutable views, above and below the `block_row`-th row
+    /// of blocks.
+    ///
+    /// The first view has all the rows of blocks before `block_row`, and the second has the
+    /// rest. Either may be empty. Both are [block-aligned][GridViewMut::is_block_aligned].
+    ///
+    /// # Panics
+    ///
+    /// If `block_row` is greater than [`row_blocks`][Self::row_blocks].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, U2};
+    ///
+    /// let mut grid = BlockGrid::<u8, U2>::new(5, 4)?;
+    /// let (mut top, mut bottom) = grid.split_at_row_block_mut(1);
+    /// assert_eq!((top.rows(), bottom.rows()), (2, 3));
+    /// top[(0, 0)] = 1;
+    /// bottom[(0, 0)] = 2;
+    /// assert_eq!((grid[(0, 0)], grid[(2, 0)]), (1, 2));
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn split_at_row_block_mut(
+        &mut self,
+        block_row: usize,
+    ) -> (GridViewMut<'_, T, B>, GridViewMut<'_, T, B>) {
+        assert!(block_row <= self.row_blocks(), "Block row out of bounds");
+        let (rows, cols) = (self.rows(), self.cols());
+        let mid = rows.min(B::HEIGHT * block_row);
+        let whole = self.view_parts_mut();
+        let top = ViewParts {
+            size: (mid, cols),
+            ..whole.clone()
+        };
+        let bottom = ViewParts {
+            start: (mid, 0),
+            size: (rows - mid, cols),
+            ..whole
+        };
+        // SAFETY: Both windows fit in the grid and don't overlap
+        unsafe {
+            (
+                GridViewMut::from_parts(top),
+                GridViewMut::from_parts(bottom),
+            )
+        }
+    }
+
+    /// Splits the grid into two disjoint mutable views, left and right of the `block_col`-th
+    /// column of blocks.
+    ///
+    /// The first view has all the columns of blocks before `block_col`, and the second has the
+    /// rest. Either may be empty. Both are [block-aligned][GridViewMut::is_block_aligned].
+    ///
+    /// # Panics
+    ///
+    /// If `block_col` is greater than [`col_blocks`][Self::col_blocks].
+    pub fn split_at_col_block_mut(
+        &mut self,
+        block_col: usize,
+    ) -> (GridViewMut<'_, T, B>, GridViewMut<'_, T, B>) {
+        assert!(block_col <= self.col_blocks(), "Block column out of bounds");
+        let (rows, cols) = (self.rows(), self.cols());
+        let mid = cols.min(B::WIDTH * block_col);
+        let whole = self.view_parts_mut();
+        let left = ViewParts {
+            size: (rows, mid),
+            ..whole.clone()
+        };
+        let right = ViewParts {
+            start: (0, mid),
+            size: (rows, cols - mid),
+            ..whole
+        };
+        // SAFETY: Both windows fit in the grid and don't overlap
+        unsafe {
+            (
+                GridViewMut::from_parts(left),
+                GridViewMut::from_parts(right),
+            )
+        }
+    }
+
+    /// Returns an iterator that splits the grid into disjoint mutable views, each of
+    /// `block_size` blocks, `(rows, cols)`.
+    ///
+    /// The views are yielded in row-major order, and the ones on the bottom and right edges may
+    /// be smaller. They are all [block-aligned][GridViewMut::is_block_aligned], and can be sent
+    /// to different threads. If you need the coordinates of each view in units of `block_size`,
+    /// follow up with a chained [`.coords()`][coords] call.
+    ///
+    /// [coords]: crate::CoordsIterator::coords()
+    ///
+    /// # Panics
+    ///
+    /// If either of `block_size` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, CoordsIterator, U2};
+    ///
+    /// let mut grid = BlockGrid::<usize, U2>::new(6, 6)?;
+    /// // Each view is 2x1 blocks, so 4x2 elements
+    /// for ((i, j), mut view) in grid.split_blocks_mut((2, 1)).coords() {
+    ///     view.each_iter_mut().for_each(|x| *x = 10 * i + j);
+    /// }
+    /// assert_eq!(grid[(3, 3)], 1);
+    /// assert_eq!(grid[(5, 4)], 12);
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn split_blocks_mut(&mut self, block_size: Coords) -> SplitBlocksMut<'_, T, B> {
+        assert!(
+            block_size.0 > 0 && block_size.1 > 0,
+            "Split size must be non-zero"
+        );
+        let parts = self.view_parts_mut();
+        // SAFETY: The grid is mutably borrowed
+        unsafe { SplitBlocksMut::new(parts, block_size) }
+    }
+
+    /// Returns the raw parts of a mutable view of the entire grid.
+    ///
+    /// Disjoint views must all be made from the same parts, since getting another pointer to
+    /// the memory invalidates earlier ones.
+    #[inline]
+    fn view_parts_mut(&mut self) -> ViewParts<T> {
+        let (grid_size, col_blocks) = ((self.rows(), self.cols()), self.col_blocks());
+        ViewParts {
+            ptr: NonNull::from(self.raw_mut()).cast(),
+            col_blocks,
+            start: (0, 0),
+            size: grid_size,
+            grid_size,
+        }
+    }
+}
+
+impl<'a, T, B: BlockShape> GridView<'a, T, B> {
+    /// Returns the number of rows.
+    #[inline]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns.
+    #[inline]
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the number of elements.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.rows * self.cols
+    }
+
+    /// Returns the coordinates of the first (top-left) element, in the underlying grid.
+    #[inline]
+    pub fn starts_at(&self) -> Coords {
+        self.start
+    }
+
+    /// Returns `true` if the given coordinates are valid.
+    #[inline]
+    pub fn contains(&self, (row, col): Coords) -> bool {
+        row < self.rows && col < self.cols
+    }
+
+    /// Returns `true` if the window is made up of entire blocks.
+    ///
+    /// This means it starts on a block boundary, and ends on one or on the edge of the grid. In
+    /// that case, [`block_iter`][Self::block_iter] can be used, and the memory order iterator is
+    /// quicker.
+    #[inline]
+    pub fn is_block_aligned(&self) -> bool {
+        is_block_aligned::<B>(self.grid_size, self.start, (self.rows, self.cols))
+    }
+
+    /// Returns a reference to the element at the given coordinates, or [`None`] if they are
+    /// out-of-bounds.
+    #[inline]
+    pub fn get(&self, coords: Coords) -> Option<&'a T> {
+        if !self.contains(coords) {
+            return None;
+        }
+        // SAFETY: `coords` is a valid index
+        Some(unsafe { self.get_unchecked(coords) })
+    }
+
+    /// Returns a reference to the element at the given coordinates, without bounds checking.
+    ///
+    /// # Safety
+    ///
+    /// Calling this method with out-of-bounds coordinates is *undefined-behaviour*.
+    #[inline]
+    pub unsafe fn get_unchecked(&self, coords: Coords) -> &'a T {
+        debug_assert!(self.contains(coords));
+        &*self.elem_ptr(coords)
+    }
+
+    /// Returns a view of a rectangular window within this one.
+    ///
+    /// # Panics
+    ///
+    /// If the window does not fit in this view.
+    pub fn view(&self, start: Coords, size: Coords) -> GridView<'a, T, B> {
+        check_window((self.rows, self.cols), start, size);
+        GridView {
+            start: (self.start.0 + start.0, self.start.1 + start.1),
+            rows: size.0,
+            cols: size.1,
+            ..*self
+        }
+    }
+
+    /// Returns an iterator over all the elements in memory order.
+    ///
+    /// Just like [`BlockGrid::each_iter`], this visits the blocks in order, and the elements
+    /// within each block in order. Only the elements in the window are visited.
+    #[inline]
+    pub fn each_iter(&self) -> ViewEachIter<'a, T, B> {
+        // SAFETY: The window is valid and borrowed for `'a`
+        unsafe { ViewEachIter::new(self.parts()) }
+    }
+
+    /// Returns an iterator over all the elements in [row-major order][row_major].
+    ///
+    /// [row_major]: https://en.wikipedia.org/wiki/Row-_and_column-major_order
+    #[inline]
+    pub fn row_major_iter(&self) -> ViewRowMajorIter<'a, T, B> {
+        ViewRowMajorIter::new(*self)
+    }
+
+    /// Returns an iterator over all blocks in the window, or [`None`] if it's not
+    /// [block-aligned][Self::is_block_aligned].
+    ///
+    /// The [`Block`][crate::Block]s yielded refer to the underlying grid, but chaining a
+    /// [`.coords()`][coords] call gives block coordinates relative to the window.
+    ///
+    /// [coords]: crate::CoordsIterator::coords()
+    #[inline]
+    pub fn block_iter(&self) -> Option<ViewBlockIter<'a, T, B>> {
+        if !self.is_block_aligned() {
+            return None;
+        }
+        // SAFETY: The window is valid, block-aligned, and borrowed for `'a`
+        Some(unsafe { ViewBlockIter::new(self.parts()) })
+    }
+
+    /// Returns the raw parts shared with the iterators.
+    #[inline]
+    fn parts(&self) -> ViewParts<T> {
+        ViewParts {
+            ptr: self.ptr,
+            col_blocks: self.col_blocks,
+            start: self.start,
+            size: (self.rows, self.cols),
+            grid_size: self.grid_size,
+        }
+    }
+
+    /// Returns a pointer to the element at the given coordinates.
+    #[inline]
+    pub(crate) unsafe fn elem_ptr(&self, (row, col): Coords) -> *mut T {
+        let coords = (self.start.0 + row, self.start.1 + col);
+        self.ptr
+            .as_ptr()
+            .add(calc_index::<B>(self.col_blocks, (), coords))
+    }
+}
+
+impl<'a, T, B: BlockShape> GridViewMut<'a, T, B> {
+    /// Constructs a `GridViewMut<'a, T, B>` from its raw parts.
+    ///
+    /// # Safety
+    ///
+    /// `parts` *must* be of a valid window that can be mutably borrowed for `'a`.
+    #[inline]
+    pub(crate) unsafe fn from_parts(parts: ViewParts<T>) -> Self {
+        Self {
+            ptr: parts.ptr,
+            col_blocks: parts.col_blocks,
+            start: parts.start,
+            rows: parts.size.0,
+            cols: parts.size.1,
+            grid_size: parts.grid_size,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the number of rows.
+    #[inline]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns.
+    #[inline]
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the number of elements.
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.rows * self.cols
+    }
+
+    /// Returns the coordinates of the first (top-left) element, in the underlying grid.
+    #[inline]
+    pub fn starts_at(&self) -> Coords {
+        self.start
+    }
+
+    /// Returns `true` if the given coordinates are valid.
+    #[inline]
+    pub fn contains(&self, (row, col): Coords) -> bool {
+        row < self.rows && col < self.cols
+    }
+
+    /// Returns `true` if the window is made up of entire blocks.
+    ///
+    /// See [`GridView::is_block_aligned`] for details.
+    #[inline]
+    pub fn is_block_aligned(&self) -> bool {
+        is_block_aligned::<B>(self.grid_size, self.start, (self.rows, self.cols))
+    }
+
+    /// Returns a reference to the element at the given coordinates, or [`None`] if they are
+    /// out-of-bounds.
+    #[inline]
+    pub fn get(&self, coords: Coords) -> Option<&T> {
+        if !self.contains(coords) {
+            return None;
+        }
+        // SAFETY: `coords` is a valid index
+        Some(unsafe { self.get_unchecked(coords) })
+    }
+
+    /// Returns a mutable reference to the element at the given coordinates, or [`None`] if they
+    /// are out-of-bounds.
+    #[inline]
+    pub fn get_mut(&mut self, coords: Coords) -> Option<&mut T> {
+        if !self.contains(coords) {
+            return None;
+        }
+        // SAFETY: `coords` is a valid index
+        Some(unsafe { self.get_unchecked_mut(coords) })
+    }
+
+    /// Returns a reference to the element at the given coordinates, without bounds checking.
+    ///
+    /// # Safety
+    ///
+    /// Calling this method with out-of-bounds coordinates is *undefined-behaviour*.
+    #[inline]
+    pub unsafe fn get_unchecked(&self, coords: Coords) -> &T {
+        debug_assert!(self.contains(coords));
+        &*self.elem_ptr(coords)
+    }
+
+    /// Returns a mutable reference to the element at the given coordinates, without bounds
+    /// checking.
+    ///
+    /// # Safety
+    ///
+    /// Calling this method with out-of-bounds coordinates is *undefined-behaviour*.
+    #[inline]
+    pub unsafe fn get_unchecked_mut(&mut self, coords: Coords) -> &mut T {
+        debug_assert!(self.contains(coords));
+        &mut *self.elem_ptr(coords)
+    }
+
+    /// Returns an immutable view of the same window.
+    #[inline]
+    pub fn as_view(&self) -> GridView<'_, T, B> {
+        GridView {
+            ptr: self.ptr,
+            col_blocks: self.col_blocks,
+            start: self.start,
+            rows: self.rows,
+            cols: self.cols,
+            grid_size: self.grid_size,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns a view of a rectangular window within this one.
+    ///
+    /// # Panics
+    ///
+    /// If the window does not fit in this view.
+    #[inline]
+    pub fn view(&self, start: Coords, size: Coords) -> GridView<'_, T, B> {
+        self.as_view().view(start, size)
+    }
+
+    /// Returns a mutable view of a rectangular window within this one.
+    ///
+    /// # Panics
+    ///
+    /// If the window does not fit in this view.
+    pub fn view_mut(&mut self, start: Coords, size: Coords) -> GridViewMut<'_, T, B> {
+        check_window((self.rows, self.cols), start, size);
+        let parts = ViewParts {
+            start: (self.start.0 + start.0, self.start.1 + start.1),
+            size,
+            ..self.parts()
+        };
+        // SAFETY: The window fits in this one, which is mutably borrowed
+        unsafe { GridViewMut::from_parts(parts) }
+    }
+
+    /// Returns an iterator over all the elements in memory order.
+    ///
+    /// See [`GridView::each_iter`] for details.
+    #[inline]
+    pub fn each_iter(&self) -> ViewEachIter<'_, T, B> {
+        // SAFETY: The window is valid and borrowed through `self`
+        unsafe { ViewEachIter::new(self.parts()) }
+    }
+
+    /// Returns a mutable iterator over all the elements in memory order.
+    ///
+    /// See [`GridView::each_iter`] for details.
+    #[inline]
+    pub fn each_iter_mut(&mut self) -> ViewEachIterMut<'_, T, B> {
+        // SAFETY: The window is valid and mutably borrowed through `self`
+        unsafe { ViewEachIterMut::new(self.parts()) }
+    }
+
+    /// Returns an iterator over all the elements in [row-major order][row_major].
+    ///
+    /// [row_major]: https://en.wikipedia.org/wiki/Row-_and_column-major_order
+    #[inline]
+    pub fn row_major_iter(&self) -> ViewRowMajorIter<'_, T, B> {
+        ViewRowMajorIter::new(self.as_view())
+    }
+
+    /// Returns a mutable iterator over all the elements in [row-major order][row_major].
+    ///
+    /// [row_major]: https://en.wikipedia.org/wiki/Row-_and_column-major_order
+    #[inline]
+    pub fn row_major_iter_mut(&mut self) -> ViewRowMajorIterMut<'_, T, B> {
+        ViewRowMajorIterMut::new(self.view_mut((0, 0), (self.rows, self.cols)))
+    }
+
+    /// Returns an iterator over all blocks in the window, or [`None`] if it's not
+    /// [block-aligned][Self::is_block_aligned].
+    ///
+    /// See [`GridView::block_iter`] for details.
+    #[inline]
+    pub fn block_iter(&self) -> Option<ViewBlockIter<'_, T, B>> {
+        if !self.is_block_aligned() {
+            return None;
+        }
+        // SAFETY: The window is valid, block-aligned, and borrowed through `self`
+        Some(unsafe { ViewBlockIter::new(self.parts()) })
+    }
+
+    /// Returns a mutable iterator over all blocks in the window, or [`None`] if it's not
+    /// [block-aligned][Self::is_block_aligned].
+    ///
+    /// See [`GridView::block_iter`] for details.
+    #[inline]
+    pub fn block_iter_mut(&mut self) -> Option<ViewBlockIterMut<'_, T, B>> {
+        if !self.is_block_aligned() {
+            return None;
+        }
+        // SAFETY: The window is valid, block-aligned, and mutably borrowed through `self`
+        Some(unsafe { ViewBlockIterMut::new(self.parts()) })
+    }
+
+    /// Returns the raw parts shared with the iterators.
+    #[inline]
+    fn parts(&self) -> ViewParts<T> {
+        ViewParts {
+            ptr: self.ptr,
+            col_blocks: self.col_blocks,
+            start: self.start,
+            size: (self.rows, self.cols),
+            grid_size: self.grid_size,
+        }
+    }
+
+    /// Returns a pointer to the element at the given coordinates.
+    #[inline]
+    pub(crate) unsafe fn elem_ptr(&self, (row, col): Coords) -> *mut T {
+        let coords = (self.start.0 + row, self.start.1 + col);
+        self.ptr
+            .as_ptr()
+            .add(calc_index::<B>(self.col_blocks, (), coords))
+    }
+}
+
+impl<T, B: BlockShape> Index<Coords> for GridView<'_, T, B> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, coords: Coords) -> &Self::Output {
+        self.get(coords).expect("Index out of bounds")
+    }
+}
+
+impl<T, B: BlockShape> Index<Coords> for GridViewMut<'_, T, B> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, coords: Coords) -> &Self::Output {
+        self.get(coords).expect("Index out of bounds")
+    }
+}
+
+impl<T, B: BlockShape> IndexMut<Coords> for GridViewMut<'_, T, B> {
+    #[inline]
+    fn index_mut(&mut self, coords: Coords) -> &mut Self::Output {
+        self.get_mut(coords).expect("Index out of bounds")
+    }
+}
+
+/// The raw parts of a view, which the view iterators are built from.
+#[derive(Debug)]
+pub(crate) struct ViewParts<T> {
+    /// Pointer to the start of the grid memory.
+    pub(crate) ptr: NonNull<T>,
+    pub(crate) col_blocks: usize,
+    /// Coordinates of the top-left element of the window, in the grid.
+    pub(crate) start: Coords,
+    /// Dimensions of the window.
+    pub(crate) size: Coords,
+    /// Dimensions of the grid.
+    pub(crate) grid_size: Coords,
+}
+
+impl<T> Clone for ViewParts<T> {
+    fn clone(&self) -> Self {
+        Self { ..*self }
+    }
+}
+
+/// Panics if a window of `size` starting at `start` does not fit within `bounds`.
+#[inline]
+fn check_window(bounds: Coords, start: Coords, size: Coords) {
+    let fits = |s: usize, n: usize, len: usize| s.checked_add(n).map_or(false, |e| e <= len);
+    assert!(
+        fits(start.0, size.0, bounds.0) && fits(start.1, size.1, bounds.1),
+        "View out of bounds"
+    );
+}
+
+/// Returns `true` if a window is made up of entire blocks of the grid.
+#[inline]
+fn is_block_aligned<B: BlockShape>(grid_size: Coords, start: Coords, size: Coords) -> bool {
+    // An empty window at the edge of the grid has no blocks at all, so it's trivially aligned
+    let aligned = |s: usize, n: usize, len: usize, dim: usize| {
+        (s % dim == 0 || s == len) && ((s + n) % dim == 0 || s + n == len)
+    };
+    aligned(start.0, size.0, grid_size.0, B::HEIGHT)
+        && aligned(start.1, size.1, grid_size.1, B::WIDTH)
+}
@@ -3,12 +3,20 @@
 //! You probably won't need to interact with this module unless you need to name one of the
 //! iterator types explicitly.
 
+use alloc::boxed::Box;
+use alloc::collections::btree_map::{self, BTreeMap};
 use core::iter::FusedIterator;
 use core::marker::PhantomData;
+use core::ops::{Index, IndexMut, Range};
 use core::ptr::NonNull;
-use core::slice::{ChunksExact, ChunksExactMut, Iter, IterMut};
+use core::slice::{self, ChunksExact, ChunksExactMut, Iter, IterMut};
 
-use crate::{Block, BlockDim, BlockGrid, BlockMut, Coords};
+use crate::block_grid::{block_extent, calc_blocks, calc_index};
+use crate::grid_view::ViewParts;
+use crate::{
+    Block, BlockGrid, BlockMut, BlockShape, Blocked, Coords, Dims, DynDim, GridView, GridViewMut,
+    Layout, SignedCoords,
+};
 
 /// Provides an interface for iterators that can also yield 2D coordinates.
 ///
@@ -25,10 +33,40 @@ pub trait CoordsIterator: Iterator + private::Sealed {
     #[doc(hidden)]
     fn current_coords(&self) -> Coords;
 
+    /// Returns the coordinates of the item *to be* yielded next from the back.
+    ///
+    /// Like [`current_coords`][Self::current_coords], this is an implementation detail of
+    /// [`WithCoordsIter`]. The value is meaningless once the iterator is empty.
+    #[doc(hidden)]
+    fn current_back_coords(&self) -> Coords;
+
     /// Returns an iterator adapter that also gives coordinates as well as the next value.
     ///
     /// The iterator returned yields 2-tuples `(coords, elem)`, where `coords` is the coordinates
     /// of the next element `elem`. This is essentially a 2D version of [`Iterator::enumerate`].
+    ///
+    /// The adapter is double-ended, so the coordinates are also correct when iterating from the
+    /// back. To iterate in reverse with coordinates, call [`rev`][Iterator::rev] *after* this,
+    /// as in `.coords().rev()`. The other way around isn't possible, since [`Rev`] doesn't give
+    /// access to the iterator it wraps.
+    ///
+    /// [`Rev`]: core::iter::Rev
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, CoordsIterator, U2};
+    ///
+    /// let data: Vec<_> = (0..(3 * 3)).collect();
+    /// let grid = BlockGrid::<usize, U2>::from_row_major(3, 3, &data)?;
+    ///
+    /// let mut iter = grid.each_iter().coords().rev();
+    /// assert_eq!(iter.next(), Some(((2, 2), &8)));
+    /// assert_eq!(iter.next(), Some(((2, 1), &7)));
+    /// assert_eq!(iter.next(), Some(((2, 0), &6)));
+    /// assert_eq!(iter.next(), Some(((1, 2), &5)));
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
     fn coords(self) -> WithCoordsIter<Self>
     where
         Self: Sized,
@@ -41,34 +79,31 @@ pub trait CoordsIterator: Iterator + private::Sealed {
 ///
 /// Created by the [`BlockGrid::each_iter`] method.
 #[derive(Clone, Debug)]
-pub struct EachIter<'a, T, B: BlockDim> {
-    row: usize,
-    col: usize,
-    cols: usize,
+pub struct EachIter<'a, T, B: Dims> {
+    coords: MemOrderCoords<B>,
+    len: usize,
     iter: Iter<'a, T>,
-    _phantom: PhantomData<B>,
 }
 
 /// Mutable iterator in memory order.
 ///
 /// Created by the [`BlockGrid::each_iter_mut`] method.
 #[derive(Debug)]
-pub struct EachIterMut<'a, T, B: BlockDim> {
-    row: usize,
-    col: usize,
-    cols: usize,
+pub struct EachIterMut<'a, T, B: Dims> {
+    coords: MemOrderCoords<B>,
+    len: usize,
     iter: IterMut<'a, T>,
-    _phantom: PhantomData<B>,
 }
 
 /// Immutable iterator over entire blocks.
 ///
 /// Created by the [`BlockGrid::block_iter`] method.
 #[derive(Clone, Debug)]
-pub struct BlockIter<'a, T, B: BlockDim> {
-    block_row: usize,
-    block_col: usize,
-    col_blocks: usize,
+pub struct BlockIter<'a, T, B: Dims> {
+    span: RowMajorSpan,
+    rows: usize,
+    cols: usize,
+    dims: B::Value,
     chunks: ChunksExact<'a, T>,
     _phantom: PhantomData<B>,
 }
@@ -77,10 +112,11 @@ pub struct BlockIter<'a, T, B: BlockDim> {
 ///
 /// Created by the [`BlockGrid::block_iter_mut`] method.
 #[derive(Debug)]
-pub struct BlockIterMut<'a, T, B: BlockDim> {
-    block_row: usize,
-    block_col: usize,
-    col_blocks: usize,
+pub struct BlockIterMut<'a, T, B: Dims> {
+    span: RowMajorSpan,
+    rows: usize,
+    cols: usize,
+    dims: B::Value,
     chunks: ChunksExactMut<'a, T>,
     _phantom: PhantomData<B>,
 }
@@ -89,21 +125,424 @@ pub struct BlockIterMut<'a, T, B: BlockDim> {
 ///
 /// Created by the [`BlockGrid::row_major_iter`] method.
 #[derive(Clone, Debug)]
-pub struct RowMajorIter<'a, T, B: BlockDim> {
-    row: usize,
-    col: usize,
-    grid: &'a BlockGrid<T, B>,
+pub struct RowMajorIter<'a, T, B: Dims, L: Layout = Blocked> {
+    span: RowMajorSpan,
+    blocks: Coords,
+    dims: B::Value,
+    arr: &'a [T],
+    _phantom: PhantomData<(B, L)>,
 }
 
 /// Mutable iterator in row-major order.
 ///
 /// Created by the [`BlockGrid::row_major_iter_mut`] method.
 #[derive(Debug)]
-pub struct RowMajorIterMut<'a, T, B: BlockDim> {
+pub struct RowMajorIterMut<'a, T, B: Dims, L: Layout = Blocked> {
+    span: RowMajorSpan,
+    blocks: Coords,
+    dims: B::Value,
+    ptr: NonNull<T>,
+    _phantom: PhantomData<(&'a mut T, B, L)>,
+}
+
+/// Immutable iterator in column-major order.
+///
+/// Created by the [`BlockGrid::col_major_iter`] method.
+#[derive(Clone, Debug)]
+pub struct ColMajorIter<'a, T, B: BlockShape, L: Layout = Blocked> {
+    span: RowMajorSpan,
+    grid: &'a BlockGrid<T, B, L>,
+}
+
+/// Mutable iterator in column-major order.
+///
+/// Created by the [`BlockGrid::col_major_iter_mut`] method.
+#[derive(Debug)]
+pub struct ColMajorIterMut<'a, T, B: BlockShape, L: Layout = Blocked> {
+    span: RowMajorSpan,
+    blocks: Coords,
+    ptr: NonNull<T>,
+    _phantom: PhantomData<(&'a mut T, B, L)>,
+}
+
+/// Immutable iterator along a Morton, or Z-order, curve.
+///
+/// Created by the [`BlockGrid::morton_iter`] method.
+#[derive(Clone, Debug)]
+pub struct MortonIter<'a, T, B: BlockShape, L: Layout = Blocked> {
+    span: CurveSpan,
+    grid: &'a BlockGrid<T, B, L>,
+}
+
+/// Mutable iterator along a Morton, or Z-order, curve.
+///
+/// Created by the [`BlockGrid::morton_iter_mut`] method.
+#[derive(Debug)]
+pub struct MortonIterMut<'a, T, B: BlockShape, L: Layout = Blocked> {
+    span: CurveSpan,
+    blocks: Coords,
+    ptr: NonNull<T>,
+    _phantom: PhantomData<(&'a mut T, B, L)>,
+}
+
+/// Immutable iterator along a Hilbert curve.
+///
+/// Created by the [`BlockGrid::hilbert_iter`] method.
+#[derive(Clone, Debug)]
+pub struct HilbertIter<'a, T, B: BlockShape, L: Layout = Blocked> {
+    span: CurveSpan,
+    grid: &'a BlockGrid<T, B, L>,
+}
+
+/// Mutable iterator along a Hilbert curve.
+///
+/// Created by the [`BlockGrid::hilbert_iter_mut`] method.
+#[derive(Debug)]
+pub struct HilbertIterMut<'a, T, B: BlockShape, L: Layout = Blocked> {
+    span: CurveSpan,
+    blocks: Coords,
+    ptr: NonNull<T>,
+    _phantom: PhantomData<(&'a mut T, B, L)>,
+}
+
+/// Immutable iterator in memory order, for a [`DynBlockGrid`].
+///
+/// Created by the [`DynBlockGrid::each_iter`] method.
+///
+/// [`DynBlockGrid`]: crate::DynBlockGrid
+/// [`DynBlockGrid::each_iter`]: crate::DynBlockGrid::each_iter
+pub type DynEachIter<'a, T> = EachIter<'a, T, DynDim>;
+
+/// Mutable iterator in memory order, for a [`DynBlockGrid`].
+///
+/// Created by the [`DynBlockGrid::each_iter_mut`] method.
+///
+/// [`DynBlockGrid`]: crate::DynBlockGrid
+/// [`DynBlockGrid::each_iter_mut`]: crate::DynBlockGrid::each_iter_mut
+pub type DynEachIterMut<'a, T> = EachIterMut<'a, T, DynDim>;
+
+/// Immutable iterator over entire blocks, for a [`DynBlockGrid`].
+///
+/// Created by the [`DynBlockGrid::block_iter`] method.
+///
+/// [`DynBlockGrid`]: crate::DynBlockGrid
+/// [`DynBlockGrid::block_iter`]: crate::DynBlockGrid::block_iter
+pub type DynBlockIter<'a, T> = BlockIter<'a, T, DynDim>;
+
+/// Mutable iterator over entire blocks, for a [`DynBlockGrid`].
+///
+/// Created by the [`DynBlockGrid::block_iter_mut`] method.
+///
+/// [`DynBlockGrid`]: crate::DynBlockGrid
+/// [`DynBlockGrid::block_iter_mut`]: crate::DynBlockGrid::block_iter_mut
+pub type DynBlockIterMut<'a, T> = BlockIterMut<'a, T, DynDim>;
+
+/// Immutable iterator in row-major order, for a [`DynBlockGrid`].
+///
+/// Created by the [`DynBlockGrid::row_major_iter`] method.
+///
+/// [`DynBlockGrid`]: crate::DynBlockGrid
+/// [`DynBlockGrid::row_major_iter`]: crate::DynBlockGrid::row_major_iter
+pub type DynRowMajorIter<'a, T> = RowMajorIter<'a, T, DynDim>;
+
+/// Mutable iterator in row-major order, for a [`DynBlockGrid`].
+///
+/// Created by the [`DynBlockGrid::row_major_iter_mut`] method.
+///
+/// [`DynBlockGrid`]: crate::DynBlockGrid
+/// [`DynBlockGrid::row_major_iter_mut`]: crate::DynBlockGrid::row_major_iter_mut
+pub type DynRowMajorIterMut<'a, T> = RowMajorIterMut<'a, T, DynDim>;
+
+/// Immutable iterator in memory order, over a [`GridView`] or [`GridViewMut`].
+///
+/// Created by the [`GridView::each_iter`] and [`GridViewMut::each_iter`] methods.
+#[derive(Clone, Debug)]
+pub struct ViewEachIter<'a, T, B: BlockShape> {
+    runs: ViewRuns<B>,
+    ptr: NonNull<T>,
+    front: ViewRun<Iter<'a, T>>,
+    back: ViewRun<Iter<'a, T>>,
+    start: Coords,
+    len: usize,
+}
+
+/// Mutable iterator in memory order, over a [`GridViewMut`].
+///
+/// Created by the [`GridViewMut::each_iter_mut`] method.
+#[derive(Debug)]
+pub struct ViewEachIterMut<'a, T, B: BlockShape> {
+    runs: ViewRuns<B>,
+    ptr: NonNull<T>,
+    front: ViewRun<IterMut<'a, T>>,
+    back: ViewRun<IterMut<'a, T>>,
+    start: Coords,
+    len: usize,
+}
+
+/// Immutable iterator over entire blocks, in a block-aligned [`GridView`] or [`GridViewMut`].
+///
+/// Created by the [`GridView::block_iter`] and [`GridViewMut::block_iter`] methods.
+#[derive(Clone, Debug)]
+pub struct ViewBlockIter<'a, T, B: BlockShape> {
+    parts: ViewParts<T>,
+    span: RowMajorSpan,
+    _phantom: PhantomData<(&'a T, B)>,
+}
+
+/// Mutable iterator over entire blocks, in a block-aligned [`GridViewMut`].
+///
+/// Created by the [`GridViewMut::block_iter_mut`] method.
+#[derive(Debug)]
+pub struct ViewBlockIterMut<'a, T, B: BlockShape> {
+    parts: ViewParts<T>,
+    span: RowMajorSpan,
+    _phantom: PhantomData<(&'a mut T, B)>,
+}
+
+/// Immutable iterator in row-major order, over a [`GridView`] or [`GridViewMut`].
+///
+/// Created by the [`GridView::row_major_iter`] and [`GridViewMut::row_major_iter`] methods.
+#[derive(Clone, Debug)]
+pub struct ViewRowMajorIter<'a, T, B: BlockShape> {
+    span: RowMajorSpan,
+    view: GridView<'a, T, B>,
+}
+
+/// Mutable iterator in row-major order, over a [`GridViewMut`].
+///
+/// Created by the [`GridViewMut::row_major_iter_mut`] method.
+#[derive(Debug)]
+pub struct ViewRowMajorIterMut<'a, T, B: BlockShape> {
+    span: RowMajorSpan,
+    view: GridViewMut<'a, T, B>,
+}
+
+/// Iterator over disjoint mutable views of a grid, each made of the same number of blocks.
+///
+/// Created by the [`BlockGrid::split_blocks_mut`] method.
+#[derive(Debug)]
+pub struct SplitBlocksMut<'a, T, B: BlockShape> {
+    parts: ViewParts<T>,
+    size: Coords,
+    span: RowMajorSpan,
+    _phantom: PhantomData<(&'a mut T, B)>,
+}
+
+/// Immutable iterator over the allocated blocks of a [`ChunkedGrid`], yielding
+/// `(block_coords, block)` pairs.
+///
+/// Created by the [`ChunkedGrid::block_iter`] method.
+///
+/// [`ChunkedGrid`]: crate::ChunkedGrid
+/// [`ChunkedGrid::block_iter`]: crate::ChunkedGrid::block_iter
+#[derive(Clone, Debug)]
+pub struct ChunkedBlockIter<'a, T, B: BlockShape> {
+    iter: btree_map::Iter<'a, SignedCoords, Box<[T]>>,
+    _phantom: PhantomData<B>,
+}
+
+/// Mutable iterator over the allocated blocks of a [`ChunkedGrid`], yielding
+/// `(block_coords, block)` pairs.
+///
+/// Created by the [`ChunkedGrid::block_iter_mut`] method.
+///
+/// [`ChunkedGrid`]: crate::ChunkedGrid
+/// [`ChunkedGrid::block_iter_mut`]: crate::ChunkedGrid::block_iter_mut
+#[derive(Debug)]
+pub struct ChunkedBlockIterMut<'a, T, B: BlockShape> {
+    iter: btree_map::IterMut<'a, SignedCoords, Box<[T]>>,
+    _phantom: PhantomData<B>,
+}
+
+/// Immutable view of a single row or column, which is also an iterator over it.
+///
+/// Created by the [`BlockGrid::row`] and [`BlockGrid::col`] methods, or by iterating over
+/// [`RowsIter`] or [`ColsIter`]. As an iterator, the elements are yielded in order along the
+/// line, from either end. Indexing is relative to the elements not yet yielded, so `line[0]` is
+/// always the next element.
+#[derive(Clone, Debug)]
+pub struct Line<'a, T> {
+    layout: LineLayout,
+    front: usize,
+    back: usize,
+    arr: &'a [T],
+}
+
+/// Mutable view of a single row or column, which is also an iterator over it.
+///
+/// Created by the [`BlockGrid::row_mut`] and [`BlockGrid::col_mut`] methods, or by iterating
+/// over [`RowsIterMut`] or [`ColsIterMut`]. See [`Line`] for details.
+#[derive(Debug)]
+pub struct LineMut<'a, T> {
+    layout: LineLayout,
+    front: usize,
+    back: usize,
+    ptr: NonNull<T>,
+    _phantom: PhantomData<&'a mut T>,
+}
+
+/// Immutable iterator over the rows, yielding [`Line`]s.
+///
+/// Created by the [`BlockGrid::rows_iter`] method.
+#[derive(Clone, Debug)]
+pub struct RowsIter<'a, T, B: BlockShape> {
+    lines: Range<usize>,
+    grid: &'a BlockGrid<T, B>,
+}
+
+/// Mutable iterator over the rows, yielding [`LineMut`]s.
+///
+/// Created by the [`BlockGrid::rows_iter_mut`] method.
+#[derive(Debug)]
+pub struct RowsIterMut<'a, T, B: BlockShape> {
+    lines: Range<usize>,
+    col_blocks: usize,
+    len: usize,
+    ptr: NonNull<T>,
+    _phantom: PhantomData<(&'a mut T, B)>,
+}
+
+/// Immutable iterator over the columns, yielding [`Line`]s.
+///
+/// Created by the [`BlockGrid::cols_iter`] method.
+#[derive(Clone, Debug)]
+pub struct ColsIter<'a, T, B: BlockShape> {
+    lines: Range<usize>,
+    grid: &'a BlockGrid<T, B>,
+}
+
+/// Mutable iterator over the columns, yielding [`LineMut`]s.
+///
+/// Created by the [`BlockGrid::cols_iter_mut`] method.
+#[derive(Debug)]
+pub struct ColsIterMut<'a, T, B: BlockShape> {
+    lines: Range<usize>,
+    col_blocks: usize,
+    len: usize,
+    ptr: NonNull<T>,
+    _phantom: PhantomData<(&'a mut T, B)>,
+}
+
+// SAFETY: These iterators are the same as iterating over `&[T]` or `&mut [T]`
+unsafe impl<T: Sync, B: BlockShape> Send for ViewEachIter<'_, T, B> {}
+unsafe impl<T: Sync, B: BlockShape> Sync for ViewEachIter<'_, T, B> {}
+unsafe impl<T: Send, B: BlockShape> Send for ViewEachIterMut<'_, T, B> {}
+unsafe impl<T: Sync, B: BlockShape> Sync for ViewEachIterMut<'_, T, B> {}
+unsafe impl<T: Sync, B: BlockShape> Send for ViewBlockIter<'_, T, B> {}
+unsafe impl<T: Sync, B: BlockShape> Sync for ViewBlockIter<'_, T, B> {}
+unsafe impl<T: Send, B: BlockShape> Send for ViewBlockIterMut<'_, T, B> {}
+unsafe impl<T: Sync, B: BlockShape> Sync for ViewBlockIterMut<'_, T, B> {}
+unsafe impl<T: Send, B: BlockShape> Send for SplitBlocksMut<'_, T, B> {}
+unsafe impl<T: Sync, B: BlockShape> Sync for SplitBlocksMut<'_, T, B> {}
+unsafe impl<T: Send, B: Dims, L: Layout> Send for RowMajorIterMut<'_, T, B, L> {}
+unsafe impl<T: Sync, B: Dims, L: Layout> Sync for RowMajorIterMut<'_, T, B, L> {}
+unsafe impl<T: Send, B: BlockShape, L: Layout> Send for ColMajorIterMut<'_, T, B, L> {}
+unsafe impl<T: Sync, B: BlockShape, L: Layout> Sync for ColMajorIterMut<'_, T, B, L> {}
+unsafe impl<T: Send, B: BlockShape, L: Layout> Send for MortonIterMut<'_, T, B, L> {}
+unsafe impl<T: Sync, B: BlockShape, L: Layout> Sync for MortonIterMut<'_, T, B, L> {}
+unsafe impl<T: Send, B: BlockShape, L: Layout> Send for HilbertIterMut<'_, T, B, L> {}
+unsafe impl<T: Sync, B: BlockShape, L: Layout> Sync for HilbertIterMut<'_, T, B, L> {}
+unsafe impl<T: Send> Send for LineMut<'_, T> {}
+unsafe impl<T: Sync> Sync for LineMut<'_, T> {}
+unsafe impl<T: Send, B: BlockShape> Send for RowsIterMut<'_, T, B> {}
+unsafe impl<T: Sync, B: BlockShape> Sync for RowsIterMut<'_, T, B> {}
+unsafe impl<T: Send, B: BlockShape> Send for ColsIterMut<'_, T, B> {}
+unsafe impl<T: Sync, B: BlockShape> Sync for ColsIterMut<'_, T, B> {}
+
+/// Walks the contiguous runs of memory in a window, in memory order, from either end.
+///
+/// The front is at `row` in block `(block_row, block_col)`, and the back is just before
+/// `back_row` in block `(back_block_row, back_block_col)`. Once they meet in the same block,
+/// there are no runs left.
+#[derive(Clone, Debug)]
+struct ViewRuns<B: BlockShape> {
+    start: Coords,
+    end: Coords,
+    col_blocks: usize,
+    block_row: usize,
+    block_col: usize,
+    row: usize,
+    back_block_row: usize,
+    back_block_col: usize,
+    back_row: usize,
+    _phantom: PhantomData<B>,
+}
+
+/// A single run of memory in a window, with the coordinates of the element at its front.
+#[derive(Clone, Debug)]
+struct ViewRun<I> {
+    iter: I,
+    row: usize,
+    col: usize,
+    cols: Range<usize>,
+}
+
+/// Tracks both ends of an iterator over a `rows` by `cols` space, in row-major order.
+///
+/// The front coordinates are stepped along, while the back is only a linear index, since it's
+/// converted to coordinates much less often.
+#[derive(Clone, Debug)]
+struct RowMajorSpan {
+    row: usize,
+    col: usize,
+    front: usize,
+    back: usize,
+    cols: usize,
+}
+
+/// Where the elements of a single row or column are in memory.
+///
+/// The `k`-th element is in the `k >> shift` block along the line, which are `outer` apart in
+/// memory, and then `inner` apart within the block.
+#[derive(Clone, Copy, Debug)]
+struct LineLayout {
+    base: usize,
+    shift: usize,
+    outer: usize,
+    inner: usize,
+    /// The row or column index of the line
+    index: usize,
+    is_col: bool,
+}
+
+/// A space-filling curve to iterate along.
+#[derive(Clone, Copy, Debug)]
+enum Curve {
+    Morton,
+    Hilbert,
+}
+
+/// Tracks both ends of an iterator along a space-filling curve.
+///
+/// The grid is covered by square tiles along its longer side, each the smallest power-of-two
+/// that covers the shorter side, and the curve goes through each tile in turn. Positions outside
+/// the grid are skipped over an entire aligned square at a time, since each is a contiguous
+/// range of the curve.
+#[derive(Clone, Debug)]
+struct CurveSpan {
+    curve: Curve,
+    rows: usize,
+    cols: usize,
+    /// The tiles are `1 << shift` wide
+    shift: u32,
+    front: usize,
+    back: usize,
+    front_coords: Coords,
+    back_coords: Coords,
+    len: usize,
+}
+
+/// Tracks the coordinates of the elements at both ends of a memory order iterator.
+#[derive(Clone, Debug)]
+struct MemOrderCoords<B: Dims> {
     row: usize,
     col: usize,
-    grid: NonNull<BlockGrid<T, B>>,
-    _phantom: PhantomData<&'a mut BlockGrid<T, B>>,
+    back_row: usize,
+    back_col: usize,
+    rows: usize,
+    cols: usize,
+    padded_cols: usize,
+    dims: B::Value,
 }
 
 /// An iterator adapter that yields the coordinates and the element.
@@ -115,276 +554,2429 @@ pub struct WithCoordsIter<I> {
     iter: I,
 }
 
-impl<'a, T, B: BlockDim> EachIter<'a, T, B> {
-    pub(crate) fn new(grid: &'a BlockGrid<T, B>) -> Self {
+impl<B: Dims> MemOrderCoords<B> {
+    fn new(rows: usize, cols: usize, col_blocks: usize, dims: B::Value) -> Self {
+        Self {
+            row: 0,
+            col: 0,
+            back_row: rows.saturating_sub(1),
+            back_col: cols.saturating_sub(1),
+            rows,
+            cols,
+            padded_cols: B::width(dims) * col_blocks,
+            dims,
+        }
+    }
+
+    /// Returns `true` if `(row, col)` is not padding.
+    #[inline]
+    fn is_valid(&self, row: usize, col: usize) -> bool {
+        row < self.rows && col < self.cols
+    }
+
+    /// Steps forward to the coordinates of the next element in memory.
+    #[inline]
+    fn step(&mut self) {
+        let (height, width) = (B::height(self.dims), B::width(self.dims));
+        // Block dimensions are powers of two, so masking is the same as modulo
+        self.col += 1;
+        if self.col & (width - 1) == 0 {
+            self.row += 1;
+            if self.row & (height - 1) == 0 {
+                if self.col == self.padded_cols {
+                    self.col = 0;
+                } else {
+                    self.row -= height;
+                }
+            } else {
+                self.col -= width;
+            }
+        }
+    }
+
+    /// Steps the back backward to the coordinates of the previous element in memory.
+    #[inline]
+    fn step_back(&mut self) {
+        let (height, width) = (B::height(self.dims), B::width(self.dims));
+        if self.back_col & (width - 1) != 0 {
+            self.back_col -= 1;
+        } else if self.back_row & (height - 1) != 0 {
+            // Onto the end of the previous row in the same block
+            self.back_row -= 1;
+            self.back_col += width - 1;
+        } else if self.back_col == 0 {
+            // Onto the last element of the previous row of blocks
+            self.back_row -= 1;
+            self.back_col = self.padded_cols - 1;
+        } else {
+            // Onto the last element of the block to the left
+            self.back_row += height - 1;
+            self.back_col -= 1;
+        }
+    }
+
+    /// Returns the memory index of the element at `(row, col)`.
+    #[inline]
+    fn index_of(&self, row: usize, col: usize) -> usize {
+        let col_blocks = self.padded_cols / B::width(self.dims);
+        calc_index::<B>(col_blocks, self.dims, (row, col))
+    }
+
+    /// Returns the coordinates of the element at memory index `ind`.
+    #[inline]
+    fn coords_of(&self, ind: usize) -> Coords {
+        let (height, width) = (B::height(self.dims), B::width(self.dims));
+        let (block, sub) = (ind / (height * width), ind & (height * width - 1));
+        let col_blocks = self.padded_cols / width;
+        (
+            height * (block / col_blocks) + sub / width,
+            width * (block % col_blocks) + (sub & (width - 1)),
+        )
+    }
+
+    /// Moves the front forward by `n` elements, assuming there's no padding in the way.
+    #[inline]
+    fn skip(&mut self, n: usize) {
+        let (row, col) = self.coords_of(self.index_of(self.row, self.col) + n);
+        self.row = row;
+        self.col = col;
+    }
+
+    /// Moves the back backward by `n` elements, assuming there's no padding in the way.
+    #[inline]
+    fn skip_back(&mut self, n: usize) {
+        let (row, col) = self.coords_of(self.index_of(self.back_row, self.back_col) - n);
+        self.back_row = row;
+        self.back_col = col;
+    }
+}
+
+impl RowMajorSpan {
+    fn new((rows, cols): Coords) -> Self {
+        Self {
+            row: 0,
+            col: 0,
+            front: 0,
+            back: rows * cols,
+            cols,
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.back - self.front
+    }
+
+    #[inline]
+    fn front_coords(&self) -> Coords {
+        (self.row, self.col)
+    }
+
+    #[inline]
+    fn back_coords(&self) -> Coords {
+        match self.back.checked_sub(1) {
+            Some(k) if self.back > self.front => (k / self.cols, k % self.cols),
+            _ => (self.row, self.col),
+        }
+    }
+
+    /// Takes the coordinates at the front, if any are left.
+    #[inline]
+    fn next(&mut self) -> Option<Coords> {
+        if self.front == self.back {
+            return None;
+        }
+        let coords = (self.row, self.col);
+        self.front += 1;
+        self.col += 1;
+        if self.col == self.cols {
+            self.row += 1;
+            self.col = 0;
+        }
+        Some(coords)
+    }
+
+    /// Takes the coordinates at the back, if any are left.
+    #[inline]
+    fn next_back(&mut self) -> Option<Coords> {
+        if self.front == self.back {
+            return None;
+        }
+        let coords = self.back_coords();
+        self.back -= 1;
+        Some(coords)
+    }
+
+    /// Skips up to `n` coordinates from the front.
+    #[inline]
+    fn skip(&mut self, n: usize) {
+        let n = n.min(self.len());
+        if n > 0 {
+            self.front += n;
+            self.row = self.front / self.cols;
+            self.col = self.front % self.cols;
+        }
+    }
+
+    /// Skips up to `n` coordinates from the back.
+    #[inline]
+    fn skip_back(&mut self, n: usize) {
+        self.back -= n.min(self.len());
+    }
+}
+
+impl<'a, T, B: Dims> EachIter<'a, T, B> {
+    /// Constructs an iterator over the memory `arr` of a grid of `rows` and `cols`.
+    pub(crate) fn new(
+        (rows, cols): Coords,
+        col_blocks: usize,
+        dims: B::Value,
+        arr: &'a [T],
+    ) -> Self {
+        let coords = MemOrderCoords::<B>::new(rows, cols, col_blocks, dims);
+        // Leave off any padding after the last element, so the back always starts out valid
+        let last = coords.index_of(coords.back_row, coords.back_col);
+        Self {
+            coords,
+            len: rows * cols,
+            iter: arr[..=last].iter(),
+        }
+    }
+}
+
+impl<T, B: Dims> CoordsIterator for EachIter<'_, T, B> {
+    #[inline]
+    fn current_coords(&self) -> Coords {
+        (self.coords.row, self.coords.col)
+    }
+
+    #[inline]
+    fn current_back_coords(&self) -> Coords {
+        (self.coords.back_row, self.coords.back_col)
+    }
+}
+
+impl<'a, T, B: Dims> Iterator for EachIter<'a, T, B> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let x = self.iter.next();
+        self.len -= 1;
+        self.coords.step();
+        // Skip over padding, which only exists if there are more elements in memory than valid
+        if self.len != self.iter.len() {
+            while self.len > 0 && !self.coords.is_valid(self.coords.row, self.coords.col) {
+                self.iter.next();
+                self.coords.step();
+            }
+        }
+        x
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        // Without any padding left, the coordinates can be found directly from the memory index
+        if n > 0 && n < self.len && self.len == self.iter.len() {
+            self.iter.nth(n - 1);
+            self.len -= n;
+            self.coords.skip(n);
+        } else {
+            for _ in 0..n {
+                self.next()?;
+            }
+        }
+        self.next()
+    }
+}
+
+impl<T, B: Dims> DoubleEndedIterator for EachIter<'_, T, B> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let x = self.iter.next_back();
+        self.len -= 1;
+        if self.len > 0 {
+            self.coords.step_back();
+            if self.len != self.iter.len() {
+                while !self
+                    .coords
+                    .is_valid(self.coords.back_row, self.coords.back_col)
+                {
+                    self.iter.next_back();
+                    self.coords.step_back();
+                }
+            }
+        }
+        x
+    }
+
+    #[inline]
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        if n > 0 && n < self.len && self.len == self.iter.len() {
+            self.iter.nth_back(n - 1);
+            self.len -= n;
+            self.coords.skip_back(n);
+        } else {
+            for _ in 0..n {
+                self.next_back()?;
+            }
+        }
+        self.next_back()
+    }
+}
+
+impl<T, B: Dims> ExactSizeIterator for EachIter<'_, T, B> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<T, B: Dims> FusedIterator for EachIter<'_, T, B> {}
+
+impl<'a, T, B: Dims> EachIterMut<'a, T, B> {
+    /// Constructs an iterator over the memory `arr` of a grid of `rows` and `cols`.
+    pub(crate) fn new(
+        (rows, cols): Coords,
+        col_blocks: usize,
+        dims: B::Value,
+        arr: &'a mut [T],
+    ) -> Self {
+        let coords = MemOrderCoords::<B>::new(rows, cols, col_blocks, dims);
+        // Leave off any padding after the last element, so the back always starts out valid
+        let last = coords.index_of(coords.back_row, coords.back_col);
+        Self {
+            coords,
+            len: rows * cols,
+            iter: arr[..=last].iter_mut(),
+        }
+    }
+}
+
+impl<T, B: Dims> CoordsIterator for EachIterMut<'_, T, B> {
+    #[inline]
+    fn current_coords(&self) -> Coords {
+        (self.coords.row, self.coords.col)
+    }
+
+    #[inline]
+    fn current_back_coords(&self) -> Coords {
+        (self.coords.back_row, self.coords.back_col)
+    }
+}
+
+impl<'a, T, B: Dims> Iterator for EachIterMut<'a, T, B> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let x = self.iter.next();
+        self.len -= 1;
+        self.coords.step();
+        // Skip over padding, which only exists if there are more elements in memory than valid
+        if self.len != self.iter.len() {
+            while self.len > 0 && !self.coords.is_valid(self.coords.row, self.coords.col) {
+                self.iter.next();
+                self.coords.step();
+            }
+        }
+        x
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        // Without any padding left, the coordinates can be found directly from the memory index
+        if n > 0 && n < self.len && self.len == self.iter.len() {
+            self.iter.nth(n - 1);
+            self.len -= n;
+            self.coords.skip(n);
+        } else {
+            for _ in 0..n {
+                self.next()?;
+            }
+        }
+        self.next()
+    }
+}
+
+impl<T, B: Dims> DoubleEndedIterator for EachIterMut<'_, T, B> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let x = self.iter.next_back();
+        self.len -= 1;
+        if self.len > 0 {
+            self.coords.step_back();
+            if self.len != self.iter.len() {
+                while !self
+                    .coords
+                    .is_valid(self.coords.back_row, self.coords.back_col)
+                {
+                    self.iter.next_back();
+                    self.coords.step_back();
+                }
+            }
+        }
+        x
+    }
+
+    #[inline]
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        if n > 0 && n < self.len && self.len == self.iter.len() {
+            self.iter.nth_back(n - 1);
+            self.len -= n;
+            self.coords.skip_back(n);
+        } else {
+            for _ in 0..n {
+                self.next_back()?;
+            }
+        }
+        self.next_back()
+    }
+}
+
+impl<T, B: Dims> ExactSizeIterator for EachIterMut<'_, T, B> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<T, B: Dims> FusedIterator for EachIterMut<'_, T, B> {}
+
+impl<'a, T, B: Dims> BlockIter<'a, T, B> {
+    /// Constructs an iterator over the memory `arr` of a grid of `rows` and `cols`.
+    pub(crate) fn new((rows, cols): Coords, blocks: Coords, dims: B::Value, arr: &'a [T]) -> Self {
+        Self {
+            span: RowMajorSpan::new(blocks),
+            rows,
+            cols,
+            dims,
+            chunks: arr.chunks_exact(B::area(dims)),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the block at `coords`, made from its `chunk` of memory.
+    #[inline]
+    fn block(&self, coords: Coords, chunk: &'a [T]) -> Block<'a, T, B> {
+        let extent = block_extent::<B>(self.rows, self.cols, self.dims, coords);
+        // SAFETY: `self.chunks` gives slices of exactly the length of a block
+        unsafe { Block::new(coords, extent, self.dims, chunk) }
+    }
+}
+
+impl<T, B: Dims> CoordsIterator for BlockIter<'_, T, B> {
+    #[inline]
+    fn current_coords(&self) -> Coords {
+        self.span.front_coords()
+    }
+
+    #[inline]
+    fn current_back_coords(&self) -> Coords {
+        self.span.back_coords()
+    }
+}
+
+impl<'a, T, B: Dims> Iterator for BlockIter<'a, T, B> {
+    type Item = Block<'a, T, B>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunk = self.chunks.next()?;
+        let coords = self.span.next()?;
+        Some(self.block(coords, chunk))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.chunks.count()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.span.skip(n);
+        let chunk = self.chunks.nth(n)?;
+        let coords = self.span.next()?;
+        Some(self.block(coords, chunk))
+    }
+}
+
+impl<T, B: Dims> DoubleEndedIterator for BlockIter<'_, T, B> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let chunk = self.chunks.next_back()?;
+        let coords = self.span.next_back()?;
+        Some(self.block(coords, chunk))
+    }
+
+    #[inline]
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.span.skip_back(n);
+        let chunk = self.chunks.nth_back(n)?;
+        let coords = self.span.next_back()?;
+        Some(self.block(coords, chunk))
+    }
+}
+
+impl<T, B: Dims> ExactSizeIterator for BlockIter<'_, T, B> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.chunks.len()
+    }
+}
+
+impl<T, B: Dims> FusedIterator for BlockIter<'_, T, B> {}
+
+impl<'a, T, B: Dims> BlockIterMut<'a, T, B> {
+    /// Constructs an iterator over the memory `arr` of a grid of `rows` and `cols`.
+    pub(crate) fn new(
+        (rows, cols): Coords,
+        blocks: Coords,
+        dims: B::Value,
+        arr: &'a mut [T],
+    ) -> Self {
+        Self {
+            span: RowMajorSpan::new(blocks),
+            rows,
+            cols,
+            dims,
+            chunks: arr.chunks_exact_mut(B::area(dims)),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the block at `coords`, made from its `chunk` of memory.
+    #[inline]
+    fn block(&self, coords: Coords, chunk: &'a mut [T]) -> BlockMut<'a, T, B> {
+        let extent = block_extent::<B>(self.rows, self.cols, self.dims, coords);
+        // SAFETY: `self.chunks` gives slices of exactly the length of a block
+        unsafe { BlockMut::new(coords, extent, self.dims, chunk) }
+    }
+}
+
+impl<T, B: Dims> CoordsIterator for BlockIterMut<'_, T, B> {
+    #[inline]
+    fn current_coords(&self) -> Coords {
+        self.span.front_coords()
+    }
+
+    #[inline]
+    fn current_back_coords(&self) -> Coords {
+        self.span.back_coords()
+    }
+}
+
+impl<'a, T, B: Dims> Iterator for BlockIterMut<'a, T, B> {
+    type Item = BlockMut<'a, T, B>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunk = self.chunks.next()?;
+        let coords = self.span.next()?;
+        Some(self.block(coords, chunk))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.chunks.count()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.span.skip(n);
+        let chunk = self.chunks.nth(n)?;
+        let coords = self.span.next()?;
+        Some(self.block(coords, chunk))
+    }
+}
+
+impl<T, B: Dims> DoubleEndedIterator for BlockIterMut<'_, T, B> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let chunk = self.chunks.next_back()?;
+        let coords = self.span.next_back()?;
+        Some(self.block(coords, chunk))
+    }
+
+    #[inline]
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.span.skip_back(n);
+        let chunk = self.chunks.nth_back(n)?;
+        let coords = self.span.next_back()?;
+        Some(self.block(coords, chunk))
+    }
+}
+
+impl<T, B: Dims> ExactSizeIterator for BlockIterMut<'_, T, B> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.chunks.len()
+    }
+}
+
+impl<T, B: Dims> FusedIterator for BlockIterMut<'_, T, B> {}
+
+impl<'a, T, B: Dims, L: Layout> RowMajorIter<'a, T, B, L> {
+    /// Constructs an iterator over the memory `arr` of a grid of `size`, given as
+    /// `(rows, cols)`.
+    pub(crate) fn new(size: Coords, blocks: Coords, dims: B::Value, arr: &'a [T]) -> Self {
+        Self {
+            span: RowMajorSpan::new(size),
+            blocks,
+            dims,
+            arr,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the element at `coords`, without bounds checking.
+    #[inline]
+    unsafe fn get_unchecked(&self, coords: Coords) -> &'a T {
+        let arr: &'a [T] = self.arr;
+        arr.get_unchecked(B::offset::<L>(self.dims, self.blocks, coords))
+    }
+}
+
+impl<T, B: Dims, L: Layout> CoordsIterator for RowMajorIter<'_, T, B, L> {
+    #[inline]
+    fn current_coords(&self) -> Coords {
+        self.span.front_coords()
+    }
+
+    #[inline]
+    fn current_back_coords(&self) -> Coords {
+        self.span.back_coords()
+    }
+}
+
+impl<'a, T, B: Dims, L: Layout> Iterator for RowMajorIter<'a, T, B, L> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let coords = self.span.next()?;
+        // SAFETY: `self.span` only gives valid coordinates
+        Some(unsafe { self.get_unchecked(coords) })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let k = self.span.len();
+        (k, Some(k))
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.span.skip(n);
+        self.next()
+    }
+}
+
+impl<T, B: Dims, L: Layout> DoubleEndedIterator for RowMajorIter<'_, T, B, L> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let coords = self.span.next_back()?;
+        // SAFETY: `self.span` only gives valid coordinates
+        Some(unsafe { self.get_unchecked(coords) })
+    }
+
+    #[inline]
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.span.skip_back(n);
+        self.next_back()
+    }
+}
+
+impl<T, B: Dims, L: Layout> ExactSizeIterator for RowMajorIter<'_, T, B, L> {}
+
+impl<T, B: Dims, L: Layout> FusedIterator for RowMajorIter<'_, T, B, L> {}
+
+impl<'a, T, B: Dims, L: Layout> RowMajorIterMut<'a, T, B, L> {
+    /// Constructs an iterator over the memory `arr` of a grid of `size`, given as
+    /// `(rows, cols)`.
+    pub(crate) fn new(size: Coords, blocks: Coords, dims: B::Value, arr: &'a mut [T]) -> Self {
+        Self {
+            span: RowMajorSpan::new(size),
+            blocks,
+            dims,
+            ptr: NonNull::from(arr).cast(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, B: Dims, L: Layout> CoordsIterator for RowMajorIterMut<'_, T, B, L> {
+    #[inline]
+    fn current_coords(&self) -> Coords {
+        self.span.front_coords()
+    }
+
+    #[inline]
+    fn current_back_coords(&self) -> Coords {
+        self.span.back_coords()
+    }
+}
+
+impl<'a, T, B: Dims, L: Layout> Iterator for RowMajorIterMut<'a, T, B, L> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let coords = self.span.next()?;
+        let ind = B::offset::<L>(self.dims, self.blocks, coords);
+        // SAFETY: `self.ptr` is valid for the whole grid, which is mutably borrowed for `'a`,
+        //         and `self.span` only gives each valid coordinate once
+        Some(unsafe { &mut *self.ptr.as_ptr().add(ind) })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let k = self.span.len();
+        (k, Some(k))
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.span.skip(n);
+        self.next()
+    }
+}
+
+impl<T, B: Dims, L: Layout> DoubleEndedIterator for RowMajorIterMut<'_, T, B, L> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let coords = self.span.next_back()?;
+        let ind = B::offset::<L>(self.dims, self.blocks, coords);
+        // SAFETY: `self.ptr` is valid for the whole grid, which is mutably borrowed for `'a`,
+        //         and `self.span` only gives each valid coordinate once
+        Some(unsafe { &mut *self.ptr.as_ptr().add(ind) })
+    }
+
+    #[inline]
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.span.skip_back(n);
+        self.next_back()
+    }
+}
+
+impl<T, B: Dims, L: Layout> ExactSizeIterator for RowMajorIterMut<'_, T, B, L> {}
+
+impl<T, B: Dims, L: Layout> FusedIterator for RowMajorIterMut<'_, T, B, L> {}
+
+impl<'a, T, B: BlockShape, L: Layout> ColMajorIter<'a, T, B, L> {
+    pub(crate) fn new(grid: &'a BlockGrid<T, B, L>) -> Self {
+        // The span is over the transposed grid, so its coordinates are swapped
+        Self {
+            span: RowMajorSpan::new((grid.cols(), grid.rows())),
+            grid,
+        }
+    }
+}
+
+impl<T, B: BlockShape, L: Layout> CoordsIterator for ColMajorIter<'_, T, B, L> {
+    #[inline]
+    fn current_coords(&self) -> Coords {
+        let (col, row) = self.span.front_coords();
+        (row, col)
+    }
+
+    #[inline]
+    fn current_back_coords(&self) -> Coords {
+        let (col, row) = self.span.back_coords();
+        (row, col)
+    }
+}
+
+impl<'a, T, B: BlockShape, L: Layout> Iterator for ColMajorIter<'a, T, B, L> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (col, row) = self.span.next()?;
+        // SAFETY: `self.span` only gives valid coordinates
+        Some(unsafe { self.grid.get_unchecked((row, col)) })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let k = self.span.len();
+        (k, Some(k))
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.span.skip(n);
+        self.next()
+    }
+}
+
+impl<T, B: BlockShape, L: Layout> DoubleEndedIterator for ColMajorIter<'_, T, B, L> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (col, row) = self.span.next_back()?;
+        // SAFETY: `self.span` only gives valid coordinates
+        Some(unsafe { self.grid.get_unchecked((row, col)) })
+    }
+
+    #[inline]
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.span.skip_back(n);
+        self.next_back()
+    }
+}
+
+impl<T, B: BlockShape, L: Layout> ExactSizeIterator for ColMajorIter<'_, T, B, L> {}
+
+impl<T, B: BlockShape, L: Layout> FusedIterator for ColMajorIter<'_, T, B, L> {}
+
+impl<'a, T, B: BlockShape, L: Layout> ColMajorIterMut<'a, T, B, L> {
+    pub(crate) fn new(grid: &'a mut BlockGrid<T, B, L>) -> Self {
+        // The span is over the transposed grid, so its coordinates are swapped
+        Self {
+            span: RowMajorSpan::new((grid.cols(), grid.rows())),
+            blocks: grid.block_dims(),
+            ptr: NonNull::from(grid.raw_mut()).cast(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, B: BlockShape, L: Layout> CoordsIterator for ColMajorIterMut<'_, T, B, L> {
+    #[inline]
+    fn current_coords(&self) -> Coords {
+        let (col, row) = self.span.front_coords();
+        (row, col)
+    }
+
+    #[inline]
+    fn current_back_coords(&self) -> Coords {
+        let (col, row) = self.span.back_coords();
+        (row, col)
+    }
+}
+
+impl<'a, T, B: BlockShape, L: Layout> Iterator for ColMajorIterMut<'a, T, B, L> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (col, row) = self.span.next()?;
+        let ind = L::offset::<B>(self.blocks, (row, col));
+        // SAFETY: `self.ptr` is valid for the whole grid, which is mutably borrowed for `'a`,
+        //         and `self.span` only gives each valid coordinate once
+        Some(unsafe { &mut *self.ptr.as_ptr().add(ind) })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let k = self.span.len();
+        (k, Some(k))
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.span.skip(n);
+        self.next()
+    }
+}
+
+impl<T, B: BlockShape, L: Layout> DoubleEndedIterator for ColMajorIterMut<'_, T, B, L> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (col, row) = self.span.next_back()?;
+        let ind = L::offset::<B>(self.blocks, (row, col));
+        // SAFETY: `self.ptr` is valid for the whole grid, which is mutably borrowed for `'a`,
+        //         and `self.span` only gives each valid coordinate once
+        Some(unsafe { &mut *self.ptr.as_ptr().add(ind) })
+    }
+
+    #[inline]
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.span.skip_back(n);
+        self.next_back()
+    }
+}
+
+impl<T, B: BlockShape, L: Layout> ExactSizeIterator for ColMajorIterMut<'_, T, B, L> {}
+
+impl<T, B: BlockShape, L: Layout> FusedIterator for ColMajorIterMut<'_, T, B, L> {}
+
+impl CurveSpan {
+    fn new(curve: Curve, rows: usize, cols: usize) -> Self {
+        let shift = rows.min(cols).next_power_of_two().trailing_zeros();
+        let tiles = calc_blocks(rows.max(cols), 1 << shift);
+        let mut span = Self {
+            curve,
+            rows,
+            cols,
+            shift,
+            front: 0,
+            back: tiles << (2 * shift),
+            front_coords: (0, 0),
+            back_coords: (0, 0),
+            len: rows * cols,
+        };
+        if span.len > 0 {
+            span.step_back();
+        }
+        span
+    }
+
+    /// Returns the coordinates at position `d` along the curve, which may be outside the grid.
+    #[inline]
+    fn coords_at(&self, d: usize) -> Coords {
+        let k = self.shift;
+        let (tile, local) = (d >> (2 * k), d & ((1 << (2 * k)) - 1));
+        let (row, col) = match self.curve {
+            Curve::Morton => morton_coords(local, k),
+            Curve::Hilbert => hilbert_coords(local, k),
+        };
+        if self.rows > self.cols {
+            (row + (tile << k), col)
+        } else {
+            (row, col + (tile << k))
+        }
+    }
+
+    /// Returns the level of the largest aligned square around `(row, col)` that's entirely
+    /// outside the grid, or [`None`] if it's inside the grid.
+    #[inline]
+    fn outside_level(&self, (row, col): Coords) -> Option<u32> {
+        if row < self.rows && col < self.cols {
+            return None;
+        }
+        let outside = |l: u32| (row >> l << l) >= self.rows || (col >> l << l) >= self.cols;
+        Some((1..=self.shift).rev().find(|&l| outside(l)).unwrap_or(0))
+    }
+
+    /// Moves the front onto the next position along the curve that's inside the grid.
+    #[inline]
+    fn step(&mut self) {
+        let mut d = self.front + 1;
+        loop {
+            let coords = self.coords_at(d);
+            match self.outside_level(coords) {
+                Some(l) => d = ((d >> (2 * l)) + 1) << (2 * l),
+                None => {
+                    self.front = d;
+                    self.front_coords = coords;
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Moves the back onto the previous position along the curve that's inside the grid.
+    #[inline]
+    fn step_back(&mut self) {
+        let mut d = self.back - 1;
+        loop {
+            let coords = self.coords_at(d);
+            match self.outside_level(coords) {
+                // The square can't start at zero, since the first position is always inside
+                Some(l) => d = (d >> (2 * l) << (2 * l)) - 1,
+                None => {
+                    self.back = d;
+                    self.back_coords = coords;
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Takes the coordinates at the front, if any are left.
+    #[inline]
+    fn next(&mut self) -> Option<Coords> {
+        if self.len == 0 {
+            return None;
+        }
+        let coords = self.front_coords;
+        self.len -= 1;
+        if self.len > 0 {
+            self.step();
+        }
+        Some(coords)
+    }
+
+    /// Takes the coordinates at the back, if any are left.
+    #[inline]
+    fn next_back(&mut self) -> Option<Coords> {
+        if self.len == 0 {
+            return None;
+        }
+        let coords = self.back_coords;
+        self.len -= 1;
+        if self.len > 0 {
+            self.step_back();
+        }
+        Some(coords)
+    }
+}
+
+/// Returns the coordinates at position `d` along a Morton curve in a `1 << k` wide square.
+///
+/// The bits of `d` alternate between the column and row, starting with the column, so each
+/// quadrant is visited in row-major order.
+#[inline]
+fn morton_coords(d: usize, k: u32) -> Coords {
+    let (mut row, mut col) = (0, 0);
+    for i in 0..k {
+        col |= ((d >> (2 * i)) & 1) << i;
+        row |= ((d >> (2 * i + 1)) & 1) << i;
+    }
+    (row, col)
+}
+
+/// Returns the coordinates at position `d` along a Hilbert curve in a `1 << k` wide square.
+///
+/// The curve starts at the top-left, and ends at the top-right.
+#[inline]
+fn hilbert_coords(d: usize, k: u32) -> Coords {
+    let (mut row, mut col, mut t) = (0, 0, d);
+    for i in 0..k {
+        let s = 1 << i;
+        let (rx, ry) = (1 & (t / 2), 1 & (t ^ (1 & (t / 2))));
+        // Rotate the quadrant so the curve joins up with its neighbours
+        if ry == 0 {
+            if rx == 1 {
+                row = s - 1 - row;
+                col = s - 1 - col;
+            }
+            core::mem::swap(&mut row, &mut col);
+        }
+        col += s * rx;
+        row += s * ry;
+        t /= 4;
+    }
+    (row, col)
+}
+
+/// Implements the iterator traits for the immutable and mutable iterators along a `$curve`.
+macro_rules! impl_curve_iters {
+    ($Iter: ident, $IterMut: ident, $curve: expr) => {
+        impl<'a, T, B: BlockShape, L: Layout> $Iter<'a, T, B, L> {
+            pub(crate) fn new(grid: &'a BlockGrid<T, B, L>) -> Self {
+                Self {
+                    span: CurveSpan::new($curve, grid.rows(), grid.cols()),
+                    grid,
+                }
+            }
+        }
+
+        impl<'a, T, B: BlockShape, L: Layout> $IterMut<'a, T, B, L> {
+            pub(crate) fn new(grid: &'a mut BlockGrid<T, B, L>) -> Self {
+                Self {
+                    span: CurveSpan::new($curve, grid.rows(), grid.cols()),
+                    blocks: grid.block_dims(),
+                    ptr: NonNull::from(grid.raw_mut()).cast(),
+                    _phantom: PhantomData,
+                }
+            }
+
+            #[inline]
+            fn get(&self, coords: Coords) -> &'a mut T {
+                let ind = L::offset::<B>(self.blocks, coords);
+                // SAFETY: `self.ptr` is valid for the whole grid, which is mutably borrowed for
+                //         `'a`, and `self.span` only gives each valid coordinate once
+                unsafe { &mut *self.ptr.as_ptr().add(ind) }
+            }
+        }
+
+        impl<'a, T, B: BlockShape, L: Layout> Iterator for $Iter<'a, T, B, L> {
+            type Item = &'a T;
+
+            #[inline]
+            fn next(&mut self) -> Option<Self::Item> {
+                let coords = self.span.next()?;
+                // SAFETY: `self.span` only gives valid coordinates
+                Some(unsafe { self.grid.get_unchecked(coords) })
+            }
+
+            #[inline]
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.span.len, Some(self.span.len))
+            }
+
+            #[inline]
+            fn count(self) -> usize {
+                self.span.len
+            }
+        }
+
+        impl<T, B: BlockShape, L: Layout> DoubleEndedIterator for $Iter<'_, T, B, L> {
+            #[inline]
+            fn next_back(&mut self) -> Option<Self::Item> {
+                let coords = self.span.next_back()?;
+                // SAFETY: `self.span` only gives valid coordinates
+                Some(unsafe { self.grid.get_unchecked(coords) })
+            }
+        }
+
+        impl<'a, T, B: BlockShape, L: Layout> Iterator for $IterMut<'a, T, B, L> {
+            type Item = &'a mut T;
+
+            #[inline]
+            fn next(&mut self) -> Option<Self::Item> {
+                let coords = self.span.next()?;
+                Some(self.get(coords))
+            }
+
+            #[inline]
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.span.len, Some(self.span.len))
+            }
+
+            #[inline]
+            fn count(self) -> usize {
+                self.span.len
+            }
+        }
+
+        impl<T, B: BlockShape, L: Layout> DoubleEndedIterator for $IterMut<'_, T, B, L> {
+            #[inline]
+            fn next_back(&mut self) -> Option<Self::Item> {
+                let coords = self.span.next_back()?;
+                Some(self.get(coords))
+            }
+        }
+
+        impl<T, B: BlockShape, L: Layout> CoordsIterator for $Iter<'_, T, B, L> {
+            #[inline]
+            fn current_coords(&self) -> Coords {
+                self.span.front_coords
+            }
+
+            #[inline]
+            fn current_back_coords(&self) -> Coords {
+                self.span.back_coords
+            }
+        }
+
+        impl<T, B: BlockShape, L: Layout> CoordsIterator for $IterMut<'_, T, B, L> {
+            #[inline]
+            fn current_coords(&self) -> Coords {
+                self.span.front_coords
+            }
+
+            #[inline]
+            fn current_back_coords(&self) -> Coords {
+                self.span.back_coords
+            }
+        }
+
+        impl<T, B: BlockShape, L: Layout> ExactSizeIterator for $Iter<'_, T, B, L> {}
+
+        impl<T, B: BlockShape, L: Layout> ExactSizeIterator for $IterMut<'_, T, B, L> {}
+
+        impl<T, B: BlockShape, L: Layout> FusedIterator for $Iter<'_, T, B, L> {}
+
+        impl<T, B: BlockShape, L: Layout> FusedIterator for $IterMut<'_, T, B, L> {}
+    };
+}
+
+impl_curve_iters!(MortonIter, MortonIterMut, Curve::Morton);
+impl_curve_iters!(HilbertIter, HilbertIterMut, Curve::Hilbert);
+
+impl<B: BlockShape> ViewRuns<B> {
+    fn new<T>(parts: &ViewParts<T>) -> Self {
+        let (start, (rows, cols)) = (parts.start, parts.size);
+        let (block_row, block_col) = (start.0 / B::HEIGHT, start.1 / B::WIDTH);
+        let (end, back_block_row, back_block_col) = if rows == 0 || cols == 0 {
+            // Nothing to visit, so both ends start out having met
+            (start, block_row, block_col)
+        } else {
+            let end = (start.0 + rows, start.1 + cols);
+            (end, (end.0 - 1) / B::HEIGHT, (end.1 - 1) / B::WIDTH)
+        };
+        Self {
+            start,
+            end,
+            col_blocks: parts.col_blocks,
+            block_row,
+            block_col,
+            row: start.0,
+            back_block_row,
+            back_block_col,
+            back_row: end.0,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the next run from the front as a range of indices into the grid memory, along
+    /// with the grid coordinates of its first element and the range of columns it covers in each
+    /// row.
+    fn next_run(&mut self) -> Option<(Range<usize>, Coords, Range<usize>)> {
+        loop {
+            let last =
+                (self.block_row, self.block_col) == (self.back_block_row, self.back_block_col);
+            let top = B::HEIGHT * self.block_row;
+            let bottom = if last {
+                self.back_row
+            } else {
+                self.end.0.min(top + B::HEIGHT)
+            };
+            if self.row < bottom {
+                let cols = self.block_cols(self.block_col);
+                let row = self.row;
+                // Rows spanning the entire block width are contiguous, so take them all at once
+                self.row = if cols.len() == B::WIDTH {
+                    bottom
+                } else {
+                    row + 1
+                };
+                return Some(self.run((self.block_row, self.block_col), row..self.row, cols));
+            }
+            if last {
+                return None;
+            }
+            // Move onto the next block
+            self.block_col += 1;
+            if B::WIDTH * self.block_col >= self.end.1 {
+                self.block_col = self.start.1 / B::WIDTH;
+                self.block_row += 1;
+            }
+            self.row = self.start.0.max(B::HEIGHT * self.block_row);
+        }
+    }
+
+    /// Returns the next run from the back, the same as [`next_run`][Self::next_run].
+    fn next_run_back(&mut self) -> Option<(Range<usize>, Coords, Range<usize>)> {
+        loop {
+            let first =
+                (self.block_row, self.block_col) == (self.back_block_row, self.back_block_col);
+            let top = if first {
+                self.row
+            } else {
+                self.start.0.max(B::HEIGHT * self.back_block_row)
+            };
+            if self.back_row > top {
+                let cols = self.block_cols(self.back_block_col);
+                let row = self.back_row;
+                self.back_row = if cols.len() == B::WIDTH { top } else { row - 1 };
+                let block = (self.back_block_row, self.back_block_col);
+                return Some(self.run(block, self.back_row..row, cols));
+            }
+            if first {
+                return None;
+            }
+            // Move back onto the previous block
+            if self.back_block_col == self.start.1 / B::WIDTH {
+                self.back_block_col = (self.end.1 - 1) / B::WIDTH;
+                self.back_block_row -= 1;
+            } else {
+                self.back_block_col -= 1;
+            }
+            self.back_row = self.end.0.min(B::HEIGHT * (self.back_block_row + 1));
+        }
+    }
+
+    /// Returns the range of columns of the window within the `block_col` column of blocks.
+    #[inline]
+    fn block_cols(&self, block_col: usize) -> Range<usize> {
+        let left = B::WIDTH * block_col;
+        self.start.1.max(left)..self.end.1.min(left + B::WIDTH)
+    }
+
+    /// Returns the run covering `rows` and `cols` of a block, as given by `next_run`.
+    #[inline]
+    fn run(
+        &self,
+        (block_row, block_col): Coords,
+        rows: Range<usize>,
+        cols: Range<usize>,
+    ) -> (Range<usize>, Coords, Range<usize>) {
+        let (top, left) = (B::HEIGHT * block_row, B::WIDTH * block_col);
+        let block_ind = B::AREA * (self.col_blocks * block_row + block_col);
+        let ind = block_ind + B::WIDTH * (rows.start - top) + (cols.start - left);
+        let n = rows.len() * cols.len();
+        (ind..(ind + n), (rows.start, cols.start), cols)
+    }
+}
+
+impl<I: ExactSizeIterator> ViewRun<I> {
+    fn new(iter: I, (row, col): Coords, cols: Range<usize>) -> Self {
+        Self {
+            iter,
+            row,
+            col,
+            cols,
+        }
+    }
+
+    /// Moves the front coordinates onto the next element.
+    #[inline]
+    fn step(&mut self) {
+        self.col += 1;
+        if self.col == self.cols.end {
+            self.col = self.cols.start;
+            self.row += 1;
+        }
+    }
+
+    /// Skips `n` elements from the front, which *must* be at most the number left.
+    #[inline]
+    fn skip(&mut self, n: usize) {
+        if n > 0 {
+            self.iter.nth(n - 1);
+            let k = (self.col - self.cols.start) + n;
+            self.row += k / self.cols.len();
+            self.col = self.cols.start + k % self.cols.len();
+        }
+    }
+
+    /// Returns the coordinates of the last element left in the run.
+    #[inline]
+    fn back_coords(&self) -> Coords {
+        match self.iter.len().checked_sub(1) {
+            Some(n) => {
+                let k = (self.col - self.cols.start) + n;
+                (
+                    self.row + k / self.cols.len(),
+                    self.cols.start + k % self.cols.len(),
+                )
+            }
+            None => (self.row, self.col),
+        }
+    }
+}
+
+impl<'a, T, B: BlockShape> ViewEachIter<'a, T, B> {
+    /// # Safety
+    ///
+    /// `parts` *must* be of a valid window that can be borrowed for `'a`.
+    pub(crate) unsafe fn new(parts: ViewParts<T>) -> Self {
+        let empty = || ViewRun::new([].iter(), parts.start, 0..0);
+        let mut it = Self {
+            runs: ViewRuns::new(&parts),
+            ptr: parts.ptr,
+            front: empty(),
+            back: empty(),
+            start: parts.start,
+            len: parts.size.0 * parts.size.1,
+        };
+        it.next_run(false);
+        it.next_run(true);
+        it
+    }
+
+    /// Moves the front, or the back, onto the next run of memory from that end.
+    ///
+    /// Once all the runs have been taken, the remaining elements are in one or both of the front
+    /// and back runs, so whichever end is empty takes from the other.
+    #[inline]
+    fn next_run(&mut self, back: bool) {
+        let run = if back {
+            self.runs.next_run_back()
+        } else {
+            self.runs.next_run()
+        };
+        if let Some((run, coords, cols)) = run {
+            // SAFETY: `run` is within the window, which is borrowed for `'a`
+            let arr = unsafe { slice::from_raw_parts(self.ptr.as_ptr().add(run.start), run.len()) };
+            let run = ViewRun::new(arr.iter(), coords, cols);
+            if back {
+                self.back = run;
+            } else {
+                self.front = run;
+            }
+        }
+    }
+}
+
+impl<T, B: BlockShape> CoordsIterator for ViewEachIter<'_, T, B> {
+    #[inline]
+    fn current_coords(&self) -> Coords {
+        let run = if self.front.iter.len() > 0 {
+            &self.front
+        } else {
+            &self.back
+        };
+        (run.row - self.start.0, run.col - self.start.1)
+    }
+
+    #[inline]
+    fn current_back_coords(&self) -> Coords {
+        let run = if self.back.iter.len() > 0 {
+            &self.back
+        } else {
+            &self.front
+        };
+        let (row, col) = run.back_coords();
+        (row - self.start.0, col - self.start.1)
+    }
+}
+
+impl<'a, T, B: BlockShape> Iterator for ViewEachIter<'a, T, B> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let run = if self.front.iter.len() > 0 {
+            &mut self.front
+        } else {
+            &mut self.back
+        };
+        let x = run.iter.next();
+        run.step();
+        self.len -= 1;
+        if self.front.iter.len() == 0 && self.len > 0 {
+            self.next_run(false);
+        }
+        x
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn nth(&mut self, mut n: usize) -> Option<Self::Item> {
+        // Skip entire runs at a time, rather than each element
+        while n > 0 && self.len > 0 {
+            let run = if self.front.iter.len() > 0 {
+                &mut self.front
+            } else {
+                &mut self.back
+            };
+            let k = n.min(run.iter.len());
+            run.skip(k);
+            self.len -= k;
+            n -= k;
+            if self.front.iter.len() == 0 && self.len > 0 {
+                self.next_run(false);
+            }
+        }
+        self.next()
+    }
+}
+
+impl<T, B: BlockShape> DoubleEndedIterator for ViewEachIter<'_, T, B> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let run = if self.back.iter.len() > 0 {
+            &mut self.back
+        } else {
+            &mut self.front
+        };
+        let x = run.iter.next_back();
+        self.len -= 1;
+        if self.back.iter.len() == 0 && self.len > 0 {
+            self.next_run(true);
+        }
+        x
+    }
+
+    #[inline]
+    fn nth_back(&mut self, mut n: usize) -> Option<Self::Item> {
+        while n > 0 && self.len > 0 {
+            let run = if self.back.iter.len() > 0 {
+                &mut self.back
+            } else {
+                &mut self.front
+            };
+            let k = n.min(run.iter.len());
+            if k > 0 {
+                run.iter.nth_back(k - 1);
+            }
+            self.len -= k;
+            n -= k;
+            if self.back.iter.len() == 0 && self.len > 0 {
+                self.next_run(true);
+            }
+        }
+        self.next_back()
+    }
+}
+
+impl<T, B: BlockShape> ExactSizeIterator for ViewEachIter<'_, T, B> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<T, B: BlockShape> FusedIterator for ViewEachIter<'_, T, B> {}
+
+impl<'a, T, B: BlockShape> ViewEachIterMut<'a, T, B> {
+    /// # Safety
+    ///
+    /// `parts` *must* be of a valid window that can be mutably borrowed for `'a`.
+    pub(crate) unsafe fn new(parts: ViewParts<T>) -> Self {
+        let empty = || ViewRun::new([].iter_mut(), parts.start, 0..0);
+        let mut it = Self {
+            runs: ViewRuns::new(&parts),
+            ptr: parts.ptr,
+            front: empty(),
+            back: empty(),
+            start: parts.start,
+            len: parts.size.0 * parts.size.1,
+        };
+        it.next_run(false);
+        it.next_run(true);
+        it
+    }
+
+    /// Moves the front, or the back, onto the next run of memory from that end.
+    ///
+    /// Once all the runs have been taken, the remaining elements are in one or both of the front
+    /// and back runs, so whichever end is empty takes from the other.
+    #[inline]
+    fn next_run(&mut self, back: bool) {
+        let run = if back {
+            self.runs.next_run_back()
+        } else {
+            self.runs.next_run()
+        };
+        if let Some((run, coords, cols)) = run {
+            // SAFETY: `run` is within the window, which is mutably borrowed for `'a`, and each
+            //         run is only visited once
+            let arr =
+                unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr().add(run.start), run.len()) };
+            let run = ViewRun::new(arr.iter_mut(), coords, cols);
+            if back {
+                self.back = run;
+            } else {
+                self.front = run;
+            }
+        }
+    }
+}
+
+impl<T, B: BlockShape> CoordsIterator for ViewEachIterMut<'_, T, B> {
+    #[inline]
+    fn current_coords(&self) -> Coords {
+        let run = if self.front.iter.len() > 0 {
+            &self.front
+        } else {
+            &self.back
+        };
+        (run.row - self.start.0, run.col - self.start.1)
+    }
+
+    #[inline]
+    fn current_back_coords(&self) -> Coords {
+        let run = if self.back.iter.len() > 0 {
+            &self.back
+        } else {
+            &self.front
+        };
+        let (row, col) = run.back_coords();
+        (row - self.start.0, col - self.start.1)
+    }
+}
+
+impl<'a, T, B: BlockShape> Iterator for ViewEachIterMut<'a, T, B> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let run = if self.front.iter.len() > 0 {
+            &mut self.front
+        } else {
+            &mut self.back
+        };
+        let x = run.iter.next();
+        run.step();
+        self.len -= 1;
+        if self.front.iter.len() == 0 && self.len > 0 {
+            self.next_run(false);
+        }
+        x
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn nth(&mut self, mut n: usize) -> Option<Self::Item> {
+        // Skip entire runs at a time, rather than each element
+        while n > 0 && self.len > 0 {
+            let run = if self.front.iter.len() > 0 {
+                &mut self.front
+            } else {
+                &mut self.back
+            };
+            let k = n.min(run.iter.len());
+            run.skip(k);
+            self.len -= k;
+            n -= k;
+            if self.front.iter.len() == 0 && self.len > 0 {
+                self.next_run(false);
+            }
+        }
+        self.next()
+    }
+}
+
+impl<T, B: BlockShape> DoubleEndedIterator for ViewEachIterMut<'_, T, B> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let run = if self.back.iter.len() > 0 {
+            &mut self.back
+        } else {
+            &mut self.front
+        };
+        let x = run.iter.next_back();
+        self.len -= 1;
+        if self.back.iter.len() == 0 && self.len > 0 {
+            self.next_run(true);
+        }
+        x
+    }
+
+    #[inline]
+    fn nth_back(&mut self, mut n: usize) -> Option<Self::Item> {
+        while n > 0 && self.len > 0 {
+            let run = if self.back.iter.len() > 0 {
+                &mut self.back
+            } else {
+                &mut self.front
+            };
+            let k = n.min(run.iter.len());
+            if k > 0 {
+                run.iter.nth_back(k - 1);
+            }
+            self.len -= k;
+            n -= k;
+            if self.back.iter.len() == 0 && self.len > 0 {
+                self.next_run(true);
+            }
+        }
+        self.next_back()
+    }
+}
+
+impl<T, B: BlockShape> ExactSizeIterator for ViewEachIterMut<'_, T, B> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<T, B: BlockShape> FusedIterator for ViewEachIterMut<'_, T, B> {}
+
+impl<'a, T, B: BlockShape> ViewBlockIter<'a, T, B> {
+    /// # Safety
+    ///
+    /// `parts` *must* be of a valid block-aligned window that can be borrowed for `'a`.
+    pub(crate) unsafe fn new(parts: ViewParts<T>) -> Self {
+        let blocks = (
+            calc_blocks(parts.size.0, B::HEIGHT),
+            calc_blocks(parts.size.1, B::WIDTH),
+        );
+        Self {
+            parts,
+            span: RowMajorSpan::new(blocks),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the block at `(block_row, block_col)` within the window.
+    #[inline]
+    fn block(&self, (block_row, block_col): Coords) -> Block<'a, T, B> {
+        let (rows, cols) = self.parts.grid_size;
+        let coords = (
+            self.parts.start.0 / B::HEIGHT + block_row,
+            self.parts.start.1 / B::WIDTH + block_col,
+        );
+        let extent = block_extent::<B>(rows, cols, (), coords);
+        let ind = B::AREA * (self.parts.col_blocks * coords.0 + coords.1);
+        // SAFETY: The window is block-aligned, so the entire block is within it, and it's
+        //         borrowed for `'a`
+        unsafe {
+            let arr = slice::from_raw_parts(self.parts.ptr.as_ptr().add(ind), B::AREA);
+            Block::new(coords, extent, (), arr)
+        }
+    }
+}
+
+impl<T, B: BlockShape> CoordsIterator for ViewBlockIter<'_, T, B> {
+    #[inline]
+    fn current_coords(&self) -> Coords {
+        self.span.front_coords()
+    }
+
+    #[inline]
+    fn current_back_coords(&self) -> Coords {
+        self.span.back_coords()
+    }
+}
+
+impl<'a, T, B: BlockShape> Iterator for ViewBlockIter<'a, T, B> {
+    type Item = Block<'a, T, B>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let coords = self.span.next()?;
+        Some(self.block(coords))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.span.len(), Some(self.span.len()))
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.span.len()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.span.skip(n);
+        self.next()
+    }
+}
+
+impl<T, B: BlockShape> DoubleEndedIterator for ViewBlockIter<'_, T, B> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let coords = self.span.next_back()?;
+        Some(self.block(coords))
+    }
+
+    #[inline]
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.span.skip_back(n);
+        self.next_back()
+    }
+}
+
+impl<T, B: BlockShape> ExactSizeIterator for ViewBlockIter<'_, T, B> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.span.len()
+    }
+}
+
+impl<T, B: BlockShape> FusedIterator for ViewBlockIter<'_, T, B> {}
+
+impl<'a, T, B: BlockShape> ViewBlockIterMut<'a, T, B> {
+    /// # Safety
+    ///
+    /// `parts` *must* be of a valid block-aligned window that can be mutably borrowed for `'a`.
+    pub(crate) unsafe fn new(parts: ViewParts<T>) -> Self {
+        let blocks = (
+            calc_blocks(parts.size.0, B::HEIGHT),
+            calc_blocks(parts.size.1, B::WIDTH),
+        );
+        Self {
+            parts,
+            span: RowMajorSpan::new(blocks),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the block at `(block_row, block_col)` within the window.
+    #[inline]
+    fn block(&self, (block_row, block_col): Coords) -> BlockMut<'a, T, B> {
+        let (rows, cols) = self.parts.grid_size;
+        let coords = (
+            self.parts.start.0 / B::HEIGHT + block_row,
+            self.parts.start.1 / B::WIDTH + block_col,
+        );
+        let extent = block_extent::<B>(rows, cols, (), coords);
+        let ind = B::AREA * (self.parts.col_blocks * coords.0 + coords.1);
+        // SAFETY: The window is block-aligned, so the entire block is within it, and it's
+        //         mutably borrowed for `'a`. Each block is only visited once.
+        unsafe {
+            let arr = slice::from_raw_parts_mut(self.parts.ptr.as_ptr().add(ind), B::AREA);
+            BlockMut::new(coords, extent, (), arr)
+        }
+    }
+}
+
+impl<T, B: BlockShape> CoordsIterator for ViewBlockIterMut<'_, T, B> {
+    #[inline]
+    fn current_coords(&self) -> Coords {
+        self.span.front_coords()
+    }
+
+    #[inline]
+    fn current_back_coords(&self) -> Coords {
+        self.span.back_coords()
+    }
+}
+
+impl<'a, T, B: BlockShape> Iterator for ViewBlockIterMut<'a, T, B> {
+    type Item = BlockMut<'a, T, B>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let coords = self.span.next()?;
+        Some(self.block(coords))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.span.len(), Some(self.span.len()))
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.span.len()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.span.skip(n);
+        self.next()
+    }
+}
+
+impl<T, B: BlockShape> DoubleEndedIterator for ViewBlockIterMut<'_, T, B> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let coords = self.span.next_back()?;
+        Some(self.block(coords))
+    }
+
+    #[inline]
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.span.skip_back(n);
+        self.next_back()
+    }
+}
+
+impl<T, B: BlockShape> ExactSizeIterator for ViewBlockIterMut<'_, T, B> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.span.len()
+    }
+}
+
+impl<T, B: BlockShape> FusedIterator for ViewBlockIterMut<'_, T, B> {}
+
+impl<'a, T, B: BlockShape> ViewRowMajorIter<'a, T, B> {
+    pub(crate) fn new(view: GridView<'a, T, B>) -> Self {
+        Self {
+            span: RowMajorSpan::new((view.rows(), view.cols())),
+            view,
+        }
+    }
+}
+
+impl<T, B: BlockShape> CoordsIterator for ViewRowMajorIter<'_, T, B> {
+    #[inline]
+    fn current_coords(&self) -> Coords {
+        self.span.front_coords()
+    }
+
+    #[inline]
+    fn current_back_coords(&self) -> Coords {
+        self.span.back_coords()
+    }
+}
+
+impl<'a, T, B: BlockShape> Iterator for ViewRowMajorIter<'a, T, B> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let coords = self.span.next()?;
+        // SAFETY: `self.span` only gives valid coordinates
+        Some(unsafe { self.view.get_unchecked(coords) })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let k = self.span.len();
+        (k, Some(k))
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.span.skip(n);
+        self.next()
+    }
+}
+
+impl<T, B: BlockShape> DoubleEndedIterator for ViewRowMajorIter<'_, T, B> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let coords = self.span.next_back()?;
+        // SAFETY: `self.span` only gives valid coordinates
+        Some(unsafe { self.view.get_unchecked(coords) })
+    }
+
+    #[inline]
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.span.skip_back(n);
+        self.next_back()
+    }
+}
+
+impl<T, B: BlockShape> ExactSizeIterator for ViewRowMajorIter<'_, T, B> {}
+
+impl<T, B: BlockShape> FusedIterator for ViewRowMajorIter<'_, T, B> {}
+
+impl<'a, T, B: BlockShape> ViewRowMajorIterMut<'a, T, B> {
+    pub(crate) fn new(view: GridViewMut<'a, T, B>) -> Self {
+        Self {
+            span: RowMajorSpan::new((view.rows(), view.cols())),
+            view,
+        }
+    }
+}
+
+impl<T, B: BlockShape> CoordsIterator for ViewRowMajorIterMut<'_, T, B> {
+    #[inline]
+    fn current_coords(&self) -> Coords {
+        self.span.front_coords()
+    }
+
+    #[inline]
+    fn current_back_coords(&self) -> Coords {
+        self.span.back_coords()
+    }
+}
+
+impl<'a, T, B: BlockShape> Iterator for ViewRowMajorIterMut<'a, T, B> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let coords = self.span.next()?;
+        // SAFETY: `self.span` only gives each valid coordinate once, and the view is mutably
+        //         borrowed for `'a`
+        Some(unsafe { &mut *self.view.elem_ptr(coords) })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let k = self.span.len();
+        (k, Some(k))
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.span.skip(n);
+        self.next()
+    }
+}
+
+impl<T, B: BlockShape> DoubleEndedIterator for ViewRowMajorIterMut<'_, T, B> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let coords = self.span.next_back()?;
+        // SAFETY: `self.span` only gives each valid coordinate once, and the view is mutably
+        //         borrowed for `'a`
+        Some(unsafe { &mut *self.view.elem_ptr(coords) })
+    }
+
+    #[inline]
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.span.skip_back(n);
+        self.next_back()
+    }
+}
+
+impl<T, B: BlockShape> ExactSizeIterator for ViewRowMajorIterMut<'_, T, B> {}
+
+impl<T, B: BlockShape> FusedIterator for ViewRowMajorIterMut<'_, T, B> {}
+
+impl<'a, T, B: BlockShape> SplitBlocksMut<'a, T, B> {
+    /// # Safety
+    ///
+    /// `parts` *must* be of an entire grid that can be mutably borrowed for `'a`, and
+    /// `block_size` *must* be non-zero.
+    pub(crate) unsafe fn new(parts: ViewParts<T>, block_size: Coords) -> Self {
+        let size = (B::HEIGHT * block_size.0, B::WIDTH * block_size.1);
+        let (rows, cols) = parts.grid_size;
+        let views = (calc_blocks(rows, size.0), calc_blocks(cols, size.1));
         Self {
-            row: 0,
-            col: 0,
-            cols: grid.cols(),
-            iter: grid.raw().iter(),
+            parts,
+            size,
+            span: RowMajorSpan::new(views),
             _phantom: PhantomData,
         }
     }
+
+    /// Returns the view at `(row, col)` among all the views.
+    #[inline]
+    fn view(&self, (row, col): Coords) -> GridViewMut<'a, T, B> {
+        let (rows, cols) = self.parts.grid_size;
+        let start = (self.size.0 * row, self.size.1 * col);
+        let size = (
+            self.size.0.min(rows - start.0),
+            self.size.1.min(cols - start.1),
+        );
+        let parts = ViewParts {
+            start,
+            size,
+            ..self.parts.clone()
+        };
+        // SAFETY: Each window is within the grid, which is mutably borrowed for `'a`, and they
+        //         are all disjoint
+        unsafe { GridViewMut::from_parts(parts) }
+    }
 }
 
-impl<T, B: BlockDim> CoordsIterator for EachIter<'_, T, B> {
+impl<T, B: BlockShape> CoordsIterator for SplitBlocksMut<'_, T, B> {
     #[inline]
     fn current_coords(&self) -> Coords {
-        (self.row, self.col)
+        self.span.front_coords()
+    }
+
+    #[inline]
+    fn current_back_coords(&self) -> Coords {
+        self.span.back_coords()
     }
 }
 
-impl<'a, T, B: BlockDim> Iterator for EachIter<'a, T, B> {
-    type Item = &'a T;
+impl<'a, T, B: BlockShape> Iterator for SplitBlocksMut<'a, T, B> {
+    type Item = GridViewMut<'a, T, B>;
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        // TODO: Try out bitwise ops for potential speedup?
-        self.col += 1;
-        if self.col % B::WIDTH == 0 {
-            self.row += 1;
-            if self.row % B::WIDTH == 0 {
-                if self.col == self.cols {
-                    self.col = 0;
-                } else {
-                    self.row -= B::WIDTH;
-                }
-            } else {
-                self.col -= B::WIDTH;
-            }
-        }
-        self.iter.next()
+        let coords = self.span.next()?;
+        Some(self.view(coords))
     }
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        self.iter.size_hint()
+        (self.span.len(), Some(self.span.len()))
     }
 
     #[inline]
     fn count(self) -> usize {
-        self.iter.count()
+        self.span.len()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.span.skip(n);
+        self.next()
+    }
+}
+
+impl<T, B: BlockShape> DoubleEndedIterator for SplitBlocksMut<'_, T, B> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let coords = self.span.next_back()?;
+        Some(self.view(coords))
+    }
+
+    #[inline]
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.span.skip_back(n);
+        self.next_back()
     }
 }
 
-impl<T, B: BlockDim> ExactSizeIterator for EachIter<'_, T, B> {
+impl<T, B: BlockShape> ExactSizeIterator for SplitBlocksMut<'_, T, B> {
     #[inline]
     fn len(&self) -> usize {
-        self.iter.len()
+        self.span.len()
     }
 }
 
-impl<T, B: BlockDim> FusedIterator for EachIter<'_, T, B> {}
+impl<T, B: BlockShape> FusedIterator for SplitBlocksMut<'_, T, B> {}
 
-impl<'a, T, B: BlockDim> EachIterMut<'a, T, B> {
-    pub(crate) fn new(grid: &'a mut BlockGrid<T, B>) -> Self {
+impl<'a, T, B: BlockShape> ChunkedBlockIter<'a, T, B> {
+    pub(crate) fn new(chunks: &'a BTreeMap<SignedCoords, Box<[T]>>) -> Self {
         Self {
-            row: 0,
-            col: 0,
-            cols: grid.cols(),
-            iter: grid.raw_mut().iter_mut(),
+            iter: chunks.iter(),
             _phantom: PhantomData,
         }
     }
-}
 
-impl<T, B: BlockDim> CoordsIterator for EachIterMut<'_, T, B> {
-    #[inline]
-    fn current_coords(&self) -> Coords {
-        (self.row, self.col)
+    fn chunk(coords: SignedCoords, chunk: &'a [T]) -> (SignedCoords, Block<'a, T, B>) {
+        // SAFETY: Every chunk is allocated with exactly `B::AREA` elements
+        (coords, unsafe {
+            Block::new((0, 0), (B::HEIGHT, B::WIDTH), (), chunk)
+        })
     }
 }
 
-impl<'a, T, B: BlockDim> Iterator for EachIterMut<'a, T, B> {
-    type Item = &'a mut T;
+impl<'a, T, B: BlockShape> Iterator for ChunkedBlockIter<'a, T, B> {
+    type Item = (SignedCoords, Block<'a, T, B>);
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        self.col += 1;
-        if self.col % B::WIDTH == 0 {
-            self.row += 1;
-            if self.row % B::WIDTH == 0 {
-                if self.col == self.cols {
-                    self.col = 0;
-                } else {
-                    self.row -= B::WIDTH;
-                }
-            } else {
-                self.col -= B::WIDTH;
-            }
-        }
-        self.iter.next()
+        self.iter.next().map(|(&c, chunk)| Self::chunk(c, chunk))
     }
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
         self.iter.size_hint()
     }
-
-    #[inline]
-    fn count(self) -> usize {
-        self.iter.count()
-    }
 }
 
-impl<T, B: BlockDim> ExactSizeIterator for EachIterMut<'_, T, B> {
+impl<T, B: BlockShape> DoubleEndedIterator for ChunkedBlockIter<'_, T, B> {
     #[inline]
-    fn len(&self) -> usize {
-        self.iter.len()
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next_back()
+            .map(|(&c, chunk)| Self::chunk(c, chunk))
     }
 }
 
-impl<T, B: BlockDim> FusedIterator for EachIterMut<'_, T, B> {}
+impl<T, B: BlockShape> ExactSizeIterator for ChunkedBlockIter<'_, T, B> {}
 
-impl<'a, T, B: BlockDim> BlockIter<'a, T, B> {
-    pub(crate) fn new(grid: &'a BlockGrid<T, B>) -> Self {
+impl<T, B: BlockShape> FusedIterator for ChunkedBlockIter<'_, T, B> {}
+
+impl<'a, T, B: BlockShape> ChunkedBlockIterMut<'a, T, B> {
+    pub(crate) fn new(chunks: &'a mut BTreeMap<SignedCoords, Box<[T]>>) -> Self {
         Self {
-            block_row: 0,
-            block_col: 0,
-            col_blocks: grid.col_blocks(),
-            chunks: grid.raw().chunks_exact(B::AREA),
+            iter: chunks.iter_mut(),
             _phantom: PhantomData,
         }
     }
-}
 
-impl<T, B: BlockDim> CoordsIterator for BlockIter<'_, T, B> {
-    #[inline]
-    fn current_coords(&self) -> Coords {
-        (self.block_row, self.block_col)
+    fn chunk(coords: SignedCoords, chunk: &'a mut [T]) -> (SignedCoords, BlockMut<'a, T, B>) {
+        // SAFETY: Every chunk is allocated with exactly `B::AREA` elements
+        (coords, unsafe {
+            BlockMut::new((0, 0), (B::HEIGHT, B::WIDTH), (), chunk)
+        })
     }
 }
 
-impl<'a, T, B: BlockDim> Iterator for BlockIter<'a, T, B> {
-    type Item = Block<'a, T, B>;
+impl<'a, T, B: BlockShape> Iterator for ChunkedBlockIterMut<'a, T, B> {
+    type Item = (SignedCoords, BlockMut<'a, T, B>);
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        let chunk = self.chunks.next()?;
-        // SAFETY: `self.chunks` gives slices of exactly `B::AREA` length
-        let block = unsafe { Block::new(self.current_coords(), chunk) };
-        self.block_col += 1;
-        if self.block_col == self.col_blocks {
-            self.block_row += 1;
-            self.block_col = 0;
-        }
-        Some(block)
+        self.iter.next().map(|(&c, chunk)| Self::chunk(c, chunk))
     }
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        self.chunks.size_hint()
+        self.iter.size_hint()
     }
+}
 
+impl<T, B: BlockShape> DoubleEndedIterator for ChunkedBlockIterMut<'_, T, B> {
     #[inline]
-    fn count(self) -> usize {
-        self.chunks.count()
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next_back()
+            .map(|(&c, chunk)| Self::chunk(c, chunk))
     }
 }
 
-impl<T, B: BlockDim> ExactSizeIterator for BlockIter<'_, T, B> {
+impl<T, B: BlockShape> ExactSizeIterator for ChunkedBlockIterMut<'_, T, B> {}
+
+impl<T, B: BlockShape> FusedIterator for ChunkedBlockIterMut<'_, T, B> {}
+
+impl LineLayout {
+    fn row<B: BlockShape>(col_blocks: usize, row: usize) -> Self {
+        Self {
+            base: calc_index::<B>(col_blocks, (), (row, 0)),
+            shift: B::COL_SHIFT,
+            outer: B::AREA,
+            inner: 1,
+            index: row,
+            is_col: false,
+        }
+    }
+
+    fn col<B: BlockShape>(col_blocks: usize, col: usize) -> Self {
+        Self {
+            base: calc_index::<B>(col_blocks, (), (0, col)),
+            shift: B::ROW_SHIFT,
+            outer: B::AREA * col_blocks,
+            inner: B::WIDTH,
+            index: col,
+            is_col: true,
+        }
+    }
+
+    /// Returns the memory index of the `k`-th element.
     #[inline]
-    fn len(&self) -> usize {
-        self.chunks.len()
+    fn mem_index(&self, k: usize) -> usize {
+        let mask = (1 << self.shift) - 1;
+        self.base + self.outer * (k >> self.shift) + self.inner * (k & mask)
+    }
+
+    /// Returns the coordinates of the `k`-th element.
+    #[inline]
+    fn coords(&self, k: usize) -> Coords {
+        if self.is_col {
+            (k, self.index)
+        } else {
+            (self.index, k)
+        }
     }
 }
 
-impl<T, B: BlockDim> FusedIterator for BlockIter<'_, T, B> {}
+impl<'a, T> Line<'a, T> {
+    pub(crate) fn row<B: BlockShape>(grid: &'a BlockGrid<T, B>, row: usize) -> Self {
+        let layout = LineLayout::row::<B>(grid.col_blocks(), row);
+        Self::new(grid.raw(), layout, grid.cols())
+    }
+
+    pub(crate) fn col<B: BlockShape>(grid: &'a BlockGrid<T, B>, col: usize) -> Self {
+        let layout = LineLayout::col::<B>(grid.col_blocks(), col);
+        Self::new(grid.raw(), layout, grid.rows())
+    }
 
-impl<'a, T, B: BlockDim> BlockIterMut<'a, T, B> {
-    pub(crate) fn new(grid: &'a mut BlockGrid<T, B>) -> Self {
+    fn new(arr: &'a [T], layout: LineLayout, len: usize) -> Self {
         Self {
-            block_row: 0,
-            block_col: 0,
-            col_blocks: grid.col_blocks(),
-            chunks: grid.raw_mut().chunks_exact_mut(B::AREA),
-            _phantom: PhantomData,
+            layout,
+            front: 0,
+            back: len,
+            arr,
+        }
+    }
+
+    /// Returns a reference to the `k`-th remaining element, or [`None`] if out-of-bounds.
+    #[inline]
+    pub fn get(&self, k: usize) -> Option<&'a T> {
+        if k >= self.len() {
+            return None;
         }
+        let ind = self.layout.mem_index(self.front + k);
+        // SAFETY: `ind` is the index of a valid element in the line
+        Some(unsafe { self.arr.get_unchecked(ind) })
     }
 }
 
-impl<T, B: BlockDim> CoordsIterator for BlockIterMut<'_, T, B> {
+impl<T> CoordsIterator for Line<'_, T> {
     #[inline]
     fn current_coords(&self) -> Coords {
-        (self.block_row, self.block_col)
+        self.layout.coords(self.front)
+    }
+
+    #[inline]
+    fn current_back_coords(&self) -> Coords {
+        self.layout.coords(self.back.saturating_sub(1))
     }
 }
 
-impl<'a, T, B: BlockDim> Iterator for BlockIterMut<'a, T, B> {
-    type Item = BlockMut<'a, T, B>;
+impl<'a, T> Iterator for Line<'a, T> {
+    type Item = &'a T;
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        let chunk = self.chunks.next()?;
-        // SAFETY: `self.chunks` gives slices of exactly `B::AREA` length
-        let block = unsafe { BlockMut::new(self.current_coords(), chunk) };
-        self.block_col += 1;
-        if self.block_col == self.col_blocks {
-            self.block_row += 1;
-            self.block_col = 0;
-        }
-        Some(block)
+        let x = self.get(0)?;
+        self.front += 1;
+        Some(x)
     }
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        self.chunks.size_hint()
+        let k = self.back - self.front;
+        (k, Some(k))
     }
 
     #[inline]
     fn count(self) -> usize {
-        self.chunks.count()
+        self.len()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.front += n.min(self.len());
+        self.next()
     }
 }
 
-impl<T, B: BlockDim> ExactSizeIterator for BlockIterMut<'_, T, B> {
+impl<T> DoubleEndedIterator for Line<'_, T> {
     #[inline]
-    fn len(&self) -> usize {
-        self.chunks.len()
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let x = self.get(self.len().checked_sub(1)?)?;
+        self.back -= 1;
+        Some(x)
+    }
+
+    #[inline]
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.back -= n.min(self.len());
+        self.next_back()
     }
 }
 
-impl<T, B: BlockDim> FusedIterator for BlockIterMut<'_, T, B> {}
+impl<T> ExactSizeIterator for Line<'_, T> {}
 
-impl<'a, T, B: BlockDim> RowMajorIter<'a, T, B> {
-    pub(crate) fn new(grid: &'a BlockGrid<T, B>) -> Self {
+impl<T> FusedIterator for Line<'_, T> {}
+
+impl<T> Index<usize> for Line<'_, T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, k: usize) -> &Self::Output {
+        self.get(k).expect("Index out of bounds")
+    }
+}
+
+impl<'a, T> LineMut<'a, T> {
+    pub(crate) fn row<B: BlockShape>(grid: &'a mut BlockGrid<T, B>, row: usize) -> Self {
+        let (layout, len) = (LineLayout::row::<B>(grid.col_blocks(), row), grid.cols());
+        // SAFETY: The pointer is to the whole grid, which is mutably borrowed for `'a`
+        unsafe { Self::new(NonNull::from(grid.raw_mut()).cast(), layout, len) }
+    }
+
+    pub(crate) fn col<B: BlockShape>(grid: &'a mut BlockGrid<T, B>, col: usize) -> Self {
+        let (layout, len) = (LineLayout::col::<B>(grid.col_blocks(), col), grid.rows());
+        // SAFETY: The pointer is to the whole grid, which is mutably borrowed for `'a`
+        unsafe { Self::new(NonNull::from(grid.raw_mut()).cast(), layout, len) }
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` *must* be valid for the whole grid the `layout` is in, and no other references to
+    /// the elements of the line can exist for `'a`.
+    unsafe fn new(ptr: NonNull<T>, layout: LineLayout, len: usize) -> Self {
         Self {
-            row: 0,
-            col: 0,
-            grid,
+            layout,
+            front: 0,
+            back: len,
+            ptr,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the `k`-th remaining element, or [`None`] if out-of-bounds.
+    #[inline]
+    pub fn get(&self, k: usize) -> Option<&T> {
+        // SAFETY: Pointer is valid from `self.ptr_at`, and is borrowed from `self`
+        self.ptr_at(k).map(|p| unsafe { &*p })
+    }
+
+    /// Returns a mutable reference to the `k`-th remaining element, or [`None`] if
+    /// out-of-bounds.
+    #[inline]
+    pub fn get_mut(&mut self, k: usize) -> Option<&mut T> {
+        // SAFETY: Pointer is valid from `self.ptr_at`, and is mutably borrowed from `self`
+        self.ptr_at(k).map(|p| unsafe { &mut *p })
+    }
+
+    /// Returns a pointer to the `k`-th remaining element, or [`None`] if out-of-bounds.
+    #[inline]
+    fn ptr_at(&self, k: usize) -> Option<*mut T> {
+        if k >= self.len() {
+            return None;
         }
+        let ind = self.layout.mem_index(self.front + k);
+        // SAFETY: `ind` is the index of a valid element in the line
+        Some(unsafe { self.ptr.as_ptr().add(ind) })
     }
 }
 
-impl<T, B: BlockDim> CoordsIterator for RowMajorIter<'_, T, B> {
+impl<T> CoordsIterator for LineMut<'_, T> {
     #[inline]
     fn current_coords(&self) -> Coords {
-        (self.row, self.col)
+        self.layout.coords(self.front)
+    }
+
+    #[inline]
+    fn current_back_coords(&self) -> Coords {
+        self.layout.coords(self.back.saturating_sub(1))
     }
 }
 
-impl<'a, T, B: BlockDim> Iterator for RowMajorIter<'a, T, B> {
-    type Item = &'a T;
+impl<'a, T> Iterator for LineMut<'a, T> {
+    type Item = &'a mut T;
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        if self.row >= self.grid.rows() {
-            return None;
-        }
-        // SAFETY: Method logic ensures `(self.row, self.col)` is a valid index
-        let x = unsafe { self.grid.get_unchecked((self.row, self.col)) };
-        self.col += 1;
-        if self.col == self.grid.cols() {
-            self.row += 1;
-            self.col = 0;
-        }
-        Some(x)
+        let p = self.ptr_at(0)?;
+        self.front += 1;
+        // SAFETY: Each element is only yielded once, and the line is borrowed for `'a`
+        Some(unsafe { &mut *p })
     }
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let idx = self.row * self.grid.cols() + self.col;
-        let k = self.grid.size() - idx;
+        let k = self.back - self.front;
         (k, Some(k))
     }
 
@@ -392,72 +2984,165 @@ impl<'a, T, B: BlockDim> Iterator for RowMajorIter<'a, T, B> {
     fn count(self) -> usize {
         self.len()
     }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.front += n.min(self.len());
+        self.next()
+    }
 }
 
-impl<T, B: BlockDim> ExactSizeIterator for RowMajorIter<'_, T, B> {}
+impl<T> DoubleEndedIterator for LineMut<'_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let p = self.ptr_at(self.len().checked_sub(1)?)?;
+        self.back -= 1;
+        // SAFETY: Each element is only yielded once, and the line is borrowed for `'a`
+        Some(unsafe { &mut *p })
+    }
 
-impl<T, B: BlockDim> FusedIterator for RowMajorIter<'_, T, B> {}
+    #[inline]
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.back -= n.min(self.len());
+        self.next_back()
+    }
+}
 
-impl<'a, T, B: BlockDim> RowMajorIterMut<'a, T, B> {
-    pub(crate) fn new(grid: &'a mut BlockGrid<T, B>) -> Self {
-        Self {
-            row: 0,
-            col: 0,
-            grid: grid.into(),
-            _phantom: PhantomData,
-        }
+impl<T> ExactSizeIterator for LineMut<'_, T> {}
+
+impl<T> FusedIterator for LineMut<'_, T> {}
+
+impl<T> Index<usize> for LineMut<'_, T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, k: usize) -> &Self::Output {
+        self.get(k).expect("Index out of bounds")
     }
 }
 
-impl<T, B: BlockDim> CoordsIterator for RowMajorIterMut<'_, T, B> {
+impl<T> IndexMut<usize> for LineMut<'_, T> {
     #[inline]
-    fn current_coords(&self) -> Coords {
-        (self.row, self.col)
+    fn index_mut(&mut self, k: usize) -> &mut Self::Output {
+        self.get_mut(k).expect("Index out of bounds")
     }
 }
 
-impl<'a, T, B: BlockDim> Iterator for RowMajorIterMut<'a, T, B> {
-    type Item = &'a mut T;
+/// Implements the iterator traits for an iterator over lines, which just need a `line` method.
+macro_rules! impl_lines_iter {
+    ($Iter: ident, $Line: ident) => {
+        impl<'a, T, B: BlockShape> Iterator for $Iter<'a, T, B> {
+            type Item = $Line<'a, T>;
 
-    #[inline]
-    fn next(&mut self) -> Option<Self::Item> {
-        // SAFETY: `self.grid` is a valid pointer
-        let (rows, cols) = unsafe {
-            let grid = self.grid.as_ref();
-            (grid.rows(), grid.cols())
-        };
-        if self.row >= rows {
-            return None;
+            #[inline]
+            fn next(&mut self) -> Option<Self::Item> {
+                let i = self.lines.next()?;
+                Some(self.line(i))
+            }
+
+            #[inline]
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.lines.size_hint()
+            }
+
+            #[inline]
+            fn count(self) -> usize {
+                self.len()
+            }
+
+            #[inline]
+            fn nth(&mut self, n: usize) -> Option<Self::Item> {
+                let i = self.lines.nth(n)?;
+                Some(self.line(i))
+            }
         }
-        // SAFETY: `self.grid` is a valid mutable pointer and method logic ensures
-        //         `(self.row, self.col)` is a valid index
-        let x = unsafe { (&mut *self.grid.as_ptr()).get_unchecked_mut((self.row, self.col)) };
-        self.col += 1;
-        if self.col == cols {
-            self.row += 1;
-            self.col = 0;
+
+        impl<T, B: BlockShape> DoubleEndedIterator for $Iter<'_, T, B> {
+            #[inline]
+            fn next_back(&mut self) -> Option<Self::Item> {
+                let i = self.lines.next_back()?;
+                Some(self.line(i))
+            }
+
+            #[inline]
+            fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+                let i = self.lines.nth_back(n)?;
+                Some(self.line(i))
+            }
+        }
+
+        impl<T, B: BlockShape> ExactSizeIterator for $Iter<'_, T, B> {}
+
+        impl<T, B: BlockShape> FusedIterator for $Iter<'_, T, B> {}
+    };
+}
+
+impl<'a, T, B: BlockShape> RowsIter<'a, T, B> {
+    pub(crate) fn new(grid: &'a BlockGrid<T, B>) -> Self {
+        Self {
+            lines: 0..grid.rows(),
+            grid,
         }
-        Some(x)
     }
 
-    #[inline]
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        // SAFETY: `self.grid` is a valid pointer
-        let grid = unsafe { self.grid.as_ref() };
-        let idx = self.row * grid.cols() + self.col;
-        let k = grid.size() - idx;
-        (k, Some(k))
+    fn line(&self, i: usize) -> Line<'a, T> {
+        Line::row(self.grid, i)
     }
+}
 
-    #[inline]
-    fn count(self) -> usize {
-        self.len()
+impl<'a, T, B: BlockShape> RowsIterMut<'a, T, B> {
+    pub(crate) fn new(grid: &'a mut BlockGrid<T, B>) -> Self {
+        Self {
+            lines: 0..grid.rows(),
+            col_blocks: grid.col_blocks(),
+            len: grid.cols(),
+            ptr: NonNull::from(grid.raw_mut()).cast(),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn line(&self, i: usize) -> LineMut<'a, T> {
+        let layout = LineLayout::row::<B>(self.col_blocks, i);
+        // SAFETY: Each row is disjoint, and only yielded once
+        unsafe { LineMut::new(self.ptr, layout, self.len) }
+    }
+}
+
+impl<'a, T, B: BlockShape> ColsIter<'a, T, B> {
+    pub(crate) fn new(grid: &'a BlockGrid<T, B>) -> Self {
+        Self {
+            lines: 0..grid.cols(),
+            grid,
+        }
+    }
+
+    fn line(&self, j: usize) -> Line<'a, T> {
+        Line::col(self.grid, j)
     }
 }
 
-impl<T, B: BlockDim> ExactSizeIterator for RowMajorIterMut<'_, T, B> {}
+impl<'a, T, B: BlockShape> ColsIterMut<'a, T, B> {
+    pub(crate) fn new(grid: &'a mut BlockGrid<T, B>) -> Self {
+        Self {
+            lines: 0..grid.cols(),
+            col_blocks: grid.col_blocks(),
+            len: grid.rows(),
+            ptr: NonNull::from(grid.raw_mut()).cast(),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn line(&self, j: usize) -> LineMut<'a, T> {
+        let layout = LineLayout::col::<B>(self.col_blocks, j);
+        // SAFETY: Each column is disjoint, and only yielded once
+        unsafe { LineMut::new(self.ptr, layout, self.len) }
+    }
+}
 
-impl<T, B: BlockDim> FusedIterator for RowMajorIterMut<'_, T, B> {}
+impl_lines_iter!(RowsIter, Line);
+impl_lines_iter!(RowsIterMut, LineMut);
+impl_lines_iter!(ColsIter, Line);
+impl_lines_iter!(ColsIterMut, LineMut);
 
 impl<I: CoordsIterator> Iterator for WithCoordsIter<I> {
     type Item = (Coords, I::Item);
@@ -487,6 +3172,22 @@ impl<I: CoordsIterator> Iterator for WithCoordsIter<I> {
     }
 }
 
+impl<I: CoordsIterator + DoubleEndedIterator> DoubleEndedIterator for WithCoordsIter<I> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let c = self.iter.current_back_coords();
+        self.iter.next_back().map(|x| (c, x))
+    }
+
+    #[inline]
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        if n >= 1 {
+            self.iter.nth_back(n - 1)?;
+        }
+        self.next_back()
+    }
+}
+
 impl<I: CoordsIterator + ExactSizeIterator> ExactSizeIterator for WithCoordsIter<I> {
     #[inline]
     fn len(&self) -> usize {
@@ -500,10 +3201,25 @@ impl<I: CoordsIterator + FusedIterator> FusedIterator for WithCoordsIter<I> {}
 mod private {
     use super::*;
     pub trait Sealed {}
-    impl<T, B: BlockDim> Sealed for EachIter<'_, T, B> {}
-    impl<T, B: BlockDim> Sealed for EachIterMut<'_, T, B> {}
-    impl<T, B: BlockDim> Sealed for BlockIter<'_, T, B> {}
-    impl<T, B: BlockDim> Sealed for BlockIterMut<'_, T, B> {}
-    impl<T, B: BlockDim> Sealed for RowMajorIter<'_, T, B> {}
-    impl<T, B: BlockDim> Sealed for RowMajorIterMut<'_, T, B> {}
+    impl<T, B: Dims> Sealed for EachIter<'_, T, B> {}
+    impl<T, B: Dims> Sealed for EachIterMut<'_, T, B> {}
+    impl<T, B: Dims> Sealed for BlockIter<'_, T, B> {}
+    impl<T, B: Dims> Sealed for BlockIterMut<'_, T, B> {}
+    impl<T, B: Dims, L: Layout> Sealed for RowMajorIter<'_, T, B, L> {}
+    impl<T, B: Dims, L: Layout> Sealed for RowMajorIterMut<'_, T, B, L> {}
+    impl<T, B: BlockShape, L: Layout> Sealed for ColMajorIter<'_, T, B, L> {}
+    impl<T, B: BlockShape, L: Layout> Sealed for ColMajorIterMut<'_, T, B, L> {}
+    impl<T, B: BlockShape, L: Layout> Sealed for MortonIter<'_, T, B, L> {}
+    impl<T, B: BlockShape, L: Layout> Sealed for MortonIterMut<'_, T, B, L> {}
+    impl<T, B: BlockShape, L: Layout> Sealed for HilbertIter<'_, T, B, L> {}
+    impl<T, B: BlockShape, L: Layout> Sealed for HilbertIterMut<'_, T, B, L> {}
+    impl<T, B: BlockShape> Sealed for ViewEachIter<'_, T, B> {}
+    impl<T, B: BlockShape> Sealed for ViewEachIterMut<'_, T, B> {}
+    impl<T, B: BlockShape> Sealed for ViewBlockIter<'_, T, B> {}
+    impl<T, B: BlockShape> Sealed for ViewBlockIterMut<'_, T, B> {}
+    impl<T, B: BlockShape> Sealed for ViewRowMajorIter<'_, T, B> {}
+    impl<T, B: BlockShape> Sealed for ViewRowMajorIterMut<'_, T, B> {}
+    impl<T, B: BlockShape> Sealed for SplitBlocksMut<'_, T, B> {}
+    impl<T> Sealed for Line<'_, T> {}
+    impl<T> Sealed for LineMut<'_, T> {}
 }
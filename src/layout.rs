@@ -0,0 +1,211 @@
+use core::marker::PhantomData;
+
+use crate::{BlockShape, Coords};
+
+/// A type that represents the compile time memory layout of a [`BlockGrid`].
+///
+/// The layout maps each coordinate of the padded grid, which has a whole number of blocks in each
+/// dimension, to an index into memory. The default is [`Blocked`], with row-major blocks of
+/// row-major elements. Any [`Order`] can also be used on its own, which ignores the blocks
+/// entirely and orders the whole padded grid.
+///
+/// Only part of the API is generic over the layout. That's construction, element access and
+/// indexing, comparison and hashing, the [`row_major_iter`], [`col_major_iter`] and curve
+/// iterators, [`map`] and its relatives, and the arithmetic operators. Code written against
+/// these can be compared across layouts, as in the `layout` benchmark of the test suite.
+/// Everything else is only implemented for the default layout, since most of it relies on whole
+/// blocks being contiguous in memory. This includes [`each_iter`], [`block_iter`], rows and
+/// columns, regions and views, transforms, neighbourhoods, stencils, summed-area tables and the
+/// parallel iterators. A grid can be moved to the default layout with [`relayout`] to use them.
+///
+/// # Safety
+///
+/// [`offset`][Self::offset] *must* map every coordinate of the padded grid to a distinct index
+/// below the padded size, since these are used for unchecked access.
+///
+/// [`BlockGrid`]: crate::BlockGrid
+/// [`row_major_iter`]: crate::BlockGrid::row_major_iter
+/// [`col_major_iter`]: crate::BlockGrid::col_major_iter
+/// [`map`]: crate::BlockGrid::map
+/// [`each_iter`]: crate::BlockGrid::each_iter
+/// [`block_iter`]: crate::BlockGrid::block_iter
+/// [`relayout`]: crate::BlockGrid::relayout
+///
+/// # Example
+///
+/// ```
+/// use block_grid::{BlockGrid, Blocked, ColMajor, RowMajor, U2};
+///
+/// let data: Vec<_> = (0..(4 * 4)).collect();
+///
+/// let grid = BlockGrid::<usize, U2>::from_row_major(4, 4, &data)?;
+/// assert_eq!(&grid.raw()[..6], &[0, 1, 4, 5, 2, 3]);
+///
+/// let grid = BlockGrid::<usize, U2, Blocked<ColMajor>>::from_row_major(4, 4, &data)?;
+/// assert_eq!(&grid.raw()[..6], &[0, 1, 4, 5, 8, 9]);
+///
+/// let grid = BlockGrid::<usize, U2, RowMajor>::from_row_major(4, 4, &data)?;
+/// assert_eq!(&grid.raw()[..6], &[0, 1, 2, 3, 4, 5]);
+/// assert!(grid.row_major_iter().eq(data.iter()));
+/// # Ok::<(), block_grid::Error>(())
+/// ```
+pub unsafe trait Layout: Clone + 'static {
+    /// Returns the offset into memory of `coords`, in a padded grid of `blocks` blocks, given as
+    /// `(row_blocks, col_blocks)`.
+    fn offset<B: BlockShape>(blocks: Coords, coords: Coords) -> usize;
+}
+
+/// A type that represents a compile time ordering of a 2D rectangle.
+///
+/// These are the building blocks of a [`Layout`], either within and across blocks with
+/// [`Blocked`], or on their own.
+///
+/// # Safety
+///
+/// [`index`][Self::index] *must* map every coordinate of a `rows` by `cols` rectangle to a
+/// distinct index below `rows * cols`.
+pub unsafe trait Order: Clone + 'static {
+    /// Returns the index of `coords` in a rectangle of `dims`, given as `(rows, cols)`.
+    fn index(dims: Coords, coords: Coords) -> usize;
+}
+
+/// [Row-major order][row_major], where each row follows the last.
+///
+/// As a [`Layout`] on its own, this is a plain 2D array, just with the padding of the block size.
+///
+/// [row_major]: https://en.wikipedia.org/wiki/Row-_and_column-major_order
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct RowMajor;
+
+/// [Column-major order][col_major], where each column follows the last.
+///
+/// [col_major]: https://en.wikipedia.org/wiki/Row-_and_column-major_order
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ColMajor;
+
+/// [Morton order][morton], where each quadrant is ordered in turn, top-left, top-right,
+/// bottom-left, then bottom-right, and likewise within each quadrant.
+///
+/// A rectangle that isn't square is split into square tiles along its longer side, the smallest
+/// power-of-two that covers the shorter side, and each tile follows the last. Any parts of a tile
+/// outside the rectangle are skipped over, so this matches [`BlockGrid::morton_iter`].
+///
+/// Within a full tile, an index just interleaves the bits of the coordinates, so power-of-two
+/// dimensions, like those of a block, are quickest. A tile cut short by the edge of the rectangle
+/// has to count the skipped parts of each quadrant instead, which takes a step per bit of its
+/// side.
+///
+/// [morton]: https://en.wikipedia.org/wiki/Z-order_curve
+/// [`BlockGrid::morton_iter`]: crate::BlockGrid::morton_iter
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Morton;
+
+/// A blocked [`Layout`], with blocks ordered by `O` and the elements within each by `I`.
+///
+/// Every block is contiguous in memory. The default parameters give row-major blocks of
+/// row-major elements, which is the default layout of a [`BlockGrid`].
+///
+/// [`BlockGrid`]: crate::BlockGrid
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Blocked<O: Order = RowMajor, I: Order = RowMajor> {
+    _phantom: PhantomData<(O, I)>,
+}
+
+unsafe impl Order for RowMajor {
+    #[inline]
+    fn index((_, cols): Coords, (row, col): Coords) -> usize {
+        cols * row + col
+    }
+}
+
+unsafe impl Order for ColMajor {
+    #[inline]
+    fn index((rows, _): Coords, (row, col): Coords) -> usize {
+        rows * col + row
+    }
+}
+
+unsafe impl Order for Morton {
+    #[inline]
+    fn index((rows, cols): Coords, (row, col): Coords) -> usize {
+        let side = rows.min(cols).next_power_of_two();
+        // Every tile but the last is full, so only the position within the tile is left
+        let (base, dims, coords) = if rows > cols {
+            let start = row & !(side - 1);
+            (
+                start * cols,
+                (side.min(rows - start), cols),
+                (row - start, col),
+            )
+        } else {
+            let start = col & !(side - 1);
+            (
+                start * rows,
+                (rows, side.min(cols - start)),
+                (row, col - start),
+            )
+        };
+        if dims == (side, side) {
+            base + (spread_bits(coords.0) << 1 | spread_bits(coords.1))
+        } else {
+            base + morton_rank(side, dims, coords)
+        }
+    }
+}
+
+/// Spreads out the low half of the bits of `x` to every other bit, starting from the lowest.
+#[inline]
+fn spread_bits(x: usize) -> usize {
+    // A `usize` is at most 64 bits, and only the low half can be set for a coordinate in a
+    // square tile, since its area fits in a `usize`
+    let mut x = x as u64 & 0xFFFF_FFFF;
+    x = (x | x << 16) & 0x0000_FFFF_0000_FFFF;
+    x = (x | x << 8) & 0x00FF_00FF_00FF_00FF;
+    x = (x | x << 4) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | x << 2) & 0x3333_3333_3333_3333;
+    x = (x | x << 1) & 0x5555_5555_5555_5555;
+    x as usize
+}
+
+/// Returns the number of coordinates before `(row, col)` along a Morton curve in a square of
+/// `side`, only counting those within `rows` by `cols`.
+#[inline]
+fn morton_rank(side: usize, (rows, cols): Coords, (row, col): Coords) -> usize {
+    let count = |r0: usize, c0: usize, s: usize| {
+        rows.saturating_sub(r0).min(s) * cols.saturating_sub(c0).min(s)
+    };
+    let (mut rank, mut r0, mut c0, mut s) = (0, 0, 0, side);
+    while s > 1 {
+        let h = s / 2;
+        // Skip over the quadrants before the one with `(row, col)`
+        if row >= r0 + h {
+            rank += count(r0, c0, h) + count(r0, c0 + h, h);
+            r0 += h;
+        }
+        if col >= c0 + h {
+            rank += count(r0, c0, h);
+            c0 += h;
+        }
+        s = h;
+    }
+    rank
+}
+
+unsafe impl<O: Order, I: Order> Layout for Blocked<O, I> {
+    #[inline]
+    fn offset<B: BlockShape>(blocks: Coords, (row, col): Coords) -> usize {
+        let block_ind = O::index(blocks, (row >> B::ROW_SHIFT, col >> B::COL_SHIFT));
+        let sub_ind = I::index(
+            (B::HEIGHT, B::WIDTH),
+            (row & B::ROW_MASK, col & B::COL_MASK),
+        );
+        B::AREA * block_ind + sub_ind
+    }
+}
+
+unsafe impl<O: Order> Layout for O {
+    #[inline]
+    fn offset<B: BlockShape>((row_blocks, col_blocks): Coords, coords: Coords) -> usize {
+        O::index((row_blocks * B::HEIGHT, col_blocks * B::WIDTH), coords)
+    }
+}
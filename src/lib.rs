@@ -1,6 +1,6 @@
 //! A quick, cache-conscious, blocked 2D array.
 //!
-//! `block-grid` gives you a fixed-size 2D array with a blocked / tiled memory representation.
+//! `block-grid` gives you a 2D array with a blocked / tiled memory representation.
 //! This has the sweet benefit of being much more cache-friendly if you're often accessing nearby
 //! coordinates. It also offers a bunch of utility methods and block access. If you don't care
 //! about tiled memory and just want any 2D-grid, see the
@@ -48,7 +48,7 @@
 //!     assert_eq!(row * 6 + col, x);
 //! }
 //!
-//! # Ok::<(), ()>(())
+//! # Ok::<(), block_grid::Error>(())
 //! ```
 //!
 //! # Usage
@@ -56,8 +56,43 @@
 //! ## Types
 //!
 //! The primary type is [`BlockGrid<T, B>`], where `T` is the stored type and `B` is a generic
-//! parameter that controls the block size (all the `U*` types below). A view of a 2D block,
-//! which is stored as a contiguous piece of memory, is a [`Block`] or [`BlockMut`].
+//! parameter that controls the block size (all the `U*` types below). These are square, and are
+//! aliases of [`Shift<N>`], which can be used directly for larger sizes. Rectangular blocks of any
+//! two sizes can be made with [`Rect`], such as `Rect<U4, U64>` for blocks 4 rows high and 64
+//! columns wide. A view of a 2D block, which is stored as a contiguous
+//! piece of memory, is a [`Block`] or [`BlockMut`].
+//!
+//! A borrowed rectangular window into a grid is a [`GridView`] or [`GridViewMut`], created with
+//! [`BlockGrid::view`] and [`BlockGrid::view_mut`]. These don't have to line up with the blocks.
+//! A grid can also be split into disjoint mutable views with
+//! [`BlockGrid::split_blocks_mut`] and friends, which can then be handed to different threads.
+//! Whole regions can be copied between grids with [`BlockGrid::copy_region`], even of different
+//! block sizes, as well as filled with [`BlockGrid::fill_region`] or swapped with
+//! [`BlockGrid::swap_regions`].
+//!
+//! The memory layout can be chosen with a third parameter, as in [`BlockGrid<T, B, L>`], which
+//! defaults to row-major blocks of row-major elements. Any [`Layout`] works, like column-major
+//! blocks with [`Blocked<ColMajor>`], Morton order both across and within blocks with
+//! `Blocked<Morton, Morton>`, or a plain padded row-major array with [`RowMajor`]. Element access,
+//! the row-major, column-major and curve iterators, mapping and arithmetic work the same for all
+//! of them, so code using these can be compared across layouts. The rest of the API, like
+//! [`BlockGrid::each_iter`] and [`BlockGrid::block_iter`], needs the default layout, see
+//! [`Layout`] for details.
+//! A grid can be moved to another layout with [`BlockGrid::relayout`].
+//!
+//! A grid can be resized with [`BlockGrid::resize`]. Each row of blocks is contiguous in memory,
+//! so growing or shrinking a row of blocks at a time with [`BlockGrid::push_block_row`] and
+//! friends is quickest. A grid can also be transposed, rotated or flipped, either in place with
+//! [`BlockGrid::transpose`] and friends, or into a copy with [`BlockGrid::transposed`] and
+//! friends. These work a block at a time.
+//!
+//! For a grid without fixed bounds, use [`ChunkedGrid<T, B>`]. It only allocates the blocks that
+//! are written to, and is indexed by [`SignedCoords`], so it extends in every direction. Any
+//! rectangle of it can be copied to and from a [`BlockGrid<T, B>`].
+//!
+//! If the block size is only known at runtime, use [`DynBlockGrid<T>`] instead. It has the same
+//! interface, with the square block width given to its constructors, and can be converted to and
+//! from a [`BlockGrid<T, B>`] without copying.
 //!
 //! ## Indexing
 //!
@@ -81,13 +116,50 @@
 //!
 //! There are multiple ways of iterating over a 2D array. If you simply want to visit each
 //! element, use [`BlockGrid::each_iter`]. You can alternatively iterate in row-major order using
-//! [`BlockGrid::row_major_iter`]. Instead of iterating over elements, you can also iterate over
-//! entire blocks with [`BlockGrid::block_iter`]. For any of these, if you also need coordinates
+//! [`BlockGrid::row_major_iter`], or column-major order using [`BlockGrid::col_major_iter`].
+//! Copies in either order can be made with [`BlockGrid::to_row_major_vec`] and
+//! [`BlockGrid::to_col_major_vec`]. For better locality across rows and columns, you can also
+//! follow a space-filling curve with [`BlockGrid::morton_iter`] or [`BlockGrid::hilbert_iter`].
+//! Instead of iterating over elements, you can also iterate over entire blocks with
+//! [`BlockGrid::block_iter`]. For any of these, if you also need coordinates
 //! while iterating, you can chain a [`.coords()`][coords] call. If you only need a 1D iteration
 //! count, then there's always [`Iterator::enumerate`].
 //!
+//! To make a new grid of the same shape from each element, use [`BlockGrid::map`], or
+//! [`BlockGrid::zip_map`] to combine two. These, and their in-place versions like
+//! [`BlockGrid::map_in_place`], only visit valid elements, never any padding.
+//!
+//! A single row or column can be accessed with [`BlockGrid::row`] or [`BlockGrid::col`], and all
+//! of them with [`BlockGrid::rows_iter`] or [`BlockGrid::cols_iter`]. These give an
+//! [`iters::Line`], which can be both iterated over from either end and indexed.
+//!
 //! [coords]: CoordsIterator::coords
 //!
+//! ## Neighbourhoods
+//!
+//! The neighbours of an element can be accessed with [`BlockGrid::neighbors4`],
+//! [`BlockGrid::neighbors8`], or a whole `K` by `K` window around it with [`BlockGrid::window`].
+//! What happens at the edges of the grid is controlled by a [`Boundary`], which can skip, clamp,
+//! wrap, reflect, or fill in a constant. Since most neighbours are in the same block, these are
+//! usually quicker than indexing each neighbour individually.
+//!
+//! To compute something from the window around *every* element, use
+//! [`BlockGrid::apply_stencil`], or [`BlockGrid::convolve`] with a fixed kernel. These write to a
+//! separate output grid, and go block-by-block so only the blocks on the edges pay for the
+//! boundary handling. Large kernels that are separable, like box or Gaussian blurs, are much
+//! quicker as two 1D passes with [`BlockGrid::filter_separable`]. For sums over arbitrary
+//! rectangles, build a [`SummedAreaTable`] once with [`BlockGrid::summed_area_table`].
+//!
+//! ## Arithmetic
+//!
+//! Grids of numbers can be added, subtracted, multiplied and divided element-wise with the usual
+//! operators, either by another grid of the same dimensions or by a scalar, and the assigning
+//! versions like `+=` work too. The operators panic if the dimensions don't match, while
+//! [`BlockGrid::checked_add`] and friends return an [`Error`] instead. For reductions over the
+//! whole grid, there's [`BlockGrid::sum`], [`BlockGrid::min`], [`BlockGrid::max`] and
+//! [`BlockGrid::mean`], as well as [`BlockGrid::argmin`] and [`BlockGrid::argmax`] for their
+//! coordinates. Matrix products are computed a block at a time with [`BlockGrid::matmul`].
+//!
 //! ## Using without Blocks (Good Ol' Row-Major)
 //!
 //! If you wanna test performance against an non-blocked memory representation, you need both, or
@@ -101,13 +173,30 @@
 //!
 //! To use the [`serde`][serde] framework, enable the optional `serde` [feature] in your
 //! `Cargo.toml`. There is an important subtlety to its usage. Because the block size is generic
-//! and compile-time, you have to know `B` when deserializing. You *could* write it decide based
-//! on the input data, but I think it would lead to a bunch of extra code-gen, so I've left it
-//! out. It does, however, verify that `B` is the same value as the one originally used to
-//! serialize. If you always know the `B` value, then this shouldn't matter at all.
+//! and compile-time, you have to know `B` when deserializing. It does, however, verify that `B`
+//! is the same value as the one originally used to serialize. If you don't know the `B` value,
+//! deserialize to a [`DynBlockGrid<T>`] instead, which accepts any square block width in the
+//! data.
 //!
 //! [serde]: https://crates.io/crates/serde
 //! [feature]: https://doc.rust-lang.org/cargo/reference/features.html
+//!
+//! ## Rayon
+//!
+//! Enabling the optional `rayon` feature adds parallel iterators using [`rayon`][rayon], such as
+//! `BlockGrid::par_block_iter` and `BlockGrid::par_each_iter_mut`. These are all in the
+//! `par_iters` module. To get coordinates, chain a `.coords()` call just like the sequential
+//! iterators. Note that `rayon` itself requires `std` and a newer compiler.
+//!
+//! [rayon]: https://crates.io/crates/rayon
+//!
+//! ## Std
+//!
+//! The crate is `no_std` by default. Enabling the optional `std` feature implements
+//! [`std::error::Error`][std_error] for [`Error`], and adds `gaussian_blur` for grids of `f32`
+//! and `f64`, which needs the floating-point functions from `std`.
+//!
+//! [std_error]: https://doc.rust-lang.org/std/error/trait.Error.html
 
 #![warn(missing_docs)]
 #![warn(missing_debug_implementations)]
@@ -116,23 +205,48 @@
 
 extern crate alloc;
 
-#[cfg(test)]
+#[cfg(any(test, feature = "std"))]
 #[macro_use]
 extern crate std;
 
 mod block_grid;
 mod block_width;
+mod chunked_grid;
+mod dyn_block_grid;
+mod error;
+mod grid_view;
 pub mod iters;
+mod layout;
+mod map;
+mod neighbors;
+mod ops;
+#[cfg(feature = "rayon")]
+pub mod par_iters;
+mod region;
+mod stencil;
+mod summed_area;
 
 #[cfg(test)]
 mod tests;
 
 pub use crate::block_grid::*;
 pub use crate::block_width::*;
+pub use crate::chunked_grid::ChunkedGrid;
+pub use crate::dyn_block_grid::*;
+pub use crate::error::Error;
+pub use crate::grid_view::*;
 pub use crate::iters::CoordsIterator;
+pub use crate::layout::*;
+pub use crate::neighbors::Boundary;
+#[cfg(feature = "rayon")]
+pub use crate::par_iters::ParCoordsIterator;
+pub use crate::summed_area::SummedAreaTable;
 
 /// Type alias for a 2-tuple of indices, representing 2D coordinates.
 pub type Coords = (usize, usize);
 
+/// Type alias for a 2-tuple of signed indices, representing 2D coordinates in a [`ChunkedGrid`].
+pub type SignedCoords = (isize, isize);
+
 /// Type alias for a typical 2D grid with standard row-major memory.
 pub type Grid<T> = BlockGrid<T, U1>;
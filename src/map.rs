@@ -0,0 +1,264 @@
+use alloc::vec::Vec;
+use core::convert::Infallible;
+use core::ptr;
+
+use crate::{BlockGrid, BlockShape, Coords, Layout};
+
+impl<T, B: BlockShape, L: Layout> BlockGrid<T, B, L> {
+    /// Consumes the grid, returning one of the same shape and layout with `f` applied to each
+    /// element.
+    ///
+    /// Only valid elements are given to `f`, so it's called [`size()`][Self::size] times. Any
+    /// padding is filled with clones of the nearest result instead. See
+    /// [`map_with_coords`][Self::map_with_coords] if `f` also needs the coordinates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, U2};
+    ///
+    /// let data: Vec<_> = (0..(3 * 3)).collect();
+    /// let grid = BlockGrid::<u32, U2>::from_row_major(3, 3, &data)?;
+    ///
+    /// let grid = grid.map(|x| x as f32 / 2.0);
+    /// assert_eq!(grid[(2, 1)], 3.5);
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn map<U: Clone>(self, mut f: impl FnMut(T) -> U) -> BlockGrid<U, B, L> {
+        if !self.is_padded() {
+            let (rows, cols) = (self.rows(), self.cols());
+            let buf = self.take_raw_vec().into_iter().map(f).collect();
+            return BlockGrid::from_raw_vec(rows, cols, buf).expect("Mapped size is always valid");
+        }
+        into_ok(self.map_valid(|_, x| Ok(f(x))))
+    }
+
+    /// Consumes the grid, returning one of the same shape and layout with `f` applied to each
+    /// element and its coordinates.
+    ///
+    /// Elements are visited a block at a time. See [`map`][Self::map] for how padding is
+    /// handled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, U2};
+    ///
+    /// let grid = BlockGrid::<u8, U2>::filled(3, 4, 1)?;
+    /// let grid = grid.map_with_coords(|(row, col), x| 10 * row + col + x as usize);
+    /// assert_eq!(grid[(2, 3)], 24);
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn map_with_coords<U: Clone>(
+        self,
+        mut f: impl FnMut(Coords, T) -> U,
+    ) -> BlockGrid<U, B, L> {
+        into_ok(self.map_valid(|coords, x| Ok(f(coords, x))))
+    }
+
+    /// Consumes the grid, returning one of the same shape and layout with the fallible `f`
+    /// applied to each element, or the first error.
+    ///
+    /// See [`map`][Self::map] for details.
+    ///
+    /// # Errors
+    ///
+    /// The first error returned by `f`, after which no more elements are mapped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, U2};
+    /// use std::convert::TryFrom;
+    ///
+    /// let grid = BlockGrid::<i32, U2>::filled(2, 3, 7)?;
+    /// assert!(grid.clone().try_map(u8::try_from).is_ok());
+    ///
+    /// let mut grid = grid;
+    /// grid[(1, 2)] = -1;
+    /// assert!(grid.try_map(u8::try_from).is_err());
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn try_map<U: Clone, E>(
+        self,
+        mut f: impl FnMut(T) -> Result<U, E>,
+    ) -> Result<BlockGrid<U, B, L>, E> {
+        if !self.is_padded() {
+            let (rows, cols) = (self.rows(), self.cols());
+            let buf = self
+                .take_raw_vec()
+                .into_iter()
+                .map(f)
+                .collect::<Result<_, _>>()?;
+            return Ok(
+                BlockGrid::from_raw_vec(rows, cols, buf).expect("Mapped size is always valid")
+            );
+        }
+        self.map_valid(|_, x| f(x))
+    }
+
+    /// Consumes the grid, returning one of the same shape and layout with `f` applied to each
+    /// element and the one at the same coordinates in `other`.
+    ///
+    /// See [`map`][Self::map] for how padding is handled.
+    ///
+    /// # Panics
+    ///
+    /// If the grids have different dimensions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, U2};
+    ///
+    /// let a = BlockGrid::<u8, U2>::filled(3, 3, 2)?;
+    /// let b = BlockGrid::<u16, U2>::filled(3, 3, 300)?;
+    ///
+    /// let c = a.zip_map(&b, |x, &y| x as u16 + y);
+    /// assert!(c.row_major_iter().all(|&z| z == 302));
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn zip_map<U, V: Clone>(
+        self,
+        other: &BlockGrid<U, B, L>,
+        mut f: impl FnMut(T, &U) -> V,
+    ) -> BlockGrid<V, B, L> {
+        check_same_dims(&self, other);
+        if !self.is_padded() {
+            let (rows, cols) = (self.rows(), self.cols());
+            let buf = self
+                .take_raw_vec()
+                .into_iter()
+                .zip(other.raw())
+                .map(|(x, y)| f(x, y))
+                .collect();
+            return BlockGrid::from_raw_vec(rows, cols, buf).expect("Mapped size is always valid");
+        }
+        // SAFETY: Only valid coordinates are mapped, and the dimensions were checked above
+        into_ok(self.map_valid(|coords, x| Ok(f(x, unsafe { other.get_unchecked(coords) }))))
+    }
+
+    /// Applies `f` to each element in place.
+    ///
+    /// Padding is left as is, since it's never given to `f`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, U2};
+    ///
+    /// let mut grid = BlockGrid::<u8, U2>::filled(3, 3, 2)?;
+    /// grid.map_in_place(|x| *x *= 3);
+    /// assert_eq!(grid[(2, 2)], 6);
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn map_in_place(&mut self, f: impl FnMut(&mut T)) {
+        if self.is_padded() {
+            self.row_major_iter_mut().for_each(f);
+        } else {
+            self.raw_mut().iter_mut().for_each(f);
+        }
+    }
+
+    /// Applies `f` in place to each element and the one at the same coordinates in `other`.
+    ///
+    /// See [`map_in_place`][Self::map_in_place] for details.
+    ///
+    /// # Panics
+    ///
+    /// If the grids have different dimensions.
+    pub fn zip_with_mut<U>(&mut self, other: &BlockGrid<U, B, L>, mut f: impl FnMut(&mut T, &U)) {
+        check_same_dims(self, other);
+        if self.is_padded() {
+            for (x, y) in self.row_major_iter_mut().zip(other.row_major_iter()) {
+                f(x, y);
+            }
+        } else {
+            for (x, y) in self.raw_mut().iter_mut().zip(other.raw()) {
+                f(x, y);
+            }
+        }
+    }
+
+    /// Moves each valid element through `f`, with its coordinates, into a grid of the results.
+    ///
+    /// Elements are visited a block at a time, and row-major within each block, so the nearest
+    /// valid element to any padding has always been mapped before it. Padding is filled with
+    /// clones of those results, without calling `f`.
+    fn map_valid<U: Clone, E>(
+        self,
+        mut f: impl FnMut(Coords, T) -> Result<U, E>,
+    ) -> Result<BlockGrid<U, B, L>, E> {
+        let (rows, cols) = (self.rows(), self.cols());
+        let blocks = self.block_dims();
+        let mut src = self.take_raw_vec();
+        let len = src.len();
+        let mut buf: Vec<U> = Vec::with_capacity(len);
+        // Elements are moved out by reading, so `src` must not drop them again. If `f` or a
+        // clone panics, the elements not yet mapped and those already written are leaked, which
+        // is safe.
+        // SAFETY: All elements are either moved out or dropped below
+        unsafe { src.set_len(0) };
+        let (from, to) = (src.as_mut_ptr(), buf.as_mut_ptr());
+        let order = || {
+            let (h, w) = (B::HEIGHT, B::WIDTH);
+            (0..(blocks.0 * blocks.1)).flat_map(move |b| {
+                let (b_row, b_col) = (b / blocks.1, b % blocks.1);
+                (0..B::AREA).map(move |s| (h * b_row + s / w, w * b_col + s % w))
+            })
+        };
+        for (k, coords) in order().enumerate() {
+            let ind = L::offset::<B>(blocks, coords);
+            // SAFETY: The layout maps each padded coordinate to a distinct index below `len`, so
+            //         every element is moved or dropped once, and every index written once
+            unsafe {
+                let y = if coords.0 < rows && coords.1 < cols {
+                    match f(coords, ptr::read(from.add(ind))) {
+                        Ok(y) => y,
+                        Err(e) => {
+                            // Drop the results so far, and the elements not yet visited
+                            for (i, coords) in order().enumerate() {
+                                let ind = L::offset::<B>(blocks, coords);
+                                if i < k {
+                                    ptr::drop_in_place(to.add(ind));
+                                } else if i > k {
+                                    ptr::drop_in_place(from.add(ind));
+                                }
+                            }
+                            return Err(e);
+                        }
+                    }
+                } else {
+                    ptr::drop_in_place(from.add(ind));
+                    let nearest = (coords.0.min(rows - 1), coords.1.min(cols - 1));
+                    (*to.add(L::offset::<B>(blocks, nearest))).clone()
+                };
+                ptr::write(to.add(ind), y);
+            }
+        }
+        // SAFETY: Every index below `len` was written to exactly once
+        unsafe { buf.set_len(len) };
+        Ok(BlockGrid::from_raw_vec(rows, cols, buf).expect("Mapped size is always valid"))
+    }
+}
+
+/// Unwraps a result that can't be an error.
+#[inline]
+fn into_ok<T>(result: Result<T, Infallible>) -> T {
+    match result {
+        Ok(x) => x,
+        Err(e) => match e {},
+    }
+}
+
+/// Panics if the grids have different dimensions.
+#[inline]
+pub(crate) fn check_same_dims<T, U, B: BlockShape, L: Layout>(
+    a: &BlockGrid<T, B, L>,
+    b: &BlockGrid<U, B, L>,
+) {
+    assert!(
+        (a.rows(), a.cols()) == (b.rows(), b.cols()),
+        "Grid dimensions must match"
+    );
+}
@@ -0,0 +1,307 @@
+use crate::block_grid::calc_index;
+use crate::{BlockGrid, BlockShape, Coords};
+
+/// Controls what happens to neighbours that fall outside a grid.
+///
+/// Used by the neighbourhood methods, such as [`BlockGrid::neighbors4`] and
+/// [`BlockGrid::window`]. Each axis is handled separately, so a neighbour off a corner is resolved
+/// along both the rows and the columns.
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub enum Boundary<'a, T> {
+    /// Out-of-bounds neighbours are skipped, so they are [`None`].
+    Skip,
+    /// Coordinates are clamped to the nearest edge, repeating the edge elements.
+    Clamp,
+    /// Coordinates wrap around to the opposite edge, as if the grid was a torus.
+    Wrap,
+    /// Coordinates are mirrored about the edge, without repeating the edge elements.
+    ///
+    /// For a row `a b c d`, this extends it as `c b | a b c d | c b`.
+    Reflect,
+    /// Out-of-bounds neighbours are all the given element.
+    Constant(&'a T),
+}
+
+impl<T> Clone for Boundary<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Boundary<'_, T> {}
+
+/// Offsets of the 4 orthogonal neighbours, in row-major order.
+const OFFSETS_4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+/// Offsets of all 8 neighbours, in row-major order.
+const OFFSETS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+impl<T, B: BlockShape> BlockGrid<T, B> {
+    /// Returns the 4 orthogonally adjacent neighbours of the element at `coords`.
+    ///
+    /// The neighbours are in row-major order, so above, left, right, and then below. Any that are
+    /// out-of-bounds are handled by `boundary`, and are only ever [`None`] with
+    /// [`Boundary::Skip`].
+    ///
+    /// # Panics
+    ///
+    /// If `coords` is out-of-bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, Boundary, U2};
+    ///
+    /// let data: Vec<_> = (0..(3 * 3)).collect();
+    /// let grid = BlockGrid::<usize, U2>::from_row_major(3, 3, &data)?;
+    ///
+    /// assert_eq!(grid.neighbors4((1, 1), Boundary::Skip), [Some(&1), Some(&3), Some(&5), Some(&7)]);
+    /// assert_eq!(grid.neighbors4((0, 0), Boundary::Skip), [None, None, Some(&1), Some(&3)]);
+    /// assert_eq!(grid.neighbors4((0, 0), Boundary::Wrap), [Some(&6), Some(&2), Some(&1), Some(&3)]);
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn neighbors4<'a>(
+        &'a self,
+        coords: Coords,
+        boundary: Boundary<'a, T>,
+    ) -> [Option<&'a T>; 4] {
+        self.neighbors_at(coords, boundary, OFFSETS_4)
+    }
+
+    /// Returns all 8 neighbours of the element at `coords`, including diagonals.
+    ///
+    /// The neighbours are in row-major order, skipping the element itself. Any that are
+    /// out-of-bounds are handled by `boundary`, and are only ever [`None`] with
+    /// [`Boundary::Skip`].
+    ///
+    /// # Panics
+    ///
+    /// If `coords` is out-of-bounds.
+    pub fn neighbors8<'a>(
+        &'a self,
+        coords: Coords,
+        boundary: Boundary<'a, T>,
+    ) -> [Option<&'a T>; 8] {
+        self.neighbors_at(coords, boundary, OFFSETS_8)
+    }
+
+    /// Returns the `K` by `K` window of elements centred on `coords`.
+    ///
+    /// The window is indexed as `[row][col]`, so the element at `coords` itself is
+    /// `[K / 2][K / 2]`. Any elements that are out-of-bounds are handled by `boundary`, and are
+    /// only ever [`None`] with [`Boundary::Skip`].
+    ///
+    /// If the whole window lies within a single block, which is the case for most elements when
+    /// `K` is small compared to the block size, the elements are read directly from that block's
+    /// memory. This is much quicker than indexing each one separately.
+    ///
+    /// # Panics
+    ///
+    /// If `coords` is out-of-bounds, or if `K` is even.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, Boundary, U4};
+    ///
+    /// let data: Vec<_> = (0..(4 * 4)).collect();
+    /// let grid = BlockGrid::<usize, U4>::from_row_major(4, 4, &data)?;
+    ///
+    /// let win = grid.window::<3>((0, 1), Boundary::Clamp);
+    /// assert_eq!(win[0], [Some(&0), Some(&1), Some(&2)]);
+    /// assert_eq!(win[2], [Some(&4), Some(&5), Some(&6)]);
+    ///
+    /// // Sum up a 3x3 neighbourhood, treating the outside as zeros
+    /// let sum: usize = grid
+    ///     .window::<3>((3, 3), Boundary::Constant(&0))
+    ///     .iter()
+    ///     .flatten()
+    ///     .map(|x| *x.unwrap())
+    ///     .sum();
+    /// assert_eq!(sum, 10 + 11 + 14 + 15);
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn window<'a, const K: usize>(
+        &'a self,
+        coords: Coords,
+        boundary: Boundary<'a, T>,
+    ) -> [[Option<&'a T>; K]; K] {
+        assert!(K % 2 == 1, "Window size must be odd");
+        assert!(self.contains(coords), "Index out of bounds");
+        let (row, col) = coords;
+        let half = K / 2;
+        if row >= half && col >= half && row + half < self.rows() && col + half < self.cols() {
+            // SAFETY: Just checked that the whole window is valid
+            return unsafe { self.window_unchecked(coords) };
+        }
+
+        // Resolve each axis once, as that's all the window needs
+        let mut rows = [None; K];
+        let mut cols = [None; K];
+        for (k, (r, c)) in rows.iter_mut().zip(cols.iter_mut()).enumerate() {
+            let off = k as isize - half as isize;
+            *r = resolve_axis(row, off, self.rows(), boundary);
+            *c = resolve_axis(col, off, self.cols(), boundary);
+        }
+        let mut win = [[None; K]; K];
+        for (win_row, &r) in win.iter_mut().zip(&rows) {
+            for (x, &c) in win_row.iter_mut().zip(&cols) {
+                *x = self.resolved(r, c, boundary);
+            }
+        }
+        win
+    }
+
+    /// Returns the `K` by `K` window of elements centred on `coords`, without any bounds checking
+    /// or boundary handling.
+    ///
+    /// # Safety
+    ///
+    /// The whole window *must* be within the grid, and `K` *must* be odd.
+    pub(crate) unsafe fn window_unchecked<const K: usize>(
+        &self,
+        (row, col): Coords,
+    ) -> [[Option<&T>; K]; K] {
+        let mut win = [[None; K]; K];
+        let half = K / 2;
+        if let Some(start) = self.window_start_in_block((row, col), half) {
+            // Fast path, the window is a contiguous sub-block
+            for (i, win_row) in win.iter_mut().enumerate() {
+                let ind = start + B::WIDTH * i;
+                for (x, elem) in win_row.iter_mut().zip(&self.raw()[ind..(ind + K)]) {
+                    *x = Some(elem);
+                }
+            }
+        } else {
+            let (row, col) = (row - half, col - half);
+            for (i, win_row) in win.iter_mut().enumerate() {
+                for (j, x) in win_row.iter_mut().enumerate() {
+                    *x = Some(self.get_unchecked((row + i, col + j)));
+                }
+            }
+        }
+        win
+    }
+
+    /// Returns the neighbours at each of `offsets` from `coords`.
+    fn neighbors_at<'a, const N: usize>(
+        &'a self,
+        coords: Coords,
+        boundary: Boundary<'a, T>,
+        offsets: [(isize, isize); N],
+    ) -> [Option<&'a T>; N] {
+        assert!(self.contains(coords), "Index out of bounds");
+        let mut out = [None; N];
+        let (row, col) = coords;
+
+        if let Some(start) = self.window_start_in_block(coords, 1) {
+            // Fast path, everything is at a fixed offset within the same block
+            let centre = start + B::WIDTH + 1;
+            for (x, &(dr, dc)) in out.iter_mut().zip(&offsets) {
+                let ind = centre as isize + (B::WIDTH as isize) * dr + dc;
+                *x = Some(&self.raw()[ind as usize]);
+            }
+            return out;
+        }
+
+        for (x, &(dr, dc)) in out.iter_mut().zip(&offsets) {
+            let r = resolve_axis(row, dr, self.rows(), boundary);
+            let c = resolve_axis(col, dc, self.cols(), boundary);
+            *x = self.resolved(r, c, boundary);
+        }
+        out
+    }
+
+    /// Returns the memory index of the top-left element of the window with a radius of `half`
+    /// around `coords`, if the whole window is valid and within the same block.
+    #[inline]
+    fn window_start_in_block(&self, (row, col): Coords, half: usize) -> Option<usize> {
+        let (s_row, s_col) = (row & B::ROW_MASK, col & B::COL_MASK);
+        let fits = s_row >= half
+            && s_col >= half
+            && s_row + half < B::HEIGHT
+            && s_col + half < B::WIDTH
+            && row + half < self.rows()
+            && col + half < self.cols();
+        if !fits {
+            return None;
+        }
+        Some(calc_index::<B>(
+            self.col_blocks(),
+            (),
+            (row - half, col - half),
+        ))
+    }
+
+    /// Returns the element at the resolved coordinates, or what `boundary` says to use instead.
+    #[inline]
+    fn resolved<'a>(
+        &'a self,
+        row: Option<usize>,
+        col: Option<usize>,
+        boundary: Boundary<'a, T>,
+    ) -> Option<&'a T> {
+        match (row, col, boundary) {
+            // SAFETY: Resolved coordinates are always valid
+            (Some(r), Some(c), _) => Some(unsafe { self.get_unchecked((r, c)) }),
+            (_, _, Boundary::Constant(x)) => Some(x),
+            _ => None,
+        }
+    }
+}
+
+/// Resolves the position `pos + off` along an axis of length `len`, using `boundary` if it's
+/// out-of-bounds.
+///
+/// Returns [`None`] if there's no element in the grid to use, which is only the case for
+/// [`Boundary::Skip`] and [`Boundary::Constant`].
+pub(crate) fn resolve_axis<T>(
+    pos: usize,
+    off: isize,
+    len: usize,
+    boundary: Boundary<'_, T>,
+) -> Option<usize> {
+    let dist = off.unsigned_abs();
+    // Whether it's before the start or after the end, and how far past that edge it is
+    let (before, d) = if off < 0 {
+        match pos.checked_sub(dist) {
+            Some(p) => return Some(p),
+            None => (true, dist - pos),
+        }
+    } else {
+        match pos.checked_add(dist).filter(|&p| p < len) {
+            Some(p) => return Some(p),
+            None => (false, dist - (len - 1 - pos)),
+        }
+    };
+    let last = len - 1;
+    match boundary {
+        Boundary::Skip | Boundary::Constant(_) => None,
+        Boundary::Clamp => Some(if before { 0 } else { last }),
+        Boundary::Wrap if before => Some((len - d % len) % len),
+        Boundary::Wrap => Some((d - 1) % len),
+        Boundary::Reflect => {
+            // Reflections repeat with a period of twice the last index
+            let d = match last.checked_mul(2) {
+                Some(0) => return Some(0),
+                Some(period) => d % period,
+                None => d,
+            };
+            Some(match (before, d <= last) {
+                (true, true) => d,
+                (true, false) => 2 * last - d,
+                (false, true) => last - d,
+                (false, false) => d - last,
+            })
+        }
+    }
+}
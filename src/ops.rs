@@ -0,0 +1,361 @@
+use core::iter::Sum;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::block_grid::calc_index;
+use crate::map::check_same_dims;
+use crate::{BlockGrid, BlockShape, Coords, CoordsIterator, Error, Layout};
+
+/// Implements an element-wise binary operator, with grids or a scalar on the right.
+macro_rules! impl_bin_op {
+    ($Op: ident, $op: ident, $OpAssign: ident, $op_assign: ident) => {
+        impl<T: Copy + $Op<Output = T>, B: BlockShape, L: Layout> $Op for BlockGrid<T, B, L> {
+            type Output = Self;
+
+            #[inline]
+            fn $op(self, rhs: Self) -> Self {
+                self.$op(&rhs)
+            }
+        }
+
+        impl<T: Copy + $Op<Output = T>, B: BlockShape, L: Layout> $Op<&Self>
+            for BlockGrid<T, B, L>
+        {
+            type Output = Self;
+
+            fn $op(mut self, rhs: &Self) -> Self {
+                self.zip_valid_mut(rhs, |x, y| *x = (*x).$op(y));
+                self
+            }
+        }
+
+        impl<T: Copy + $Op<Output = T>, B: BlockShape, L: Layout> $Op for &BlockGrid<T, B, L> {
+            type Output = BlockGrid<T, B, L>;
+
+            #[inline]
+            fn $op(self, rhs: Self) -> BlockGrid<T, B, L> {
+                self.clone().$op(rhs)
+            }
+        }
+
+        impl<T: Copy + $Op<Output = T>, B: BlockShape, L: Layout> $Op<T> for BlockGrid<T, B, L> {
+            type Output = Self;
+
+            fn $op(mut self, rhs: T) -> Self {
+                self.each_valid_mut(|x| *x = (*x).$op(rhs));
+                self
+            }
+        }
+
+        impl<T: Copy + $Op<Output = T>, B: BlockShape, L: Layout> $Op<T> for &BlockGrid<T, B, L> {
+            type Output = BlockGrid<T, B, L>;
+
+            #[inline]
+            fn $op(self, rhs: T) -> BlockGrid<T, B, L> {
+                self.clone().$op(rhs)
+            }
+        }
+
+        impl<T: Copy + $OpAssign, B: BlockShape, L: Layout> $OpAssign for BlockGrid<T, B, L> {
+            #[inline]
+            fn $op_assign(&mut self, rhs: Self) {
+                self.$op_assign(&rhs);
+            }
+        }
+
+        impl<T: Copy + $OpAssign, B: BlockShape, L: Layout> $OpAssign<&Self>
+            for BlockGrid<T, B, L>
+        {
+            fn $op_assign(&mut self, rhs: &Self) {
+                self.zip_valid_mut(rhs, |x, y| x.$op_assign(y));
+            }
+        }
+
+        impl<T: Copy + $OpAssign, B: BlockShape, L: Layout> $OpAssign<T> for BlockGrid<T, B, L> {
+            fn $op_assign(&mut self, rhs: T) {
+                self.each_valid_mut(|x| x.$op_assign(rhs));
+            }
+        }
+    };
+}
+
+impl_bin_op!(Add, add, AddAssign, add_assign);
+impl_bin_op!(Sub, sub, SubAssign, sub_assign);
+impl_bin_op!(Mul, mul, MulAssign, mul_assign);
+impl_bin_op!(Div, div, DivAssign, div_assign);
+
+impl<T: Copy + Neg<Output = T>, B: BlockShape, L: Layout> Neg for BlockGrid<T, B, L> {
+    type Output = Self;
+
+    fn neg(mut self) -> Self {
+        self.each_valid_mut(|x| *x = -*x);
+        self
+    }
+}
+
+impl<T: Copy + Neg<Output = T>, B: BlockShape, L: Layout> Neg for &BlockGrid<T, B, L> {
+    type Output = BlockGrid<T, B, L>;
+
+    #[inline]
+    fn neg(self) -> BlockGrid<T, B, L> {
+        -self.clone()
+    }
+}
+
+/// Implements a `checked_*` method for each element-wise operator.
+macro_rules! impl_checked_ops {
+    ($($Op: ident, $op: ident, $checked: ident, $name: literal);+) => {
+        impl<T: Copy, B: BlockShape, L: Layout> BlockGrid<T, B, L> {
+            $(
+                #[doc = concat!("Returns the element-wise ", $name, " of two grids, or an error if")]
+                /// their dimensions don't match.
+                ///
+                #[doc = concat!("The `", stringify!($Op), "` operator panics on a mismatch instead.")]
+                /// Nothing else is checked, so integer overflow behaves just like for each
+                /// element on its own.
+                ///
+                /// # Errors
+                ///
+                /// If the dimensions don't match. See [`Error`] for details.
+                pub fn $checked(&self, rhs: &Self) -> Result<Self, Error>
+                where
+                    T: $Op<Output = T>,
+                {
+                    check_dims(self, rhs)?;
+                    Ok(self.$op(rhs))
+                }
+            )+
+        }
+    };
+}
+
+impl_checked_ops!(
+    Add, add, checked_add, "sum";
+    Sub, sub, checked_sub, "difference";
+    Mul, mul, checked_mul, "product";
+    Div, div, checked_div, "quotient"
+);
+
+impl<T: Copy, B: BlockShape, L: Layout> BlockGrid<T, B, L> {
+    /// Returns the sum of all the elements.
+    ///
+    /// Padding is skipped, so if there is any, the elements are visited in row-major order.
+    /// Otherwise, they're summed straight from memory.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, U2};
+    ///
+    /// let data: Vec<_> = (0..(3 * 3)).collect();
+    /// let grid = BlockGrid::<i32, U2>::from_row_major(3, 3, &data)?;
+    ///
+    /// assert_eq!(grid.sum(), 36);
+    /// assert_eq!((grid.min(), grid.max()), (0, 8));
+    /// assert_eq!((grid.argmin(), grid.argmax()), ((0, 0), (2, 2)));
+    /// assert_eq!((-grid * 2 + 1).max(), 1);
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn sum(&self) -> T
+    where
+        T: Sum,
+    {
+        if self.is_padded() {
+            self.row_major_iter().copied().sum()
+        } else {
+            self.raw().iter().copied().sum()
+        }
+    }
+
+    /// Returns the smallest element.
+    ///
+    /// See [`argmin`][Self::argmin] for details.
+    #[inline]
+    pub fn min(&self) -> T
+    where
+        T: PartialOrd,
+    {
+        self[self.argmin()]
+    }
+
+    /// Returns the largest element.
+    ///
+    /// See [`argmax`][Self::argmax] for details.
+    #[inline]
+    pub fn max(&self) -> T
+    where
+        T: PartialOrd,
+    {
+        self[self.argmax()]
+    }
+
+    /// Returns the coordinates of the smallest element.
+    ///
+    /// The elements are visited in row-major order, so ties go to the first. Any element that
+    /// can't be compared to the current smallest, like a NaN, never replaces it.
+    pub fn argmin(&self) -> Coords
+    where
+        T: PartialOrd,
+    {
+        self.arg_best(|x, best| x < best)
+    }
+
+    /// Returns the coordinates of the largest element.
+    ///
+    /// See [`argmin`][Self::argmin] for details.
+    pub fn argmax(&self) -> Coords
+    where
+        T: PartialOrd,
+    {
+        self.arg_best(|x, best| x > best)
+    }
+
+    /// Applies `f` to each valid element, straight from memory if there's no padding.
+    ///
+    /// Padding is skipped so that it can't cause a panic, like an integer division by zero.
+    fn each_valid_mut(&mut self, f: impl FnMut(&mut T)) {
+        if self.is_padded() {
+            self.row_major_iter_mut().for_each(f);
+        } else {
+            self.raw_mut().iter_mut().for_each(f);
+        }
+    }
+
+    /// Applies `f` to each valid element and the one at the same coordinates in `rhs`.
+    ///
+    /// See [`each_valid_mut`][Self::each_valid_mut] for details.
+    fn zip_valid_mut(&mut self, rhs: &Self, mut f: impl FnMut(&mut T, T)) {
+        check_same_dims(self, rhs);
+        if self.is_padded() {
+            for (x, &y) in self.row_major_iter_mut().zip(rhs.row_major_iter()) {
+                f(x, y);
+            }
+        } else {
+            for (x, &y) in self.raw_mut().iter_mut().zip(rhs.raw()) {
+                f(x, y);
+            }
+        }
+    }
+
+    /// Returns the coordinates of the first element that no later one is `better` than.
+    fn arg_best(&self, better: impl Fn(&T, &T) -> bool) -> Coords {
+        let mut iter = self.row_major_iter().coords();
+        let first = iter.next().expect("Grids are never empty");
+        iter.fold(
+            first,
+            |best, cur| if better(cur.1, best.1) { cur } else { best },
+        )
+        .0
+    }
+}
+
+impl<T, B: BlockShape> BlockGrid<T, B>
+where
+    T: Copy + Default + Add<Output = T> + Mul<Output = T>,
+{
+    /// Returns the matrix product of the grid with `rhs`.
+    ///
+    /// This is a blocked matrix multiplication. Each block of the output is accumulated in
+    /// place, from the blocks along the same row of blocks in `self`, and contiguous block rows
+    /// in `rhs`. Both operands stay in their blocked memory, so nothing is copied out first.
+    /// Sums start at [`T::default()`][Default::default], which is zero for all the numeric
+    /// types, and padding never takes part.
+    ///
+    /// # Panics
+    ///
+    /// If the number of columns of `self` doesn't match the number of rows of `rhs`. See
+    /// [`checked_matmul`][Self::checked_matmul] for a version that returns an error instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, U2};
+    ///
+    /// let a = BlockGrid::<i32, U2>::from_row_major(2, 3, &[1, 2, 3, 4, 5, 6])?;
+    /// let b = BlockGrid::<i32, U2>::from_row_major(3, 2, &[7, 8, 9, 10, 11, 12])?;
+    ///
+    /// let c = BlockGrid::matmul(&a, &b);
+    /// assert_eq!((c.rows(), c.cols()), (2, 2));
+    /// assert_eq!(c.to_row_major_vec(), [58, 64, 139, 154]);
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn matmul(&self, rhs: &Self) -> Self {
+        assert!(
+            self.cols() == rhs.rows(),
+            "Inner dimensions of a matrix product must match"
+        );
+        let (rows, cols, inner) = (self.rows(), rhs.cols(), self.cols());
+        let mut out = Self::filled(rows, cols, T::default()).expect("Product size is always valid");
+        let (lhs_buf, rhs_buf) = (self.raw(), rhs.raw());
+        let (lhs_col_blocks, rhs_col_blocks) = (self.col_blocks(), rhs.col_blocks());
+        for mut block in out.block_iter_mut() {
+            let (b_row, b_col) = block.coords();
+            let (h, w) = (block.rows(), block.cols());
+            let acc = block.raw_mut();
+            for k_block in 0..lhs_col_blocks {
+                let lhs = &lhs_buf[(B::AREA * (lhs_col_blocks * b_row + k_block))..][..B::AREA];
+                let k_start = B::WIDTH * k_block;
+                for sk in 0..B::WIDTH.min(inner - k_start) {
+                    // Row `k` of `rhs` is contiguous across the columns of the output block
+                    let k = k_start + sk;
+                    let start = calc_index::<B>(rhs_col_blocks, (), (k, B::WIDTH * b_col));
+                    let rhs_row = &rhs_buf[start..(start + w)];
+                    for si in 0..h {
+                        let x = lhs[B::WIDTH * si + sk];
+                        let acc_row = &mut acc[(B::WIDTH * si)..(B::WIDTH * si + w)];
+                        for (z, &y) in acc_row.iter_mut().zip(rhs_row) {
+                            *z = *z + x * y;
+                        }
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Returns the matrix product of the grid with `rhs`, or an error if the number of columns of
+    /// `self` doesn't match the number of rows of `rhs`.
+    ///
+    /// See [`matmul`][Self::matmul] for details.
+    ///
+    /// # Errors
+    ///
+    /// If the inner dimensions don't match, in which case the error has the dimensions `rhs`
+    /// was expected to have. See [`Error`] for details.
+    pub fn checked_matmul(&self, rhs: &Self) -> Result<Self, Error> {
+        let (expected, actual) = ((self.cols(), rhs.cols()), (rhs.rows(), rhs.cols()));
+        if expected != actual {
+            return Err(Error::DimensionsMismatch { expected, actual });
+        }
+        Ok(self.matmul(rhs))
+    }
+}
+
+macro_rules! impl_mean {
+    ($($F: ident),+) => {
+        $(
+            impl<B: BlockShape, L: Layout> BlockGrid<$F, B, L> {
+                /// Returns the mean of all the elements.
+                ///
+                /// See [`sum`][Self::sum] for details.
+                pub fn mean(&self) -> $F {
+                    self.sum() / self.size() as $F
+                }
+            }
+        )+
+    };
+}
+
+impl_mean!(f32, f64);
+
+/// Returns an error if the grids have different dimensions.
+#[inline]
+fn check_dims<T, B: BlockShape, L: Layout>(
+    a: &BlockGrid<T, B, L>,
+    b: &BlockGrid<T, B, L>,
+) -> Result<(), Error> {
+    let (expected, actual) = ((a.rows(), a.cols()), (b.rows(), b.cols()));
+    if expected != actual {
+        return Err(Error::DimensionsMismatch { expected, actual });
+    }
+    Ok(())
+}
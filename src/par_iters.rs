@@ -0,0 +1,474 @@
+//! Parallel iterators, using [`rayon`].
+//!
+//! Only available with the optional `rayon` feature. These are the parallel versions of the
+//! iterators in [`iters`][crate::iters], and can be created with the `par_*` methods on
+//! [`BlockGrid`]. Just like the sequential ones, coordinates can be included by chaining a
+//! [`.coords()`][ParCoordsIterator::coords] call.
+//!
+//! [`rayon`]: https://crates.io/crates/rayon
+
+use core::marker::PhantomData;
+
+use rayon::iter::plumbing::{bridge, Consumer, ProducerCallback, UnindexedConsumer};
+use rayon::prelude::*;
+
+use crate::block_grid::block_extent;
+use crate::{Block, BlockGrid, BlockMut, BlockShape, Coords};
+
+/// Provides an interface for parallel iterators that can also yield 2D coordinates.
+///
+/// This is the parallel version of [`CoordsIterator`][crate::CoordsIterator]. Note that this
+/// trait is sealed, meaning it cannot be implemented by downstream crates.
+pub trait ParCoordsIterator: ParallelIterator + private::Sealed {
+    /// Drives the iterator, yielding the coordinates of each element as well.
+    ///
+    /// This is really just an implementation detail of [`ParWithCoordsIter`], so it probably
+    /// shouldn't be used. Just use [`coords`][Self::coords] to get the coordinates.
+    #[doc(hidden)]
+    fn drive_with_coords<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<(Coords, Self::Item)>;
+
+    /// Returns a parallel iterator adapter that also gives coordinates as well as the next
+    /// value.
+    ///
+    /// The iterator returned yields 2-tuples `(coords, elem)`, where `coords` is the coordinates
+    /// of the element `elem`. These are the same coordinates as for the sequential iterators.
+    fn coords(self) -> ParWithCoordsIter<Self>
+    where
+        Self: Sized,
+    {
+        ParWithCoordsIter { iter: self }
+    }
+}
+
+/// Parallel immutable iterator over entire blocks.
+///
+/// Created by the [`BlockGrid::par_block_iter`] method.
+#[derive(Debug)]
+pub struct ParBlockIter<'a, T, B: BlockShape> {
+    col_blocks: usize,
+    rows: usize,
+    cols: usize,
+    arr: &'a [T],
+    _phantom: PhantomData<B>,
+}
+
+/// Parallel mutable iterator over entire blocks.
+///
+/// Created by the [`BlockGrid::par_block_iter_mut`] method.
+#[derive(Debug)]
+pub struct ParBlockIterMut<'a, T, B: BlockShape> {
+    col_blocks: usize,
+    rows: usize,
+    cols: usize,
+    arr: &'a mut [T],
+    _phantom: PhantomData<B>,
+}
+
+/// Parallel immutable iterator over all the elements.
+///
+/// Created by the [`BlockGrid::par_each_iter`] method.
+#[derive(Debug)]
+pub struct ParEachIter<'a, T, B: BlockShape> {
+    col_blocks: usize,
+    rows: usize,
+    cols: usize,
+    arr: &'a [T],
+    _phantom: PhantomData<B>,
+}
+
+/// Parallel mutable iterator over all the elements.
+///
+/// Created by the [`BlockGrid::par_each_iter_mut`] method.
+#[derive(Debug)]
+pub struct ParEachIterMut<'a, T, B: BlockShape> {
+    col_blocks: usize,
+    rows: usize,
+    cols: usize,
+    arr: &'a mut [T],
+    _phantom: PhantomData<B>,
+}
+
+/// Parallel immutable iterator in row-major order.
+///
+/// Created by the [`BlockGrid::par_row_major_iter`] method.
+#[derive(Debug)]
+pub struct ParRowMajorIter<'a, T, B: BlockShape> {
+    grid: &'a BlockGrid<T, B>,
+}
+
+/// A parallel iterator adapter that yields coordinates as well.
+///
+/// Created by the [`ParCoordsIterator::coords`] method.
+#[derive(Debug)]
+pub struct ParWithCoordsIter<I> {
+    iter: I,
+}
+
+impl<T: Sync, B: BlockShape + Send + Sync> BlockGrid<T, B> {
+    /// Returns a parallel iterator over all blocks, yielding [`Block`]s.
+    ///
+    /// Blocks are contiguous in memory, so this is the quickest way to process a grid in
+    /// parallel. See [`block_iter`][Self::block_iter] for details.
+    #[inline]
+    pub fn par_block_iter(&self) -> ParBlockIter<'_, T, B> {
+        ParBlockIter {
+            col_blocks: self.col_blocks(),
+            rows: self.rows(),
+            cols: self.cols(),
+            arr: self.raw(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns a parallel iterator over all the elements.
+    ///
+    /// The elements are split up by block, but not yielded in any particular order. See
+    /// [`each_iter`][Self::each_iter] for details.
+    #[inline]
+    pub fn par_each_iter(&self) -> ParEachIter<'_, T, B> {
+        ParEachIter {
+            col_blocks: self.col_blocks(),
+            rows: self.rows(),
+            cols: self.cols(),
+            arr: self.raw(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns a parallel iterator over all the elements in [row-major order][row_major].
+    ///
+    /// This is an indexed parallel iterator, so it can be zipped or collected in order. See
+    /// [`row_major_iter`][Self::row_major_iter] for details.
+    ///
+    /// [row_major]: https://en.wikipedia.org/wiki/Row-_and_column-major_order
+    #[inline]
+    pub fn par_row_major_iter(&self) -> ParRowMajorIter<'_, T, B> {
+        ParRowMajorIter { grid: self }
+    }
+}
+
+impl<T: Send, B: BlockShape + Send + Sync> BlockGrid<T, B> {
+    /// Returns a parallel mutable iterator over all blocks, yielding [`BlockMut`]s.
+    ///
+    /// See [`block_iter_mut`][Self::block_iter_mut] for details.
+    #[inline]
+    pub fn par_block_iter_mut(&mut self) -> ParBlockIterMut<'_, T, B> {
+        ParBlockIterMut {
+            col_blocks: self.col_blocks(),
+            rows: self.rows(),
+            cols: self.cols(),
+            arr: self.raw_mut(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns a parallel mutable iterator over all the elements.
+    ///
+    /// The elements are split up by block, but not yielded in any particular order. See
+    /// [`each_iter_mut`][Self::each_iter_mut] for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, ParCoordsIterator, U4};
+    /// use rayon::prelude::*;
+    ///
+    /// let mut grid = BlockGrid::<(usize, usize), U4>::new(9, 6)?;
+    /// grid.par_each_iter_mut().coords().for_each(|(c, x)| *x = c);
+    /// assert_eq!(grid[(7, 2)], (7, 2));
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    #[inline]
+    pub fn par_each_iter_mut(&mut self) -> ParEachIterMut<'_, T, B> {
+        ParEachIterMut {
+            col_blocks: self.col_blocks(),
+            rows: self.rows(),
+            cols: self.cols(),
+            arr: self.raw_mut(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// Returns the block coordinates of the `i`-th block in memory.
+#[inline]
+fn block_coords(col_blocks: usize, i: usize) -> Coords {
+    (i / col_blocks, i % col_blocks)
+}
+
+/// Returns the coordinates of the first element of the `k`-th block row in memory, along with
+/// how many of its elements are valid.
+///
+/// A block row is one row of elements within a block, so `B::WIDTH` long.
+#[inline]
+fn block_row_start<B: BlockShape>(
+    (rows, cols): Coords,
+    col_blocks: usize,
+    k: usize,
+) -> (Coords, usize) {
+    let (b_row, b_col) = block_coords(col_blocks, k / B::HEIGHT);
+    let (row, col) = (B::HEIGHT * b_row + k % B::HEIGHT, B::WIDTH * b_col);
+    let valid = if row < rows {
+        B::WIDTH.min(cols - col)
+    } else {
+        0
+    };
+    ((row, col), valid)
+}
+
+impl<'a, T: Sync, B: BlockShape + Send + Sync> ParBlockIter<'a, T, B> {
+    fn inner(self) -> impl IndexedParallelIterator<Item = (Coords, Block<'a, T, B>)> {
+        let (col_blocks, rows, cols) = (self.col_blocks, self.rows, self.cols);
+        self.arr
+            .par_chunks_exact(B::AREA)
+            .enumerate()
+            .map(move |(i, chunk)| {
+                let coords = block_coords(col_blocks, i);
+                let extent = block_extent::<B>(rows, cols, (), coords);
+                // SAFETY: `chunk` is of exactly `B::AREA` length
+                (coords, unsafe { Block::new(coords, extent, (), chunk) })
+            })
+    }
+}
+
+impl<'a, T: Sync, B: BlockShape + Send + Sync> ParallelIterator for ParBlockIter<'a, T, B> {
+    type Item = Block<'a, T, B>;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<T: Sync, B: BlockShape + Send + Sync> IndexedParallelIterator for ParBlockIter<'_, T, B> {
+    fn len(&self) -> usize {
+        self.arr.len() / B::AREA
+    }
+
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> C::Result {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> CB::Output {
+        self.inner().map(|(_, block)| block).with_producer(callback)
+    }
+}
+
+impl<T: Sync, B: BlockShape + Send + Sync> ParCoordsIterator for ParBlockIter<'_, T, B> {
+    fn drive_with_coords<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<(Coords, Self::Item)>,
+    {
+        self.inner().drive_unindexed(consumer)
+    }
+}
+
+impl<'a, T: Send, B: BlockShape + Send + Sync> ParBlockIterMut<'a, T, B> {
+    fn inner(self) -> impl IndexedParallelIterator<Item = (Coords, BlockMut<'a, T, B>)> {
+        let (col_blocks, rows, cols) = (self.col_blocks, self.rows, self.cols);
+        self.arr
+            .par_chunks_exact_mut(B::AREA)
+            .enumerate()
+            .map(move |(i, chunk)| {
+                let coords = block_coords(col_blocks, i);
+                let extent = block_extent::<B>(rows, cols, (), coords);
+                // SAFETY: `chunk` is of exactly `B::AREA` length
+                (coords, unsafe { BlockMut::new(coords, extent, (), chunk) })
+            })
+    }
+}
+
+impl<'a, T: Send, B: BlockShape + Send + Sync> ParallelIterator for ParBlockIterMut<'a, T, B> {
+    type Item = BlockMut<'a, T, B>;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<T: Send, B: BlockShape + Send + Sync> IndexedParallelIterator for ParBlockIterMut<'_, T, B> {
+    fn len(&self) -> usize {
+        self.arr.len() / B::AREA
+    }
+
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> C::Result {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> CB::Output {
+        self.inner().map(|(_, block)| block).with_producer(callback)
+    }
+}
+
+impl<T: Send, B: BlockShape + Send + Sync> ParCoordsIterator for ParBlockIterMut<'_, T, B> {
+    fn drive_with_coords<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<(Coords, Self::Item)>,
+    {
+        self.inner().drive_unindexed(consumer)
+    }
+}
+
+impl<'a, T: Sync, B: BlockShape + Send + Sync> ParEachIter<'a, T, B> {
+    fn inner(self) -> impl ParallelIterator<Item = (Coords, &'a T)> {
+        let (col_blocks, size) = (self.col_blocks, (self.rows, self.cols));
+        // Split up by rows within blocks, which are contiguous, and skip over any padding
+        self.arr
+            .par_chunks_exact(B::WIDTH)
+            .enumerate()
+            .flat_map_iter(move |(k, chunk)| {
+                let ((row, col), valid) = block_row_start::<B>(size, col_blocks, k);
+                chunk[..valid]
+                    .iter()
+                    .enumerate()
+                    .map(move |(j, x)| ((row, col + j), x))
+            })
+    }
+}
+
+impl<'a, T: Sync, B: BlockShape + Send + Sync> ParallelIterator for ParEachIter<'a, T, B> {
+    type Item = &'a T;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        self.inner().map(|(_, x)| x).drive_unindexed(consumer)
+    }
+}
+
+impl<T: Sync, B: BlockShape + Send + Sync> ParCoordsIterator for ParEachIter<'_, T, B> {
+    fn drive_with_coords<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<(Coords, Self::Item)>,
+    {
+        self.inner().drive_unindexed(consumer)
+    }
+}
+
+impl<'a, T: Send, B: BlockShape + Send + Sync> ParEachIterMut<'a, T, B> {
+    fn inner(self) -> impl ParallelIterator<Item = (Coords, &'a mut T)> {
+        let (col_blocks, size) = (self.col_blocks, (self.rows, self.cols));
+        // Split up by rows within blocks, which are contiguous, and skip over any padding
+        self.arr
+            .par_chunks_exact_mut(B::WIDTH)
+            .enumerate()
+            .flat_map_iter(move |(k, chunk)| {
+                let ((row, col), valid) = block_row_start::<B>(size, col_blocks, k);
+                chunk[..valid]
+                    .iter_mut()
+                    .enumerate()
+                    .map(move |(j, x)| ((row, col + j), x))
+            })
+    }
+}
+
+impl<'a, T: Send, B: BlockShape + Send + Sync> ParallelIterator for ParEachIterMut<'a, T, B> {
+    type Item = &'a mut T;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        self.inner().map(|(_, x)| x).drive_unindexed(consumer)
+    }
+}
+
+impl<T: Send, B: BlockShape + Send + Sync> ParCoordsIterator for ParEachIterMut<'_, T, B> {
+    fn drive_with_coords<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<(Coords, Self::Item)>,
+    {
+        self.inner().drive_unindexed(consumer)
+    }
+}
+
+impl<'a, T: Sync, B: BlockShape + Send + Sync> ParRowMajorIter<'a, T, B> {
+    fn inner(self) -> impl IndexedParallelIterator<Item = (Coords, &'a T)> {
+        let grid = self.grid;
+        (0..grid.size()).into_par_iter().map(move |i| {
+            let coords = (i / grid.cols(), i % grid.cols());
+            // SAFETY: `i` is less than the size, so `coords` is a valid index
+            (coords, unsafe { grid.get_unchecked(coords) })
+        })
+    }
+}
+
+impl<'a, T: Sync, B: BlockShape + Send + Sync> ParallelIterator for ParRowMajorIter<'a, T, B> {
+    type Item = &'a T;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<T: Sync, B: BlockShape + Send + Sync> IndexedParallelIterator for ParRowMajorIter<'_, T, B> {
+    fn len(&self) -> usize {
+        self.grid.size()
+    }
+
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> C::Result {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> CB::Output {
+        self.inner().map(|(_, x)| x).with_producer(callback)
+    }
+}
+
+impl<T: Sync, B: BlockShape + Send + Sync> ParCoordsIterator for ParRowMajorIter<'_, T, B> {
+    fn drive_with_coords<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<(Coords, Self::Item)>,
+    {
+        self.inner().drive_unindexed(consumer)
+    }
+}
+
+impl<I: ParCoordsIterator> ParallelIterator for ParWithCoordsIter<I> {
+    type Item = (Coords, I::Item);
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        self.iter.drive_with_coords(consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        self.iter.opt_len()
+    }
+}
+
+/// Prevent users from implementing the `ParCoordsIterator` trait.
+mod private {
+    use super::*;
+    pub trait Sealed {}
+    impl<T, B: BlockShape> Sealed for ParBlockIter<'_, T, B> {}
+    impl<T, B: BlockShape> Sealed for ParBlockIterMut<'_, T, B> {}
+    impl<T, B: BlockShape> Sealed for ParEachIter<'_, T, B> {}
+    impl<T, B: BlockShape> Sealed for ParEachIterMut<'_, T, B> {}
+    impl<T, B: BlockShape> Sealed for ParRowMajorIter<'_, T, B> {}
+}
@@ -0,0 +1,185 @@
+use core::ops::Range;
+
+use crate::block_grid::{calc_blocks, calc_index};
+use crate::{BlockGrid, BlockShape, Coords};
+
+impl<T, B: BlockShape> BlockGrid<T, B> {
+    /// Swaps the rectangular region of `size` elements, `(rows, cols)`, starting at `origin`
+    /// with the one starting at `other_origin` in `other`, which may have a different block
+    /// size.
+    ///
+    /// See [`copy_region`][Self::copy_region] for how the elements are moved.
+    ///
+    /// # Panics
+    ///
+    /// If either region does not fit in its grid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, U2, U4};
+    ///
+    /// let mut a = BlockGrid::<u8, U2>::filled(4, 4, 1)?;
+    /// let mut b = BlockGrid::<u8, U4>::filled(3, 5, 2)?;
+    ///
+    /// a.swap_regions((1, 1), &mut b, (0, 2), (2, 3));
+    /// assert_eq!(a[(0, 0)], 1);
+    /// assert_eq!(a[(2, 3)], 2);
+    /// assert_eq!(b[(1, 4)], 1);
+    /// assert_eq!(b[(2, 4)], 2);
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn swap_regions<B2: BlockShape>(
+        &mut self,
+        origin: Coords,
+        other: &mut BlockGrid<T, B2>,
+        other_origin: Coords,
+        size: Coords,
+    ) {
+        let dst = RegionPart::new(self, origin, size);
+        let src = RegionPart::new(other, other_origin, size);
+        let (a, b) = (self.raw_mut(), other.raw_mut());
+        for_each_run::<B, B2>(dst, src, size, |x, y| a[x].swap_with_slice(&mut b[y]));
+    }
+}
+
+impl<T: Clone, B: BlockShape> BlockGrid<T, B> {
+    /// Copies the rectangular region of `size` elements, `(rows, cols)`, starting at
+    /// `src_origin` in `src` to the one starting at `dst_origin`. The source may have a
+    /// different block size.
+    ///
+    /// If the block sizes match, and the regions cover the same whole blocks in both grids,
+    /// each block is copied as one slice. Otherwise, each row is copied in segments that are
+    /// contiguous in both grids, so at most one per block.
+    ///
+    /// # Panics
+    ///
+    /// If either region does not fit in its grid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, U2, U4};
+    ///
+    /// let data: Vec<_> = (0..(4 * 4)).collect();
+    /// let src = BlockGrid::<usize, U4>::from_row_major(4, 4, &data)?;
+    /// let mut dst = BlockGrid::<usize, U2>::filled(3, 6, 0)?;
+    ///
+    /// dst.copy_region(&src, (1, 2), (0, 3), (2, 2));
+    /// assert_eq!(dst.row(0).copied().collect::<Vec<_>>(), [0, 0, 0, 6, 7, 0]);
+    /// assert_eq!(dst.row(1).copied().collect::<Vec<_>>(), [0, 0, 0, 10, 11, 0]);
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn copy_region<B2: BlockShape>(
+        &mut self,
+        src: &BlockGrid<T, B2>,
+        src_origin: Coords,
+        dst_origin: Coords,
+        size: Coords,
+    ) {
+        let dst_part = RegionPart::new(self, dst_origin, size);
+        let src_part = RegionPart::new(src, src_origin, size);
+        let (a, b) = (self.raw_mut(), src.raw());
+        for_each_run::<B, B2>(dst_part, src_part, size, |x, y| {
+            a[x].clone_from_slice(&b[y]);
+        });
+    }
+
+    /// Fills the rectangular region of `size` elements, `(rows, cols)`, starting at `origin`
+    /// with clones of `value`.
+    ///
+    /// Whole blocks in the region are filled as one slice, and the rest a row segment at a
+    /// time.
+    ///
+    /// # Panics
+    ///
+    /// If the region does not fit in the grid.
+    pub fn fill_region(&mut self, origin: Coords, size: Coords, value: T) {
+        let part = RegionPart::new(self, origin, size);
+        let buf = self.raw_mut();
+        for_each_run::<B, B>(part, part, size, |x, _| buf[x].fill(value.clone()));
+    }
+}
+
+/// A region of a grid, as needed to find the memory of its rows.
+#[derive(Clone, Copy)]
+struct RegionPart {
+    dims: Coords,
+    col_blocks: usize,
+    origin: Coords,
+}
+
+impl RegionPart {
+    fn new<T, B: BlockShape>(grid: &BlockGrid<T, B>, origin: Coords, size: Coords) -> Self {
+        let dims = (grid.rows(), grid.cols());
+        let fits = |s: usize, n: usize, len: usize| s.checked_add(n).map_or(false, |e| e <= len);
+        assert!(
+            fits(origin.0, size.0, dims.0) && fits(origin.1, size.1, dims.1),
+            "Region out of bounds"
+        );
+        Self {
+            dims,
+            col_blocks: grid.col_blocks(),
+            origin,
+        }
+    }
+
+    /// Returns `true` if the region starts at a block, and ends at one or at the grid's edge.
+    fn is_block_aligned<B: BlockShape>(&self, (rows, cols): Coords) -> bool {
+        let aligned = |s: usize, n: usize, len: usize, dim: usize| {
+            s % dim == 0 && ((s + n) % dim == 0 || s + n == len)
+        };
+        aligned(self.origin.0, rows, self.dims.0, B::HEIGHT)
+            && aligned(self.origin.1, cols, self.dims.1, B::WIDTH)
+    }
+}
+
+/// Calls `f` with the ranges of memory of `dst` and `src` that hold the same elements of the
+/// regions, in order.
+///
+/// If the blocks line up, each range is a whole block, including any padding at the grid's edge.
+/// Otherwise, each is the longest segment of a row that's contiguous in both grids.
+fn for_each_run<B: BlockShape, B2: BlockShape>(
+    dst: RegionPart,
+    src: RegionPart,
+    (rows, cols): Coords,
+    mut f: impl FnMut(Range<usize>, Range<usize>),
+) {
+    let same_blocks = (B::HEIGHT, B::WIDTH) == (B2::HEIGHT, B2::WIDTH);
+    if same_blocks
+        && dst.is_block_aligned::<B>((rows, cols))
+        && src.is_block_aligned::<B>((rows, cols))
+    {
+        let block_start = |part: &RegionPart, (b_row, b_col): Coords| {
+            let b_row = (part.origin.0 >> B::ROW_SHIFT) + b_row;
+            let b_col = (part.origin.1 >> B::COL_SHIFT) + b_col;
+            B::AREA * (part.col_blocks * b_row + b_col)
+        };
+        let (row_blocks, col_blocks) = (calc_blocks(rows, B::HEIGHT), calc_blocks(cols, B::WIDTH));
+        for b_row in 0..row_blocks {
+            for b_col in 0..col_blocks {
+                let (a, b) = (
+                    block_start(&dst, (b_row, b_col)),
+                    block_start(&src, (b_row, b_col)),
+                );
+                f(a..(a + B::AREA), b..(b + B::AREA));
+            }
+        }
+        return;
+    }
+    for row in 0..rows {
+        let mut col = 0;
+        while col < cols {
+            let (dst_row, dst_col) = (dst.origin.0 + row, dst.origin.1 + col);
+            let (src_row, src_col) = (src.origin.0 + row, src.origin.1 + col);
+            // Rows are contiguous up to the edge of a block in either grid
+            let n = (B::WIDTH - (dst_col & B::COL_MASK))
+                .min(B2::WIDTH - (src_col & B2::COL_MASK))
+                .min(cols - col);
+            let a = calc_index::<B>(dst.col_blocks, (), (dst_row, dst_col));
+            let b = calc_index::<B2>(src.col_blocks, (), (src_row, src_col));
+            f(a..(a + n), b..(b + n));
+            col += n;
+        }
+    }
+}
@@ -0,0 +1,311 @@
+use alloc::vec;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+use core::iter::Sum;
+use core::ops::Mul;
+
+use crate::block_grid::calc_index;
+use crate::neighbors::resolve_axis;
+use crate::{BlockGrid, BlockShape, Boundary};
+
+impl<T, B: BlockShape> BlockGrid<T, B> {
+    /// Applies a stencil to the `K` by `K` window around every element, writing the results to
+    /// the same coordinates in `out`.
+    ///
+    /// The window passed to `f` is the same as from [`window`], including how `boundary` handles
+    /// the edges. The grid is processed block-by-block, in memory order of `out`. Blocks far
+    /// enough from the edges that their whole halo is valid skip the boundary handling entirely,
+    /// so it's only paid for around the edges of the grid.
+    ///
+    /// [`window`]: Self::window
+    ///
+    /// # Panics
+    ///
+    /// If the dimensions of `out` don't match, or if `K` is even.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, Boundary, U2};
+    ///
+    /// let data = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+    /// let grid = BlockGrid::<u32, U2>::from_row_major(3, 3, &data)?;
+    /// let mut out = BlockGrid::<u32, U2>::new(3, 3)?;
+    ///
+    /// // Largest element in each 3x3 neighbourhood
+    /// grid.apply_stencil(&mut out, Boundary::Skip, |win: [[Option<&u32>; 3]; 3]| {
+    ///     win.iter().flatten().flatten().map(|&&x| x).max().unwrap()
+    /// });
+    /// assert!(out.row_major_iter().copied().eq([5, 6, 6, 8, 9, 9, 8, 9, 9]));
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn apply_stencil<'a, U, F, const K: usize>(
+        &'a self,
+        out: &mut BlockGrid<U, B>,
+        boundary: Boundary<'a, T>,
+        mut f: F,
+    ) where
+        F: FnMut([[Option<&'a T>; K]; K]) -> U,
+    {
+        assert!(K % 2 == 1, "Window size must be odd");
+        assert!(
+            (self.rows(), self.cols()) == (out.rows(), out.cols()),
+            "Grid dimensions must match"
+        );
+        let half = K / 2;
+        for mut block in out.block_iter_mut() {
+            let (row, col) = block.starts_at();
+            let interior = row >= half
+                && col >= half
+                && row + block.rows() + half <= self.rows()
+                && col + block.cols() + half <= self.cols();
+            // SAFETY: Coordinates are within the block's extent, and so also in the grid. For
+            //         interior blocks, the whole window around each element is also in the grid.
+            unsafe {
+                if interior {
+                    for i in 0..block.rows() {
+                        for j in 0..block.cols() {
+                            let win = self.window_unchecked((row + i, col + j));
+                            *block.get_unchecked_mut((i, j)) = f(win);
+                        }
+                    }
+                } else {
+                    for i in 0..block.rows() {
+                        for j in 0..block.cols() {
+                            let win = self.window((row + i, col + j), boundary);
+                            *block.get_unchecked_mut((i, j)) = f(win);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Convolves the grid with a `K` by `K` kernel, writing the results to `out`.
+    ///
+    /// Each output element is the sum of the window around it multiplied element-wise by
+    /// `kernel`. The kernel is not flipped, so strictly speaking this is a cross-correlation,
+    /// which is the same thing for symmetric kernels. Elements skipped by [`Boundary::Skip`] are
+    /// left out of the sum. See [`apply_stencil`] for details.
+    ///
+    /// [`apply_stencil`]: Self::apply_stencil
+    ///
+    /// # Panics
+    ///
+    /// If the dimensions of `out` don't match, or if `K` is even.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, Boundary, U4};
+    ///
+    /// let data: Vec<i32> = (0..(5 * 5)).map(|x| x * x).collect();
+    /// let grid = BlockGrid::<i32, U4>::from_row_major(5, 5, &data)?;
+    /// let mut out = BlockGrid::<i32, U4>::new(5, 5)?;
+    ///
+    /// let laplacian = [[0, 1, 0], [1, -4, 1], [0, 1, 0]];
+    /// grid.convolve(&mut out, &laplacian, Boundary::Clamp);
+    /// assert_eq!(out[(2, 2)], 11 * 11 + 13 * 13 + 7 * 7 + 17 * 17 - 4 * 12 * 12);
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn convolve<W, U, const K: usize>(
+        &self,
+        out: &mut BlockGrid<U, B>,
+        kernel: &[[W; K]; K],
+        boundary: Boundary<'_, T>,
+    ) where
+        T: Copy + Mul<W, Output = U>,
+        W: Copy,
+        U: Sum,
+    {
+        self.apply_stencil(out, boundary, |win: [[Option<&T>; K]; K]| {
+            win.iter()
+                .flatten()
+                .zip(kernel.iter().flatten())
+                .filter_map(|(x, &w)| x.map(|&x| x * w))
+                .sum()
+        });
+    }
+
+    /// Applies a separable filter, given by a horizontal and a vertical 1D kernel, writing the
+    /// results to `out`.
+    ///
+    /// This gives the same results as [`convolve`] with the 2D kernel
+    /// `kernel[i][j] = kernel_y[i] * kernel_x[j]`, including how `boundary` handles the edges. It
+    /// is done as a horizontal pass over every element into a scratch grid, and then a vertical
+    /// pass over that. So, a `K` by `K` kernel only takes `2 * K` multiplications per element
+    /// instead of `K * K`, which adds up quickly for large kernels.
+    ///
+    /// Both passes go block-by-block. Wherever all the taps of the kernel land in the same block,
+    /// they are read directly from its memory, which for the vertical pass means a fixed stride
+    /// of `B::WIDTH` within the block. The taller the blocks, the more often that's the case.
+    ///
+    /// [`convolve`]: Self::convolve
+    ///
+    /// # Panics
+    ///
+    /// If the dimensions of `out` don't match, or if the length of either kernel is even.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use block_grid::{BlockGrid, Boundary, U4};
+    ///
+    /// let data: Vec<i32> = (0..(6 * 6)).collect();
+    /// let grid = BlockGrid::<i32, U4>::from_row_major(6, 6, &data)?;
+    /// let (mut sep, mut full) = (BlockGrid::new(6, 6)?, BlockGrid::new(6, 6)?);
+    ///
+    /// // Sobel operator for horizontal gradients
+    /// grid.filter_separable(&mut sep, &[-1, 0, 1], &[1, 2, 1], Boundary::Reflect);
+    /// let sobel = [[-1, 0, 1], [-2, 0, 2], [-1, 0, 1]];
+    /// grid.convolve(&mut full, &sobel, Boundary::Reflect);
+    /// assert_eq!(sep, full);
+    /// assert_eq!(sep[(2, 2)], 8);
+    /// # Ok::<(), block_grid::Error>(())
+    /// ```
+    pub fn filter_separable<W, U>(
+        &self,
+        out: &mut BlockGrid<U, B>,
+        kernel_x: &[W],
+        kernel_y: &[W],
+        boundary: Boundary<'_, T>,
+    ) where
+        T: Copy + Mul<W, Output = U>,
+        W: Copy,
+        U: Copy + Default + Mul<W, Output = U> + Sum,
+    {
+        assert!(
+            kernel_x.len() % 2 == 1 && kernel_y.len() % 2 == 1,
+            "Kernel length must be odd"
+        );
+        assert!(
+            (self.rows(), self.cols()) == (out.rows(), out.cols()),
+            "Grid dimensions must match"
+        );
+        let mut scratch = BlockGrid::<U, B>::new(self.rows(), self.cols())
+            .expect("Same dimensions are always valid");
+        filter_pass(self, &mut scratch, kernel_x, boundary, false);
+
+        // A constant outside of the grid would also have gone through the horizontal pass
+        let pad;
+        let boundary = match boundary {
+            Boundary::Skip => Boundary::Skip,
+            Boundary::Clamp => Boundary::Clamp,
+            Boundary::Wrap => Boundary::Wrap,
+            Boundary::Reflect => Boundary::Reflect,
+            Boundary::Constant(&x) => {
+                pad = kernel_x.iter().map(|&w| x * w).sum();
+                Boundary::Constant(&pad)
+            }
+        };
+        filter_pass(&scratch, out, kernel_y, boundary, true);
+    }
+}
+
+/// Applies the 1D `kernel` to every element of `src` down the columns if `vertical`, or along the
+/// rows otherwise, writing the results to `dst`.
+fn filter_pass<S, W, U, B>(
+    src: &BlockGrid<S, B>,
+    dst: &mut BlockGrid<U, B>,
+    kernel: &[W],
+    boundary: Boundary<'_, S>,
+    vertical: bool,
+) where
+    S: Copy + Mul<W, Output = U>,
+    W: Copy,
+    U: Sum,
+    B: BlockShape,
+{
+    let half = kernel.len() / 2;
+    // Along the pass, the length of the grid, size of the blocks, and memory stride
+    let (len, dim, step) = if vertical {
+        (src.rows(), B::HEIGHT, B::WIDTH)
+    } else {
+        (src.cols(), B::WIDTH, 1)
+    };
+    let raw = src.raw();
+    for mut block in dst.block_iter_mut() {
+        let (row, col) = block.starts_at();
+        for i in 0..block.rows() {
+            for j in 0..block.cols() {
+                let (r, c) = (row + i, col + j);
+                let (pos, sub) = if vertical { (r, i) } else { (c, j) };
+                let x = if sub >= half && sub + half < dim && pos + half < len {
+                    // All the taps are valid and in the same block
+                    let start = calc_index::<B>(src.col_blocks(), (), (r, c)) - step * half;
+                    let taps = raw[start..].iter().step_by(step);
+                    taps.zip(kernel).map(|(&x, &w)| x * w).sum()
+                } else {
+                    let taps = kernel.iter().enumerate().filter_map(|(t, &w)| {
+                        let off = t as isize - half as isize;
+                        let x = match (resolve_axis(pos, off, len, boundary), boundary) {
+                            (Some(p), _) => {
+                                let coords = if vertical { (p, c) } else { (r, p) };
+                                // SAFETY: Resolved coordinates are always valid
+                                unsafe { *src.get_unchecked(coords) }
+                            }
+                            (None, Boundary::Constant(&x)) => x,
+                            (None, _) => return None,
+                        };
+                        Some(x * w)
+                    });
+                    taps.sum()
+                };
+                // SAFETY: Coordinates are within the block's extent
+                unsafe { *block.get_unchecked_mut((i, j)) = x };
+            }
+        }
+    }
+}
+
+macro_rules! impl_blurs {
+    ($($F: ident),+) => {
+        $(
+            impl<B: BlockShape> BlockGrid<$F, B> {
+                /// Blurs the grid with a square box of `2 * radius + 1` elements on each side,
+                /// writing the results to `out`.
+                ///
+                /// Each output element is the mean of the box around it. This is done in two 1D
+                /// passes with [`filter_separable`][Self::filter_separable], so the cost only
+                /// grows linearly with the radius. Note that with [`Boundary::Skip`], the skipped
+                /// elements count as zeros, so the edges are darkened.
+                ///
+                /// # Panics
+                ///
+                /// If the dimensions of `out` don't match.
+                pub fn box_blur(&self, out: &mut Self, radius: usize, boundary: Boundary<'_, $F>) {
+                    let len = 2 * radius + 1;
+                    let kernel = vec![1.0 / len as $F; len];
+                    self.filter_separable(out, &kernel, &kernel, boundary);
+                }
+
+                /// Blurs the grid with a Gaussian of standard deviation `sigma`, writing the
+                /// results to `out`.
+                ///
+                /// The kernel is cut off at `3 * sigma` on either side, and normalized to sum to
+                /// one. This is done in two 1D passes with
+                /// [`filter_separable`][Self::filter_separable]. Only available with the `std`
+                /// feature. Note that with [`Boundary::Skip`], the skipped elements count as
+                /// zeros, so the edges are darkened.
+                ///
+                /// # Panics
+                ///
+                /// If the dimensions of `out` don't match, or if `sigma` isn't positive and
+                /// finite.
+                #[cfg(feature = "std")]
+                pub fn gaussian_blur(&self, out: &mut Self, sigma: $F, boundary: Boundary<'_, $F>) {
+                    assert!(sigma > 0.0 && sigma.is_finite(), "Sigma must be positive and finite");
+                    let radius = (3.0 * sigma).ceil() as isize;
+                    let mut kernel: Vec<$F> = (-radius..=radius)
+                        .map(|x| (-((x * x) as $F) / (2.0 * sigma * sigma)).exp())
+                        .collect();
+                    let total: $F = kernel.iter().sum();
+                    kernel.iter_mut().for_each(|w| *w /= total);
+                    self.filter_separable(out, &kernel, &kernel, boundary);
+                }
+            }
+        )+
+    };
+}
+
+impl_blurs!(f32, f64);
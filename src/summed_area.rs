@@ -0,0 +1,139 @@
+use core::ops::{Add, Sub};
+
+use crate::{BlockGrid, BlockShape, Coords, CoordsIterator};
+
+/// A summed-area table, also known as an integral image, of a [`BlockGrid`].
+///
+/// Created by the [`BlockGrid::summed_area_table`] method. Each element of the table is the sum of
+/// all the elements above and to the left of it in the original grid, inclusive. This means the
+/// sum of any rectangle can then be found in constant time with [`rect_sum`].
+///
+/// The sums are stored in a [`BlockGrid`] with the same block size, and are of type `A`, the
+/// accumulator. This is chosen when creating the table, and should be large enough to hold the
+/// sum of the entire grid without overflowing. For example, a grid of `u8` would typically use
+/// `u32` or `u64`.
+///
+/// [`rect_sum`]: Self::rect_sum
+///
+/// # Example
+///
+/// ```
+/// use block_grid::{BlockGrid, U4};
+///
+/// let grid = BlockGrid::<u8, U4>::filled(100, 100, 255)?;
+/// let sat = grid.summed_area_table::<u32>();
+///
+/// assert_eq!(sat.rect_sum((0, 0), (100, 100)), 255 * 100 * 100);
+/// assert_eq!(sat.rect_sum((10, 20), (13, 24)), 255 * 3 * 4);
+/// assert_eq!(sat.rect_sum((5, 5), (5, 50)), 0);
+/// # Ok::<(), block_grid::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct SummedAreaTable<A, B: BlockShape> {
+    sums: BlockGrid<A, B>,
+}
+
+impl<T, B: BlockShape> BlockGrid<T, B> {
+    /// Returns the summed-area table of the grid, with sums of type `A`.
+    ///
+    /// The table is built in a single pass in memory order, using [`each_iter`], rather than
+    /// row-major order. This works because the sums above and to the left of each element are
+    /// always in either the same block, or a block earlier in memory. See [`SummedAreaTable`] for
+    /// details.
+    ///
+    /// [`each_iter`]: Self::each_iter
+    pub fn summed_area_table<A>(&self) -> SummedAreaTable<A, B>
+    where
+        T: Copy + Into<A>,
+        A: Copy + Default + Add<Output = A> + Sub<Output = A>,
+    {
+        let mut sums = BlockGrid::<A, B>::new(self.rows(), self.cols())
+            .expect("Same dimensions are always valid");
+        for ((row, col), &x) in self.each_iter().coords() {
+            // SAFETY: Only coordinates above and to the left are read, which are valid, and
+            //         they've already been visited in memory order.
+            unsafe {
+                let sum_at = |r: usize, c: usize| *sums.get_unchecked((r, c));
+                let sum = match (row, col) {
+                    (0, 0) => x.into(),
+                    (0, _) => x.into() + sum_at(0, col - 1),
+                    (_, 0) => x.into() + sum_at(row - 1, 0),
+                    _ => {
+                        let (up, left) = (sum_at(row - 1, col), sum_at(row, col - 1));
+                        // The column above minus its overlap with `left` keeps every
+                        // intermediate result within the final sum, so it can't overflow
+                        x.into() + (up - sum_at(row - 1, col - 1)) + left
+                    }
+                };
+                *sums.get_unchecked_mut((row, col)) = sum;
+            }
+        }
+        SummedAreaTable { sums }
+    }
+}
+
+impl<A, B: BlockShape> SummedAreaTable<A, B> {
+    /// Returns the number of rows of the original grid.
+    #[inline]
+    pub fn rows(&self) -> usize {
+        self.sums.rows()
+    }
+
+    /// Returns the number of columns of the original grid.
+    #[inline]
+    pub fn cols(&self) -> usize {
+        self.sums.cols()
+    }
+
+    /// Returns the grid of sums, where each element is the sum of all elements above and to the
+    /// left of it, inclusive.
+    #[inline]
+    pub fn sums(&self) -> &BlockGrid<A, B> {
+        &self.sums
+    }
+
+    /// Consumes the table, returning the grid of sums.
+    #[inline]
+    pub fn into_sums(self) -> BlockGrid<A, B> {
+        self.sums
+    }
+}
+
+impl<A, B> SummedAreaTable<A, B>
+where
+    A: Copy + Default + Add<Output = A> + Sub<Output = A>,
+    B: BlockShape,
+{
+    /// Returns the sum of the rectangle from `start` up to, but not including, `end`.
+    ///
+    /// So, this is the sum of all elements with rows in `start.0..end.0` and columns in
+    /// `start.1..end.1`, which takes constant time. An empty rectangle sums to the default value
+    /// of `A`. The subtractions are ordered so that no intermediate result is ever negative, so
+    /// unsigned types are fine as well.
+    ///
+    /// # Panics
+    ///
+    /// If `start` is after `end`, or `end` is past the last row or column.
+    pub fn rect_sum(&self, start: Coords, end: Coords) -> A {
+        let ((r0, c0), (r1, c1)) = (start, end);
+        assert!(
+            r0 <= r1 && c0 <= c1 && r1 <= self.rows() && c1 <= self.cols(),
+            "Rectangle out of bounds"
+        );
+        if r0 == r1 || c0 == c1 {
+            return A::default();
+        }
+        // Sum of everything above and to the left of `(r, c)`, exclusive
+        let sum_to = |r: usize, c: usize| {
+            if r == 0 || c == 0 {
+                return A::default();
+            }
+            // SAFETY: Checked above that the rectangle is within the grid
+            unsafe { *self.sums.get_unchecked((r - 1, c - 1)) }
+        };
+        // Rows from `r0` to `r1`, for columns up to `c1` and then up to `c0`
+        let right = sum_to(r1, c1) - sum_to(r0, c1);
+        let left = sum_to(r1, c0) - sum_to(r0, c0);
+        right - left
+    }
+}
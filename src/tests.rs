@@ -1,3 +1,5 @@
+use core::convert::TryFrom;
+use std::rc::Rc;
 use std::vec::Vec;
 
 use crate::*;
@@ -5,8 +7,8 @@ use crate::*;
 #[allow(clippy::upper_case_acronyms)]
 type BG<T, B> = BlockGrid<T, B>;
 
-fn gen_from_raw_vec<B: BlockDim>() {
-    let (rows, cols) = (2 * B::WIDTH, 3 * B::WIDTH);
+fn gen_from_raw_vec<B: BlockShape>() {
+    let (rows, cols) = (2 * B::HEIGHT, 3 * B::WIDTH);
     let data: Vec<_> = (0..(rows * cols)).collect();
     let grid = BG::<_, B>::from_raw_vec(rows, cols, data.clone()).unwrap();
     assert_eq!((grid.rows(), grid.cols()), (rows, cols));
@@ -16,8 +18,8 @@ fn gen_from_raw_vec<B: BlockDim>() {
     }
 }
 
-fn gen_filled<B: BlockDim>() {
-    let (rows, cols) = (2 * B::WIDTH, 3 * B::WIDTH);
+fn gen_filled<B: BlockShape>() {
+    let (rows, cols) = (2 * B::HEIGHT, 3 * B::WIDTH);
     let grid = BG::<_, B>::filled(rows, cols, 7).unwrap();
     assert_eq!((grid.rows(), grid.cols()), (rows, cols));
     assert_eq!(grid.size(), rows * cols);
@@ -26,8 +28,8 @@ fn gen_filled<B: BlockDim>() {
     }
 }
 
-fn gen_from_row_major<B: BlockDim>() {
-    let (rows, cols) = (5 * B::WIDTH, 3 * B::WIDTH);
+fn gen_from_row_major<B: BlockShape>() {
+    let (rows, cols) = (5 * B::HEIGHT, 3 * B::WIDTH);
     let data: Vec<_> = (0..(rows * cols)).collect();
     let grid = BG::<_, B>::from_row_major(rows, cols, &data).unwrap();
     assert_eq!((grid.rows(), grid.cols()), (rows, cols));
@@ -39,8 +41,8 @@ fn gen_from_row_major<B: BlockDim>() {
     }
 }
 
-fn gen_from_col_major<B: BlockDim>() {
-    let (rows, cols) = (3 * B::WIDTH, 3 * B::WIDTH);
+fn gen_from_col_major<B: BlockShape>() {
+    let (rows, cols) = (3 * B::HEIGHT, 3 * B::WIDTH);
     let data: Vec<_> = (0..(rows * cols)).collect();
     let grid = BG::<_, B>::from_col_major(rows, cols, &data).unwrap();
     assert_eq!((grid.rows(), grid.cols()), (rows, cols));
@@ -52,31 +54,131 @@ fn gen_from_col_major<B: BlockDim>() {
     }
 }
 
-fn gen_constructor_invalid<B: BlockDim>() {
-    let mut invalid_sizes = vec![(0, 0), (B::WIDTH, 0), (0, B::WIDTH)];
-    // Ignore non-factor sizes if block-width is 1
-    if B::WIDTH != 1 {
-        invalid_sizes.extend([(3, 5), (7, 13), (B::WIDTH * 3 - 1, B::WIDTH)]);
-    }
+fn gen_constructor_invalid<B: BlockShape>() {
+    let invalid_sizes = [(0, 0), (B::HEIGHT, 0), (0, B::WIDTH), (0, 3)];
     // Try invalid sizes
     for (rows, cols) in invalid_sizes {
         let data: Vec<_> = (0..(rows * cols)).collect();
-        assert!(BG::<_, B>::from_raw_vec(rows, cols, data.clone()).is_err());
-        assert!(BG::<_, B>::filled(rows, cols, 7).is_err());
-        assert!(BG::<_, B>::from_row_major(rows, cols, &data).is_err());
-        assert!(BG::<_, B>::from_col_major(rows, cols, &data).is_err());
+        let err = Some(Error::EmptyDimensions);
+        assert_eq!(
+            BG::<_, B>::from_raw_vec(rows, cols, data.clone()).err(),
+            err
+        );
+        assert_eq!(BG::<_, B>::filled(rows, cols, 7).err(), err);
+        assert_eq!(BG::<_, B>::from_row_major(rows, cols, &data).err(), err);
+        assert_eq!(BG::<_, B>::from_col_major(rows, cols, &data).err(), err);
     }
     // Try giving invalid data length
-    let (rows, cols) = (2 * B::WIDTH, 3 * B::WIDTH);
+    let (rows, cols) = (2 * B::HEIGHT, 3 * B::WIDTH);
     let data: Vec<_> = (0..B::WIDTH).collect();
+    let err = Some(Error::LengthMismatch {
+        expected: rows * cols,
+        actual: B::WIDTH,
+    });
     assert!(BG::<_, B>::filled(rows, cols, 9).is_ok());
-    assert!(BG::<_, B>::from_raw_vec(rows, cols, data.clone()).is_err());
-    assert!(BG::<_, B>::from_row_major(rows, cols, &data).is_err());
-    assert!(BG::<_, B>::from_col_major(rows, cols, &data).is_err());
+    assert_eq!(
+        BG::<_, B>::from_raw_vec(rows, cols, data.clone()).err(),
+        err
+    );
+    assert_eq!(BG::<_, B>::from_row_major(rows, cols, &data).err(), err);
+    assert_eq!(BG::<_, B>::from_col_major(rows, cols, &data).err(), err);
+    // Raw memory must include padding
+    if B::AREA != 1 {
+        let (rows, cols) = (B::HEIGHT + 1, 3);
+        let data: Vec<_> = (0..(rows * cols)).collect();
+        let (rows_pad, cols_pad) = B::round_up_to_valid(rows, cols);
+        let err = Some(Error::LengthMismatch {
+            expected: rows_pad * cols_pad,
+            actual: rows * cols,
+        });
+        assert_eq!(
+            BG::<_, B>::from_raw_vec(rows, cols, data.clone()).err(),
+            err
+        );
+        assert!(BG::<_, B>::from_row_major(rows, cols, &data).is_ok());
+    }
+    // Try overflowing dimensions
+    let big = usize::MAX - (usize::MAX % B::WIDTH);
+    let err = Some(Error::Overflow);
+    assert_eq!(
+        BG::<usize, B>::from_raw_vec(big, big, Vec::new()).err(),
+        err
+    );
+    assert_eq!(BG::<usize, B>::from_row_major(big, big, &[]).err(), err);
+}
+
+fn gen_padded<B: BlockShape>() {
+    let sizes = [(1, 1), (3, 5), (B::HEIGHT + 1, 2 * B::WIDTH - 1), (7, 13)];
+    for (rows, cols) in sizes {
+        let data: Vec<_> = (0..(rows * cols)).collect();
+        let grid = BG::<_, B>::from_row_major(rows, cols, &data).unwrap();
+        let (rows_pad, cols_pad) = B::round_up_to_valid(rows, cols);
+        assert_eq!((grid.rows(), grid.cols()), (rows, cols));
+        assert_eq!(grid.size(), rows * cols);
+        assert_eq!(grid.raw().len(), rows_pad * cols_pad);
+        assert_eq!(grid.row_blocks(), rows_pad / B::HEIGHT);
+        assert_eq!(grid.col_blocks(), cols_pad / B::WIDTH);
+        assert!(grid.contains((rows - 1, cols - 1)));
+        assert!(!grid.contains((rows, cols - 1)));
+        assert!(!grid.contains((rows - 1, cols)));
+        assert!(grid.get((rows, 0)).is_none());
+        assert!(grid.get((0, cols)).is_none());
+        // Valid elements in row-major order
+        assert!(grid.row_major_iter().copied().eq(0..(rows * cols)));
+        assert_eq!(grid.row_major_iter().len(), rows * cols);
+        // Valid elements in memory order, skipping padding
+        let mut it = grid.each_iter().coords();
+        let mut n_left = rows * cols;
+        for bi in 0..grid.row_blocks() {
+            for bj in 0..grid.col_blocks() {
+                for si in 0..B::HEIGHT {
+                    for sj in 0..B::WIDTH {
+                        let c = (B::HEIGHT * bi + si, B::WIDTH * bj + sj);
+                        if !grid.contains(c) {
+                            continue;
+                        }
+                        assert_eq!(it.len(), n_left);
+                        let (ct, &e) = it.next().unwrap();
+                        assert_eq!(ct, c);
+                        assert_eq!(e, cols * c.0 + c.1);
+                        n_left -= 1;
+                    }
+                }
+            }
+        }
+        assert!(it.next().is_none());
+        // Edge blocks only have a partial extent
+        for block in grid.block_iter() {
+            let (row, col) = block.starts_at();
+            let extent = ((rows - row).min(B::HEIGHT), (cols - col).min(B::WIDTH));
+            assert_eq!((block.rows(), block.cols()), extent);
+            assert!(block.contains((extent.0 - 1, extent.1 - 1)));
+            assert!(block.get((extent.0, 0)).is_none());
+            assert!(block.get((0, extent.1)).is_none());
+        }
+        // Mutation only touches valid elements
+        let mut grid = BG::<_, B>::filled(rows, cols, 0).unwrap();
+        for (c, x) in grid.each_iter_mut().coords() {
+            *x = cols * c.0 + c.1 + 1;
+        }
+        for block in grid.block_iter_mut() {
+            assert_eq!(
+                block.rows() * block.cols(),
+                block.raw().iter().filter(|&&x| x != 0).count()
+            );
+        }
+        assert!(grid.row_major_iter().copied().eq(1..=(rows * cols)));
+        // Padding is ignored when comparing
+        let other = BG::<_, B>::from_row_major(rows, cols, &data).unwrap();
+        for x in grid.row_major_iter_mut() {
+            *x -= 1;
+        }
+        assert!(grid == other);
+    }
 }
 
-fn gen_get_and_get_mut<B: BlockDim>() {
-    let (rows, cols) = (2 * B::WIDTH, 3 * B::WIDTH);
+fn gen_get_and_get_mut<B: BlockShape>() {
+    let (rows, cols) = (2 * B::HEIGHT, 3 * B::WIDTH);
     let mut grid = BG::<_, B>::filled(rows, cols, 7).unwrap();
     // Try invalid coordinates
     for &coords in &[(rows, 0), (0, cols), (rows, cols)] {
@@ -98,9 +200,9 @@ fn gen_get_and_get_mut<B: BlockDim>() {
     }
 }
 
-fn gen_block_size<B: BlockDim>() {
+fn gen_block_size<B: BlockShape>() {
     for &(n, m) in &[(1, 1), (2, 3), (3, 1), (4, 4)] {
-        let (rows, cols) = (n * B::WIDTH, m * B::WIDTH);
+        let (rows, cols) = (n * B::HEIGHT, m * B::WIDTH);
         let grid = BG::<usize, B>::new(rows, cols).unwrap();
         assert_eq!(grid.row_blocks(), n);
         assert_eq!(grid.col_blocks(), m);
@@ -108,9 +210,9 @@ fn gen_block_size<B: BlockDim>() {
     }
 }
 
-fn gen_contains<B: BlockDim>() {
+fn gen_contains<B: BlockShape>() {
     for &(n, m) in &[(1, 1), (3, 2), (4, 1), (5, 5)] {
-        let (rows, cols) = (n * B::WIDTH, m * B::WIDTH);
+        let (rows, cols) = (n * B::HEIGHT, m * B::WIDTH);
         let grid = BG::<usize, B>::new(rows, cols).unwrap();
         assert!(grid.contains((0, 0)));
         assert!(grid.contains((0, cols - 1)));
@@ -121,8 +223,8 @@ fn gen_contains<B: BlockDim>() {
     }
 }
 
-fn gen_each_iter<B: BlockDim>() {
-    let (rows, cols) = (3 * B::WIDTH, 2 * B::WIDTH);
+fn gen_each_iter<B: BlockShape>() {
+    let (rows, cols) = (3 * B::HEIGHT, 2 * B::WIDTH);
     let data: Vec<_> = (0..(rows * cols)).collect();
     let grid = BG::<_, B>::from_raw_vec(rows, cols, data).unwrap();
     assert_eq!(grid.each_iter().count(), grid.size());
@@ -130,9 +232,9 @@ fn gen_each_iter<B: BlockDim>() {
     let mut it = grid.each_iter().coords();
     for bi in 0..grid.row_blocks() {
         for bj in 0..grid.col_blocks() {
-            for si in 0..B::WIDTH {
+            for si in 0..B::HEIGHT {
                 for sj in 0..B::WIDTH {
-                    let c = (B::WIDTH * bi + si, B::WIDTH * bj + sj);
+                    let c = (B::HEIGHT * bi + si, B::WIDTH * bj + sj);
                     let (ct, &e) = it.next().unwrap();
                     assert_eq!(ct, c);
                     assert_eq!(e, grid[c]);
@@ -143,8 +245,8 @@ fn gen_each_iter<B: BlockDim>() {
     assert!(it.next().is_none());
 }
 
-fn gen_each_iter_mut<B: BlockDim>() {
-    let (rows, cols) = (3 * B::WIDTH, 2 * B::WIDTH);
+fn gen_each_iter_mut<B: BlockShape>() {
+    let (rows, cols) = (3 * B::HEIGHT, 2 * B::WIDTH);
     let mut grid = BG::<_, B>::filled(rows, cols, 7usize).unwrap();
     assert_eq!(grid.each_iter_mut().count(), grid.size());
     let (row_blocks, col_blocks) = (grid.row_blocks(), grid.col_blocks());
@@ -152,9 +254,9 @@ fn gen_each_iter_mut<B: BlockDim>() {
     let mut it = grid.each_iter_mut().coords();
     for bi in 0..row_blocks {
         for bj in 0..col_blocks {
-            for si in 0..B::WIDTH {
+            for si in 0..B::HEIGHT {
                 for sj in 0..B::WIDTH {
-                    let c = (B::WIDTH * bi + si, B::WIDTH * bj + sj);
+                    let c = (B::HEIGHT * bi + si, B::WIDTH * bj + sj);
                     let (ct, e) = it.next().unwrap();
                     assert_eq!(ct, c);
                     assert_eq!(*e, 7);
@@ -164,7 +266,6 @@ fn gen_each_iter_mut<B: BlockDim>() {
         }
     }
     assert!(it.next().is_none());
-    drop(it);
     // Check if mutated correctly
     for i in 0..rows {
         for j in 0..cols {
@@ -173,8 +274,8 @@ fn gen_each_iter_mut<B: BlockDim>() {
     }
 }
 
-fn gen_block_iter<B: BlockDim>() {
-    let (rows, cols) = (2 * B::WIDTH, 3 * B::WIDTH);
+fn gen_block_iter<B: BlockShape>() {
+    let (rows, cols) = (2 * B::HEIGHT, 3 * B::WIDTH);
     let data: Vec<_> = (0..(rows * cols)).collect();
     let grid = BG::<_, B>::from_raw_vec(rows, cols, data).unwrap();
     assert_eq!(grid.block_iter().count(), grid.blocks());
@@ -183,18 +284,18 @@ fn gen_block_iter<B: BlockDim>() {
     for (c, block) in grid.block_iter().coords() {
         assert_eq!(c, (bi, bj));
         assert_eq!(block.coords(), c);
-        assert_eq!(block.starts_at(), (B::WIDTH * bi, B::WIDTH * bj));
-        for si in 0..B::WIDTH {
+        assert_eq!(block.starts_at(), (B::HEIGHT * bi, B::WIDTH * bj));
+        for si in 0..B::HEIGHT {
             for sj in 0..B::WIDTH {
                 assert_eq!(
                     block[(si, sj)],
-                    grid[(B::WIDTH * bi + si, B::WIDTH * bj + sj)]
+                    grid[(B::HEIGHT * bi + si, B::WIDTH * bj + sj)]
                 );
             }
         }
-        assert!(block.get((B::WIDTH, B::WIDTH - 1)).is_none());
-        assert!(block.get((B::WIDTH - 1, B::WIDTH)).is_none());
-        assert!(block.get((B::WIDTH, B::WIDTH)).is_none());
+        assert!(block.get((B::HEIGHT, B::WIDTH - 1)).is_none());
+        assert!(block.get((B::HEIGHT - 1, B::WIDTH)).is_none());
+        assert!(block.get((B::HEIGHT, B::WIDTH)).is_none());
 
         bj += 1;
         if bj == grid.col_blocks() {
@@ -204,8 +305,8 @@ fn gen_block_iter<B: BlockDim>() {
     }
 }
 
-fn gen_block_iter_mut<B: BlockDim>() {
-    let (rows, cols) = (3 * B::WIDTH, 2 * B::WIDTH);
+fn gen_block_iter_mut<B: BlockShape>() {
+    let (rows, cols) = (3 * B::HEIGHT, 2 * B::WIDTH);
     let mut grid = BG::<_, B>::filled(rows, cols, 7usize).unwrap();
     let col_blocks = grid.col_blocks();
     assert_eq!(grid.block_iter_mut().count(), grid.blocks());
@@ -214,17 +315,17 @@ fn gen_block_iter_mut<B: BlockDim>() {
     for (c, mut block) in grid.block_iter_mut().coords() {
         assert_eq!(c, (bi, bj));
         assert_eq!(block.coords(), c);
-        assert_eq!(block.starts_at(), (B::WIDTH * bi, B::WIDTH * bj));
-        for si in 0..B::WIDTH {
+        assert_eq!(block.starts_at(), (B::HEIGHT * bi, B::WIDTH * bj));
+        for si in 0..B::HEIGHT {
             for sj in 0..B::WIDTH {
                 assert_eq!(block[(si, sj)], 7);
                 let b_ind = bi * col_blocks + bj;
                 block[(si, sj)] = b_ind * B::AREA + si * B::WIDTH + sj;
             }
         }
-        assert!(block.get((B::WIDTH, B::WIDTH - 1)).is_none());
-        assert!(block.get((B::WIDTH - 1, B::WIDTH)).is_none());
-        assert!(block.get((B::WIDTH, B::WIDTH)).is_none());
+        assert!(block.get((B::HEIGHT, B::WIDTH - 1)).is_none());
+        assert!(block.get((B::HEIGHT - 1, B::WIDTH)).is_none());
+        assert!(block.get((B::HEIGHT, B::WIDTH)).is_none());
 
         bj += 1;
         if bj == col_blocks {
@@ -234,11 +335,11 @@ fn gen_block_iter_mut<B: BlockDim>() {
     }
 
     for ((bi, bj), block) in grid.block_iter().coords() {
-        for si in 0..B::WIDTH {
+        for si in 0..B::HEIGHT {
             for sj in 0..B::WIDTH {
                 assert_eq!(
                     block[(si, sj)],
-                    grid[(B::WIDTH * bi + si, B::WIDTH * bj + sj)]
+                    grid[(B::HEIGHT * bi + si, B::WIDTH * bj + sj)]
                 );
                 let b_ind = bi * col_blocks + bj;
                 assert_eq!(block[(si, sj)], b_ind * B::AREA + si * B::WIDTH + sj);
@@ -247,8 +348,8 @@ fn gen_block_iter_mut<B: BlockDim>() {
     }
 }
 
-fn gen_row_major_iter<B: BlockDim>() {
-    let (rows, cols) = (2 * B::WIDTH, 3 * B::WIDTH);
+fn gen_row_major_iter<B: BlockShape>() {
+    let (rows, cols) = (2 * B::HEIGHT, 3 * B::WIDTH);
     let data: Vec<_> = (0..(rows * cols)).collect();
     let grid = BG::<_, B>::from_raw_vec(rows, cols, data).unwrap();
     assert_eq!(grid.row_major_iter().count(), grid.size());
@@ -269,8 +370,8 @@ fn gen_row_major_iter<B: BlockDim>() {
     assert_eq!(it.len(), 0);
 }
 
-fn gen_row_major_iter_mut<B: BlockDim>() {
-    let (rows, cols) = (2 * B::WIDTH, 3 * B::WIDTH);
+fn gen_row_major_iter_mut<B: BlockShape>() {
+    let (rows, cols) = (2 * B::HEIGHT, 3 * B::WIDTH);
     let mut grid = BG::<_, B>::filled(rows, cols, 7usize).unwrap();
     assert_eq!(grid.row_major_iter_mut().count(), grid.size());
     // Mutate while iterating
@@ -297,25 +398,19 @@ fn gen_row_major_iter_mut<B: BlockDim>() {
     }
 }
 
-fn gen_round_up_to_valid<B: BlockDim>() {
-    let check_valid = |(rows, cols): (usize, usize)| {
-        rows > 0 && cols > 0 && rows % B::WIDTH == 0 && cols % B::WIDTH == 0
-    };
-    let mut checks = vec![
-        ((0, 0), (B::WIDTH, B::WIDTH)),
-        ((1, 1), (B::WIDTH, B::WIDTH)),
-        ((B::WIDTH, B::WIDTH), (B::WIDTH, B::WIDTH)),
-    ];
-    if B::WIDTH == 1 {
+fn gen_round_up_to_valid<B: BlockShape>() {
+    let (h, w) = (B::HEIGHT, B::WIDTH);
+    let check_valid =
+        |(rows, cols): (usize, usize)| rows > 0 && cols > 0 && rows % h == 0 && cols % w == 0;
+    let mut checks = vec![((0, 0), (h, w)), ((1, 1), (h, w)), ((h, w), (h, w))];
+    if B::AREA == 1 {
         checks.extend([((3, 7), (3, 7)), ((5, 5), (5, 5))]);
     } else {
         checks.extend([
-            ((B::WIDTH, B::WIDTH + 1), (B::WIDTH, 2 * B::WIDTH)),
-            ((7 * B::WIDTH, 3 * B::WIDTH), (7 * B::WIDTH, 3 * B::WIDTH)),
-            (
-                (4 * B::WIDTH - 1, 4 * B::WIDTH + 1),
-                (4 * B::WIDTH, 5 * B::WIDTH),
-            ),
+            ((h, w + 1), (h, 2 * w)),
+            ((h + 1, w), (2 * h, w)),
+            ((7 * h, 3 * w), (7 * h, 3 * w)),
+            ((4 * h + 1, 4 * w + 1), (5 * h, 5 * w)),
         ]);
     }
     for ((rows, cols), correct) in checks {
@@ -325,6 +420,1570 @@ fn gen_round_up_to_valid<B: BlockDim>() {
     }
 }
 
+fn gen_dyn_matches_static<B: BlockDim>() {
+    for (rows, cols) in [
+        (1, 1),
+        (3, 5),
+        (B::WIDTH + 1, 2 * B::WIDTH - 1),
+        (2 * B::WIDTH, 7),
+    ] {
+        let data: Vec<_> = (0..(rows * cols)).collect();
+        let grid = BG::<_, B>::from_row_major(rows, cols, &data).unwrap();
+        let dyn_grid = DynBlockGrid::from_row_major(rows, cols, B::WIDTH, &data).unwrap();
+        assert_eq!(dyn_grid.block_width(), B::WIDTH);
+        assert_eq!((dyn_grid.rows(), dyn_grid.cols()), (rows, cols));
+        assert_eq!(dyn_grid.row_blocks(), grid.row_blocks());
+        assert_eq!(dyn_grid.col_blocks(), grid.col_blocks());
+        assert_eq!(dyn_grid.raw(), grid.raw());
+        for i in 0..rows {
+            for j in 0..cols {
+                assert_eq!(dyn_grid[(i, j)], grid[(i, j)]);
+            }
+        }
+        assert_eq!(dyn_grid.get((rows, 0)), None);
+        assert_eq!(dyn_grid.get((0, cols)), None);
+        // Iterators
+        assert_eq!(dyn_grid.each_iter().len(), rows * cols);
+        assert!(dyn_grid.each_iter().coords().eq(grid.each_iter().coords()));
+        assert!(dyn_grid.row_major_iter().eq(data.iter()));
+        assert!(dyn_grid
+            .row_major_iter()
+            .coords()
+            .eq(grid.row_major_iter().coords()));
+        assert_eq!(dyn_grid.block_iter().len(), grid.blocks());
+        for ((dyn_c, dyn_block), (c, block)) in dyn_grid
+            .block_iter()
+            .coords()
+            .zip(grid.block_iter().coords())
+        {
+            assert_eq!(dyn_c, c);
+            assert_eq!(dyn_block.coords(), block.coords());
+            assert_eq!(dyn_block.starts_at(), block.starts_at());
+            assert_eq!(
+                (dyn_block.rows(), dyn_block.cols()),
+                (block.rows(), block.cols())
+            );
+            assert_eq!(dyn_block.raw(), block.raw());
+            assert_eq!(dyn_block[(0, 0)], block[(0, 0)]);
+        }
+        // Conversions don't change anything
+        let back = BG::<_, B>::try_from(DynBlockGrid::from(grid.clone())).unwrap();
+        assert!(back == grid);
+        let static_grid = BG::<_, B>::try_from(dyn_grid.clone()).unwrap();
+        assert!(static_grid == grid);
+    }
+}
+
+fn gen_dyn_iters_mut<B: BlockDim>() {
+    let (rows, cols) = (2 * B::WIDTH + 1, B::WIDTH + 3);
+    let mut grid = DynBlockGrid::new(rows, cols, B::WIDTH).unwrap();
+    for ((i, j), x) in grid.each_iter_mut().coords() {
+        *x = (i, j);
+    }
+    for ((i, j), &x) in grid.row_major_iter().coords() {
+        assert_eq!(x, (i, j));
+    }
+    for (i, x) in grid.row_major_iter_mut().enumerate() {
+        *x = (i, 0);
+    }
+    for (i, &x) in grid.row_major_iter().enumerate() {
+        assert_eq!(x, (i, 0));
+    }
+    for mut block in grid.block_iter_mut() {
+        let (bi, bj) = block.starts_at();
+        for i in 0..block.rows() {
+            for j in 0..block.cols() {
+                block[(i, j)] = (bi + i, bj + j);
+            }
+        }
+        assert_eq!(block.get((block.rows(), 0)), None);
+    }
+    for ((i, j), &x) in grid.each_iter().coords() {
+        assert_eq!(x, (i, j));
+    }
+}
+
+fn gen_reblock<B: BlockShape>() {
+    fn check<B: BlockShape, B2: BlockShape>(rows: usize, cols: usize) {
+        let data: Vec<_> = (0..(rows * cols)).collect();
+        let grid = BG::<_, B>::from_row_major(rows, cols, &data).unwrap();
+        let cloned = grid.reblock_cloned::<B2>();
+        assert!(cloned == BG::<_, B2>::from_row_major(rows, cols, &data).unwrap());
+        // Padding is filled the same as constructing from row-major
+        assert_eq!(
+            cloned.raw(),
+            BG::<_, B2>::from_row_major(rows, cols, &data)
+                .unwrap()
+                .raw()
+        );
+        let moved = grid.reblock::<B2>();
+        assert!(moved == cloned);
+        assert!(moved.into_grid().raw() == &data[..]);
+        // Types without a default value can be given any padding
+        #[derive(Clone, Debug, PartialEq)]
+        struct NoDefault(usize);
+        let grid = BG::<_, B>::from_row_major(rows, cols, &data)
+            .unwrap()
+            .map(NoDefault);
+        let grid = grid.reblock_with::<B2>(|| NoDefault(usize::MAX));
+        assert!(grid.row_major_iter().map(|x| x.0).eq(data.iter().copied()));
+        // The same block size reuses the memory, padding and all
+        if (B::HEIGHT, B::WIDTH) != (B2::HEIGHT, B2::WIDTH) {
+            let pads = grid.raw().iter().filter(|x| x.0 == usize::MAX).count();
+            assert_eq!(pads, grid.raw().len() - rows * cols);
+        }
+        // Elements are moved exactly once and any old padding is dropped
+        let counter = Rc::new(());
+        let grid = BG::<_, B>::filled(rows, cols, counter.clone()).unwrap();
+        let grid = grid.reblock::<B2>();
+        let kept = grid.raw().iter().filter(|x| Rc::ptr_eq(x, &counter));
+        assert_eq!(Rc::strong_count(&counter), 1 + kept.count());
+        assert!(grid.each_iter().all(|x| Rc::ptr_eq(x, &counter)));
+        drop(grid);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+    let (h, w) = (B::HEIGHT, B::WIDTH);
+    for (rows, cols) in [(1, 1), (3, 5), (h, w), (2 * h + 1, 3 * w - 1), (17, 9)] {
+        check::<B, U1>(rows, cols);
+        check::<B, U2>(rows, cols);
+        check::<B, U8>(rows, cols);
+        check::<B, Rect<U4, U1>>(rows, cols);
+        check::<B, Rect<U1, U16>>(rows, cols);
+        check::<B, B>(rows, cols);
+    }
+    let grid = Grid::from_row_major(3, 5, &[7; 15]).unwrap();
+    assert!(BG::<_, B>::from_grid(grid).each_iter().all(|&x| x == 7));
+}
+
+/// Windows of a `(2 * h + 3, 3 * w + 1)` grid, some of them block-aligned.
+fn view_windows<B: BlockShape>() -> Vec<(Coords, Coords)> {
+    let (h, w) = (B::HEIGHT, B::WIDTH);
+    let (rows, cols) = (2 * h + 3, 3 * w + 1);
+    vec![
+        ((0, 0), (rows, cols)),
+        ((0, 0), (h, w)),
+        ((h, w), (h + 3, 2 * w + 1)),
+        ((1, 1), (2 * h, 2 * w - 1)),
+        ((2, 0), (1, cols)),
+        ((rows - 1, cols - 1), (1, 1)),
+        ((3, 2), (0, w)),
+        ((0, 0), (0, 0)),
+    ]
+}
+
+fn gen_view<B: BlockShape>() {
+    let (h, w) = (B::HEIGHT, B::WIDTH);
+    let (rows, cols) = (2 * h + 3, 3 * w + 1);
+    let data: Vec<_> = (0..(rows * cols)).collect();
+    let grid = BG::<_, B>::from_row_major(rows, cols, &data).unwrap();
+    for ((r0, c0), (vr, vc)) in view_windows::<B>() {
+        let view = grid.view((r0, c0), (vr, vc));
+        assert_eq!((view.rows(), view.cols(), view.size()), (vr, vc, vr * vc));
+        assert_eq!(view.starts_at(), (r0, c0));
+        for i in 0..vr {
+            for j in 0..vc {
+                assert_eq!(view[(i, j)], grid[(r0 + i, c0 + j)]);
+            }
+        }
+        assert_eq!(view.get((vr, 0)), None);
+        assert_eq!(view.get((0, vc)), None);
+        // Row-major order
+        let grid = &grid;
+        let naive: Vec<_> = (0..vr)
+            .flat_map(|i| (0..vc).map(move |j| ((i, j), &grid[(r0 + i, c0 + j)])))
+            .collect();
+        assert_eq!(view.row_major_iter().len(), vr * vc);
+        assert!(view.row_major_iter().coords().eq(naive.iter().copied()));
+        // Memory order is the same as the grid, restricted to the window
+        let naive: Vec<_> = grid
+            .each_iter()
+            .coords()
+            .filter(|&((i, j), _)| (r0..r0 + vr).contains(&i) && (c0..c0 + vc).contains(&j))
+            .map(|((i, j), x)| ((i - r0, j - c0), x))
+            .collect();
+        assert_eq!(view.each_iter().len(), vr * vc);
+        assert!(view.each_iter().coords().eq(naive.iter().copied()));
+        // Blocks are only available if aligned
+        let aligned = (r0 % h == 0 || r0 == rows)
+            && (c0 % w == 0 || c0 == cols)
+            && ((r0 + vr) % h == 0 || r0 + vr == rows)
+            && ((c0 + vc) % w == 0 || c0 + vc == cols);
+        assert_eq!(view.is_block_aligned(), aligned);
+        assert_eq!(view.block_iter().is_some(), aligned);
+        if let Some(blocks) = view.block_iter() {
+            assert_eq!(blocks.len(), (vr + h - 1) / h * ((vc + w - 1) / w));
+            for ((bi, bj), block) in blocks.coords() {
+                assert_eq!(block.starts_at(), (r0 + h * bi, c0 + w * bj));
+                let (si, sj) = block.starts_at();
+                for i in 0..block.rows() {
+                    for j in 0..block.cols() {
+                        assert_eq!(block[(i, j)], grid[(si + i, sj + j)]);
+                    }
+                }
+            }
+        }
+        // Sub-views
+        if vr > 1 && vc > 1 {
+            let sub = view.view((1, 1), (vr - 1, vc - 1));
+            assert_eq!(sub.starts_at(), (r0 + 1, c0 + 1));
+            assert_eq!(sub[(0, 0)], view[(1, 1)]);
+            assert!(sub
+                .each_iter()
+                .eq(grid.view((r0 + 1, c0 + 1), (vr - 1, vc - 1)).each_iter()));
+        }
+    }
+}
+
+fn gen_view_mut<B: BlockShape>() {
+    let (h, w) = (B::HEIGHT, B::WIDTH);
+    let (rows, cols) = (2 * h + 3, 3 * w + 1);
+    let inside = |(r0, c0): Coords, (vr, vc): Coords, (i, j): Coords| {
+        (r0..r0 + vr).contains(&i) && (c0..c0 + vc).contains(&j)
+    };
+    for (start, size) in view_windows::<B>() {
+        let mut grid = BG::<_, B>::filled(rows, cols, (0, 0)).unwrap();
+        let mut view = grid.view_mut(start, size);
+        for (c, x) in view.each_iter_mut().coords() {
+            *x = c;
+        }
+        assert!(view.row_major_iter().coords().all(|(c, &x)| x == c));
+        for (c, x) in view.row_major_iter_mut().coords() {
+            *x = (c.0 + 1, c.1 + 1);
+        }
+        assert!(view
+            .each_iter()
+            .coords()
+            .all(|((i, j), &x)| x == (i + 1, j + 1)));
+        if let Some(blocks) = view.block_iter_mut() {
+            for mut block in blocks {
+                for i in 0..block.rows() {
+                    for j in 0..block.cols() {
+                        block[(i, j)].0 += 10;
+                    }
+                }
+            }
+            assert!(view
+                .each_iter()
+                .coords()
+                .all(|((i, j), &x)| x == (i + 11, j + 1)));
+        }
+        if size.0 > 1 && size.1 > 1 {
+            let mut sub = view.view_mut((1, 1), (size.0 - 1, size.1 - 1));
+            sub[(0, 0)] = (7, 7);
+            assert_eq!(view[(1, 1)], (7, 7));
+            assert_eq!(view.view((1, 1), (1, 1))[(0, 0)], (7, 7));
+        }
+        // Nothing outside of the window is touched
+        for (c, &x) in grid.each_iter().coords() {
+            assert_eq!(x == (0, 0), !inside(start, size, c));
+        }
+    }
+}
+
+fn gen_split_mut<B: BlockShape>() {
+    fn assert_send<S: Send>(_: &S) {}
+    let (h, w) = (B::HEIGHT, B::WIDTH);
+    let (rows, cols) = (2 * h + 3, 3 * w + 1);
+    let mut grid = BG::<_, B>::filled(rows, cols, 0).unwrap();
+    for b in 0..=grid.row_blocks() {
+        let (mut top, mut bottom) = grid.split_at_row_block_mut(b);
+        assert_send(&top);
+        assert!(top.is_block_aligned() && bottom.is_block_aligned());
+        assert_eq!(top.rows(), rows.min(h * b));
+        assert_eq!(top.rows() + bottom.rows(), rows);
+        assert_eq!((top.cols(), bottom.cols()), (cols, cols));
+        top.each_iter_mut().for_each(|x| *x = 1);
+        bottom.each_iter_mut().for_each(|x| *x = 2);
+        for ((i, _), &x) in grid.each_iter().coords() {
+            assert_eq!(x, if i < h * b { 1 } else { 2 });
+        }
+    }
+    for b in 0..=grid.col_blocks() {
+        let (mut left, mut right) = grid.split_at_col_block_mut(b);
+        assert!(left.is_block_aligned() && right.is_block_aligned());
+        assert_eq!(left.cols(), cols.min(w * b));
+        assert_eq!(left.cols() + right.cols(), cols);
+        assert_eq!((left.rows(), right.rows()), (rows, rows));
+        left.each_iter_mut().for_each(|x| *x = 1);
+        right.each_iter_mut().for_each(|x| *x = 2);
+        for ((_, j), &x) in grid.each_iter().coords() {
+            assert_eq!(x, if j < w * b { 1 } else { 2 });
+        }
+    }
+    for (bh, bw) in [(1, 1), (2, 1), (1, 2), (2, 3), (5, 5)] {
+        let views: Vec<_> = grid.split_blocks_mut((bh, bw)).coords().collect();
+        let (row_views, col_views) = (
+            (rows + h * bh - 1) / (h * bh),
+            (cols + w * bw - 1) / (w * bw),
+        );
+        assert_eq!(views.len(), row_views * col_views);
+        for ((i, j), mut view) in views {
+            assert!(view.is_block_aligned());
+            assert_eq!(view.starts_at(), (h * bh * i, w * bw * j));
+            for mut block in view.block_iter_mut().unwrap() {
+                for i2 in 0..block.rows() {
+                    for j2 in 0..block.cols() {
+                        block[(i2, j2)] = 100 * i + j;
+                    }
+                }
+            }
+        }
+        for ((i, j), &x) in grid.each_iter().coords() {
+            assert_eq!(x, 100 * (i / (h * bh)) + j / (w * bw));
+        }
+    }
+}
+
+/// Naive reference for the element at `(row + dr, col + dc)`, handling the edges with `boundary`.
+fn naive_neighbor<T: Copy, B: BlockShape>(
+    grid: &BG<T, B>,
+    (row, col): Coords,
+    (dr, dc): (isize, isize),
+    boundary: Boundary<'_, T>,
+) -> Option<T> {
+    let resolve = |pos: usize, off: isize, len: usize| {
+        let (mut p, n) = (pos as isize + off, len as isize);
+        if (0..n).contains(&p) {
+            return Some(p as usize);
+        }
+        match boundary {
+            Boundary::Skip | Boundary::Constant(_) => None,
+            Boundary::Clamp => Some(p.max(0).min(n - 1) as usize),
+            Boundary::Wrap => Some(p.rem_euclid(n) as usize),
+            Boundary::Reflect if n == 1 => Some(0),
+            Boundary::Reflect => {
+                while !(0..n).contains(&p) {
+                    p = if p < 0 { -p } else { 2 * (n - 1) - p };
+                }
+                Some(p as usize)
+            }
+        }
+    };
+    match (
+        resolve(row, dr, grid.rows()),
+        resolve(col, dc, grid.cols()),
+        boundary,
+    ) {
+        (Some(r), Some(c), _) => Some(grid[(r, c)]),
+        (_, _, Boundary::Constant(&x)) => Some(x),
+        _ => None,
+    }
+}
+
+fn gen_neighbors<B: BlockShape>() {
+    fn check<B: BlockShape, const K: usize>(grid: &BG<usize, B>, boundary: Boundary<'_, usize>) {
+        let half = (K / 2) as isize;
+        for (c, &x) in grid.each_iter().coords() {
+            let win = grid.window::<K>(c, boundary);
+            assert_eq!(win[K / 2][K / 2], Some(&x));
+            for (i, win_row) in win.iter().enumerate() {
+                for (j, y) in win_row.iter().enumerate() {
+                    let off = (i as isize - half, j as isize - half);
+                    assert_eq!(y.copied(), naive_neighbor(grid, c, off, boundary));
+                }
+            }
+        }
+    }
+
+    let (h, w) = (B::HEIGHT, B::WIDTH);
+    for &(rows, cols) in &[(2 * h + 3, 3 * w + 1), (1, 1), (1, 4), (2, 2), (3, 1)] {
+        let data: Vec<_> = (0..(rows * cols)).collect();
+        let grid = BG::<_, B>::from_row_major(rows, cols, &data).unwrap();
+        let boundaries = [
+            Boundary::Skip,
+            Boundary::Clamp,
+            Boundary::Wrap,
+            Boundary::Reflect,
+            Boundary::Constant(&usize::MAX),
+        ];
+        for &boundary in &boundaries {
+            check::<B, 1>(&grid, boundary);
+            check::<B, 3>(&grid, boundary);
+            check::<B, 5>(&grid, boundary);
+            check::<B, 9>(&grid, boundary);
+            for c in (0..rows).flat_map(|i| (0..cols).map(move |j| (i, j))) {
+                let win = grid.window::<3>(c, boundary);
+                let n4 = grid.neighbors4(c, boundary);
+                assert_eq!(n4, [win[0][1], win[1][0], win[1][2], win[2][1]]);
+                let n8 = grid.neighbors8(c, boundary);
+                let mut naive = win.iter().flatten().copied().collect::<Vec<_>>();
+                naive.remove(4);
+                assert_eq!(&n8[..], &naive[..]);
+            }
+        }
+    }
+}
+
+fn gen_stencil<B: BlockShape>() {
+    fn check<B: BlockShape, const K: usize>(grid: &BG<usize, B>, boundary: Boundary<'_, usize>) {
+        // Not symmetric, so orientation matters
+        let mut kernel = [[0; K]; K];
+        for (i, row) in kernel.iter_mut().enumerate() {
+            for (j, w) in row.iter_mut().enumerate() {
+                *w = K * i + j + 1;
+            }
+        }
+        let mut out = BG::<usize, B>::new(grid.rows(), grid.cols()).unwrap();
+        grid.convolve(&mut out, &kernel, boundary);
+        let half = (K / 2) as isize;
+        for (c, &x) in out.each_iter().coords() {
+            let mut naive = 0;
+            for (i, row) in kernel.iter().enumerate() {
+                for (j, w) in row.iter().enumerate() {
+                    let off = (i as isize - half, j as isize - half);
+                    naive += w * naive_neighbor(grid, c, off, boundary).unwrap_or(0);
+                }
+            }
+            assert_eq!(x, naive);
+        }
+        // Padding isn't touched
+        let padding = out.raw().iter().filter(|&&x| x == 0).count();
+        assert!(padding >= out.raw().len() - out.size());
+    }
+
+    let (h, w) = (B::HEIGHT, B::WIDTH);
+    for &(rows, cols) in &[(2 * h + 3, 3 * w + 1), (1, 1), (2, 5), (4 * h, 2 * w)] {
+        let data: Vec<_> = (1..=(rows * cols)).collect();
+        let grid = BG::<_, B>::from_row_major(rows, cols, &data).unwrap();
+        let boundaries = [
+            Boundary::Skip,
+            Boundary::Clamp,
+            Boundary::Wrap,
+            Boundary::Reflect,
+            Boundary::Constant(&7),
+        ];
+        for &boundary in &boundaries {
+            check::<B, 1>(&grid, boundary);
+            check::<B, 3>(&grid, boundary);
+            check::<B, 5>(&grid, boundary);
+        }
+    }
+}
+
+fn gen_separable<B: BlockShape>() {
+    fn check<B: BlockShape, const K: usize>(grid: &BG<i64, B>, boundary: Boundary<'_, i64>) {
+        // Not symmetric, so orientation matters
+        let mut kernel_x = [0; K];
+        let mut kernel_y = [0; K];
+        for (t, (x, y)) in kernel_x.iter_mut().zip(kernel_y.iter_mut()).enumerate() {
+            *x = t as i64 - 1;
+            *y = 2 * t as i64 + 1;
+        }
+        let mut kernel = [[0; K]; K];
+        for (row, &y) in kernel.iter_mut().zip(&kernel_y) {
+            for (w, &x) in row.iter_mut().zip(&kernel_x) {
+                *w = y * x;
+            }
+        }
+        let mut sep = BG::<i64, B>::new(grid.rows(), grid.cols()).unwrap();
+        let mut full = sep.clone();
+        grid.filter_separable(&mut sep, &kernel_x, &kernel_y, boundary);
+        grid.convolve(&mut full, &kernel, boundary);
+        assert!(sep == full);
+    }
+
+    let (h, w) = (B::HEIGHT, B::WIDTH);
+    for &(rows, cols) in &[(2 * h + 3, 3 * w + 1), (1, 1), (2, 5), (4 * h, 2 * w)] {
+        let data: Vec<_> = (1..=((rows * cols) as i64)).collect();
+        let grid = BG::<_, B>::from_row_major(rows, cols, &data).unwrap();
+        let boundaries = [
+            Boundary::Skip,
+            Boundary::Clamp,
+            Boundary::Wrap,
+            Boundary::Reflect,
+            Boundary::Constant(&-7),
+        ];
+        for &boundary in &boundaries {
+            check::<B, 1>(&grid, boundary);
+            check::<B, 3>(&grid, boundary);
+            check::<B, 5>(&grid, boundary);
+            check::<B, 9>(&grid, boundary);
+        }
+    }
+
+    // Blurs of a constant grid don't change it, except for skipped edges
+    let grid = BG::<f64, B>::filled(2 * h + 3, 3 * w + 1, 0.5).unwrap();
+    let mut out = BG::<f64, B>::new(grid.rows(), grid.cols()).unwrap();
+    for radius in 0..4 {
+        grid.box_blur(&mut out, radius, Boundary::Reflect);
+        assert!(out.each_iter().all(|x| (x - 0.5).abs() < 1e-12));
+        grid.box_blur(&mut out, radius, Boundary::Skip);
+        let corner = 0.5 * ((radius + 1) * (radius + 1)) as f64 / ((2 * radius + 1).pow(2) as f64);
+        assert!((out[(0, 0)] - corner).abs() < 1e-12);
+    }
+}
+
+fn gen_summed_area<B: BlockShape>() {
+    let (h, w) = (B::HEIGHT, B::WIDTH);
+    for &(rows, cols) in &[(2 * h + 3, 3 * w + 1), (1, 1), (3, 5)] {
+        let data: Vec<_> = (0..(rows * cols)).map(|x| (x % 256) as u8).collect();
+        let grid = BG::<u8, B>::from_row_major(rows, cols, &data).unwrap();
+        let sat = grid.summed_area_table::<u32>();
+        assert_eq!((sat.rows(), sat.cols()), (rows, cols));
+        let naive = |(r0, c0): Coords, (r1, c1): Coords| -> u32 {
+            (r0..r1)
+                .flat_map(|i| (c0..c1).map(move |j| (i, j)))
+                .map(|c| grid[c] as u32)
+                .sum()
+        };
+        for (c, &x) in sat.sums().each_iter().coords() {
+            assert_eq!(x, naive((0, 0), (c.0 + 1, c.1 + 1)));
+        }
+        // Every rectangle would be slow, so just the ones with evenly spaced corners
+        let step = |len: usize| (0..=len).step_by((len / 5).max(1)).chain(Some(len));
+        for r0 in step(rows) {
+            for r1 in step(rows).filter(|&r1| r1 >= r0) {
+                for c0 in step(cols) {
+                    for c1 in step(cols).filter(|&c1| c1 >= c0) {
+                        assert_eq!(sat.rect_sum((r0, c0), (r1, c1)), naive((r0, c0), (r1, c1)));
+                    }
+                }
+            }
+        }
+    }
+    // Floating-point accumulators from integers
+    let grid = BG::<u16, B>::filled(2 * h + 1, w + 2, 3).unwrap();
+    let sat = grid.summed_area_table::<f64>();
+    assert_eq!(
+        sat.rect_sum((1, 1), (2 * h + 1, w + 2)),
+        (3 * 2 * h * (w + 1)) as f64
+    );
+    // An accumulator that only just fits the total
+    let grid = BG::<u8, B>::filled(16, 16, 255).unwrap();
+    let sat = grid.summed_area_table::<u16>();
+    assert_eq!(sat.rect_sum((0, 0), (16, 16)), 65280);
+    assert_eq!(sat.rect_sum((1, 2), (16, 16)), 255 * 15 * 14);
+}
+
+fn gen_lines<B: BlockShape>() {
+    let (h, w) = (B::HEIGHT, B::WIDTH);
+    let (rows, cols) = (2 * h + 3, 3 * w + 1);
+    let data: Vec<_> = (0..(rows * cols)).collect();
+    let mut grid = BG::<_, B>::from_row_major(rows, cols, &data).unwrap();
+    let naive_row = |i: usize| (0..cols).map(move |j| cols * i + j);
+    let naive_col = |j: usize| (0..rows).map(move |i| cols * i + j);
+
+    for i in 0..rows {
+        let row = grid.row(i);
+        assert_eq!(row.len(), cols);
+        assert!(row.clone().copied().eq(naive_row(i)));
+        assert!(row.clone().rev().copied().eq(naive_row(i).rev()));
+        assert!(row
+            .clone()
+            .coords()
+            .all(|((a, b), &x)| a == i && x == cols * a + b));
+        assert_eq!(row.get(cols), None);
+        // Indexing is relative to what's left, from both ends
+        let mut row = row;
+        row.next();
+        row.next_back();
+        assert_eq!(row.len(), cols.saturating_sub(2));
+        for k in 0..row.len() {
+            assert_eq!(row[k], cols * i + k + 1);
+        }
+    }
+    for j in 0..cols {
+        let col = grid.col(j);
+        assert_eq!(col.len(), rows);
+        assert!(col.clone().copied().eq(naive_col(j)));
+        assert!(col.clone().rev().copied().eq(naive_col(j).rev()));
+        assert!(col.coords().all(|((a, b), &x)| b == j && x == cols * a + b));
+    }
+    assert_eq!(grid.rows_iter().len(), rows);
+    assert_eq!(grid.cols_iter().len(), cols);
+    assert!(grid.rows_iter().flatten().eq(grid.row_major_iter()));
+    assert!(grid
+        .rows_iter()
+        .rev()
+        .zip((0..rows).rev())
+        .all(|(row, i)| row.copied().eq(naive_row(i))));
+    assert!(grid
+        .cols_iter()
+        .enumerate()
+        .all(|(j, col)| col.copied().eq(naive_col(j))));
+
+    // Mutable
+    for (i, row) in grid.rows_iter_mut().enumerate() {
+        for (x, k) in row.rev().zip((0..cols).rev()) {
+            *x = 10 * (cols * i + k);
+        }
+    }
+    assert!(grid
+        .row_major_iter()
+        .copied()
+        .eq(data.iter().map(|x| 10 * x)));
+    let mut cols_iter = grid.cols_iter_mut();
+    let (mut first, mut last) = (cols_iter.next().unwrap(), cols_iter.next_back());
+    first[0] = 1;
+    if let Some(last) = &mut last {
+        last[rows - 1] = 2;
+        *last.get_mut(0).unwrap() = 3;
+    }
+    assert_eq!(first.get(rows), None);
+    assert!(cols_iter.all(|col| col.len() == rows));
+    assert_eq!(grid[(0, 0)], 1);
+    if cols > 1 {
+        assert_eq!(grid[(rows - 1, cols - 1)], 2);
+        assert_eq!(grid[(0, cols - 1)], 3);
+    }
+    let mut col = grid.col_mut(cols / 2);
+    col.next_back();
+    col[0] = 4;
+    assert_eq!(grid[(0, cols / 2)], 4);
+    grid.row_mut(rows - 1).for_each(|x| *x = 5);
+    assert!(grid.row(rows - 1).all(|&x| x == 5));
+}
+
+/// Checks a double-ended `CoordsIterator` against the `expected` coordinates and items, using a
+/// random mix of `next`, `next_back`, `nth` and `nth_back` from both ends.
+///
+/// A fresh iterator is made by evaluating `$make` each time, and `$key` turns each item into
+/// something that can be compared.
+macro_rules! check_double_ended {
+    ($make: expr, $key: expr, $expected: expr) => {{
+        let expected = &$expected[..];
+        let key = $key;
+        let n = expected.len();
+        let got: Vec<_> = $make.coords().map(|(c, x)| (c, key(x))).collect();
+        assert_eq!(got, expected);
+        let mut got: Vec<_> = $make.coords().rev().map(|(c, x)| (c, key(x))).collect();
+        got.reverse();
+        assert_eq!(got, expected);
+        for k in (0..(n + 2)).step_by(1 + n / 32).chain(n.checked_sub(1)) {
+            let nth = $make.coords().nth(k).map(|(c, x)| (c, key(x)));
+            assert_eq!(nth.as_ref(), expected.get(k));
+            let nth_back = $make.coords().nth_back(k).map(|(c, x)| (c, key(x)));
+            assert_eq!(
+                nth_back.as_ref(),
+                n.checked_sub(k + 1).map(|i| &expected[i])
+            );
+        }
+        // The remaining items are always `expected[lo..hi]`
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+        for _ in 0..16 {
+            let mut iter = $make.coords();
+            let (mut lo, mut hi) = (0, n);
+            loop {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                let r = (seed >> 8) as usize;
+                let k = if seed & 64 == 0 {
+                    r % 4
+                } else {
+                    r % (1 + n / 4)
+                };
+                let (got, want) = match seed % 4 {
+                    0 => (iter.next(), (lo < hi).then(|| lo)),
+                    1 => (iter.next_back(), (lo < hi).then(|| hi - 1)),
+                    2 => (iter.nth(k), (k < hi - lo).then(|| lo + k)),
+                    _ => (iter.nth_back(k), (k < hi - lo).then(|| hi - 1 - k)),
+                };
+                match (seed % 4, want) {
+                    (0, Some(i)) | (2, Some(i)) => lo = i + 1,
+                    (1, Some(i)) | (3, Some(i)) => hi = i,
+                    (0, None) | (2, None) => lo = hi,
+                    _ => hi = lo,
+                }
+                let got = got.map(|(c, x)| (c, key(x)));
+                assert_eq!(got.as_ref(), want.map(|i| &expected[i]));
+                assert_eq!(iter.len(), hi - lo);
+                if lo == hi {
+                    assert!(iter.next().is_none() && iter.next_back().is_none());
+                    break;
+                }
+            }
+        }
+    }};
+}
+
+/// Naive reference for all the coordinates of a `rows` by `cols` grid in memory order.
+fn naive_mem_order<B: BlockShape>(rows: usize, cols: usize) -> Vec<Coords> {
+    let mut coords: Vec<_> = (0..rows)
+        .flat_map(|i| (0..cols).map(move |j| (i, j)))
+        .collect();
+    coords.sort_by_key(|&(i, j)| (i / B::HEIGHT, j / B::WIDTH, i % B::HEIGHT, j % B::WIDTH));
+    coords
+}
+
+fn gen_double_ended<B: BlockShape>() {
+    let (h, w) = (B::HEIGHT, B::WIDTH);
+    for (rows, cols) in [
+        (1, 1),
+        (1, 5),
+        (4, 1),
+        (h, w),
+        (h + 1, 2 * w),
+        (2 * h + 3, 3 * w + 1),
+    ] {
+        let data: Vec<_> = (0..(rows * cols)).collect();
+        let mut grid = BG::<_, B>::from_row_major(rows, cols, &data).unwrap();
+        let at = |(i, j): Coords| ((i, j), cols * i + j);
+        let mem: Vec<_> = naive_mem_order::<B>(rows, cols)
+            .into_iter()
+            .map(at)
+            .collect();
+        let row_major: Vec<_> = (0..rows)
+            .flat_map(|i| (0..cols).map(move |j| (i, j)))
+            .map(at)
+            .collect();
+        let blocks: Vec<_> = (0..grid.row_blocks())
+            .flat_map(|i| (0..grid.col_blocks()).map(move |j| ((i, j), (h * i, w * j))))
+            .collect();
+        check_double_ended!(grid.each_iter(), |&x| x, mem);
+        check_double_ended!(grid.each_iter_mut(), |x: &mut usize| *x, mem);
+        check_double_ended!(grid.row_major_iter(), |&x| x, row_major);
+        check_double_ended!(grid.row_major_iter_mut(), |x: &mut usize| *x, row_major);
+        check_double_ended!(
+            grid.block_iter(),
+            |b: Block<'_, _, B>| b.starts_at(),
+            blocks
+        );
+        check_double_ended!(
+            grid.block_iter_mut(),
+            |b: BlockMut<'_, _, B>| b.starts_at(),
+            blocks
+        );
+        for i in [0, rows / 2, rows - 1] {
+            let row: Vec<_> = (0..cols).map(|j| at((i, j))).collect();
+            check_double_ended!(grid.row(i), |&x| x, row);
+            check_double_ended!(grid.row_mut(i), |x: &mut usize| *x, row);
+        }
+        for j in [0, cols / 2, cols - 1] {
+            let col: Vec<_> = (0..rows).map(|i| at((i, j))).collect();
+            check_double_ended!(grid.col(j), |&x| x, col);
+            check_double_ended!(grid.col_mut(j), |x: &mut usize| *x, col);
+        }
+        assert!(grid
+            .rows_iter()
+            .rev()
+            .map(|row| row[0])
+            .eq((0..rows).rev().map(|i| cols * i)));
+        assert_eq!(
+            grid.cols_iter().nth_back(0).map(|col| col[0]),
+            Some(cols - 1)
+        );
+        assert_eq!(grid.rows_iter_mut().nth(rows).map(|row| row.len()), None);
+        // Writing from the back
+        for (k, x) in grid.each_iter_mut().rev().enumerate() {
+            *x = k;
+        }
+        for (k, &(c, _)) in mem.iter().rev().enumerate() {
+            assert_eq!(grid[c], k);
+        }
+    }
+}
+
+fn gen_double_ended_dyn<B: BlockDim>() {
+    for (rows, cols) in [(1, 1), (3, 5), (B::WIDTH + 1, 2 * B::WIDTH - 1)] {
+        let data: Vec<_> = (0..(rows * cols)).collect();
+        let mut grid = DynBlockGrid::from_row_major(rows, cols, B::WIDTH, &data).unwrap();
+        let at = |(i, j): Coords| ((i, j), cols * i + j);
+        let mem: Vec<_> = naive_mem_order::<B>(rows, cols)
+            .into_iter()
+            .map(at)
+            .collect();
+        let row_major: Vec<_> = (0..rows)
+            .flat_map(|i| (0..cols).map(move |j| (i, j)))
+            .map(at)
+            .collect();
+        let w = B::WIDTH;
+        let blocks: Vec<_> = (0..grid.row_blocks())
+            .flat_map(|i| (0..grid.col_blocks()).map(move |j| ((i, j), (w * i, w * j))))
+            .collect();
+        check_double_ended!(grid.each_iter(), |&x| x, mem);
+        check_double_ended!(grid.each_iter_mut(), |x: &mut usize| *x, mem);
+        check_double_ended!(grid.row_major_iter(), |&x| x, row_major);
+        check_double_ended!(grid.row_major_iter_mut(), |x: &mut usize| *x, row_major);
+        check_double_ended!(
+            grid.block_iter(),
+            |b: DynBlock<'_, _>| b.starts_at(),
+            blocks
+        );
+        check_double_ended!(
+            grid.block_iter_mut(),
+            |b: DynBlockMut<'_, _>| b.starts_at(),
+            blocks
+        );
+    }
+}
+
+fn gen_double_ended_view<B: BlockShape>() {
+    let (h, w) = (B::HEIGHT, B::WIDTH);
+    let (rows, cols) = (2 * h + 3, 3 * w + 1);
+    let data: Vec<_> = (0..(rows * cols)).collect();
+    let mut grid = BG::<_, B>::from_row_major(rows, cols, &data).unwrap();
+    for (start, size) in view_windows::<B>() {
+        let inside = |&(i, j): &Coords| {
+            (start.0..(start.0 + size.0)).contains(&i) && (start.1..(start.1 + size.1)).contains(&j)
+        };
+        let at = |(i, j): Coords| ((i - start.0, j - start.1), cols * i + j);
+        let mem: Vec<_> = naive_mem_order::<B>(rows, cols)
+            .into_iter()
+            .filter(inside)
+            .map(at)
+            .collect();
+        let row_major: Vec<_> = (start.0..(start.0 + size.0))
+            .flat_map(|i| (start.1..(start.1 + size.1)).map(move |j| (i, j)))
+            .map(at)
+            .collect();
+        let view = grid.view(start, size);
+        check_double_ended!(view.each_iter(), |&x| x, mem);
+        check_double_ended!(view.row_major_iter(), |&x| x, row_major);
+        let mut view = grid.view_mut(start, size);
+        check_double_ended!(view.each_iter_mut(), |x: &mut usize| *x, mem);
+        check_double_ended!(view.row_major_iter_mut(), |x: &mut usize| *x, row_major);
+        if view.is_block_aligned() {
+            let (bh, bw) = ((size.0 + h - 1) / h, (size.1 + w - 1) / w);
+            let blocks: Vec<_> = (0..bh)
+                .flat_map(|i| (0..bw).map(move |j| ((i, j), (start.0 + h * i, start.1 + w * j))))
+                .collect();
+            check_double_ended!(
+                view.block_iter().unwrap(),
+                |b: Block<'_, _, B>| b.starts_at(),
+                blocks
+            );
+            check_double_ended!(
+                view.block_iter_mut().unwrap(),
+                |b: BlockMut<'_, _, B>| b.starts_at(),
+                blocks
+            );
+        }
+    }
+    for (bh, bw) in [(1, 1), (2, 1), (1, 2), (5, 5)] {
+        let (vh, vw) = (h * bh, w * bw);
+        let views: Vec<_> = (0..((rows + vh - 1) / vh))
+            .flat_map(|i| (0..((cols + vw - 1) / vw)).map(move |j| ((i, j), (vh * i, vw * j))))
+            .collect();
+        check_double_ended!(
+            grid.split_blocks_mut((bh, bw)),
+            |v: GridViewMut<'_, _, B>| v.starts_at(),
+            views
+        );
+    }
+}
+
+fn gen_col_major_iter<B: BlockShape>() {
+    let (h, w) = (B::HEIGHT, B::WIDTH);
+    for (rows, cols) in [(1, 1), (1, 5), (4, 1), (h, w), (2 * h + 3, 3 * w + 1)] {
+        let data: Vec<_> = (0..(rows * cols)).collect();
+        let mut grid = BG::<_, B>::from_col_major(rows, cols, &data).unwrap();
+        let col_major: Vec<_> = (0..cols)
+            .flat_map(|j| (0..rows).map(move |i| (i, j)))
+            .map(|(i, j)| ((i, j), rows * j + i))
+            .collect();
+        check_double_ended!(grid.col_major_iter(), |&x| x, col_major);
+        check_double_ended!(grid.col_major_iter_mut(), |x: &mut usize| *x, col_major);
+        // Round trips
+        assert_eq!(grid.to_col_major_vec(), data);
+        let row_major = grid.to_row_major_vec();
+        assert!(row_major.iter().eq(grid.row_major_iter()));
+        assert!(BG::<_, B>::from_row_major(rows, cols, &row_major).unwrap() == grid);
+        // Mutate while iterating
+        for (k, x) in grid.col_major_iter_mut().enumerate() {
+            *x = 2 * k;
+        }
+        for ((i, j), &x) in grid.each_iter().coords() {
+            assert_eq!(x, 2 * (rows * j + i));
+        }
+    }
+}
+
+/// Naive reference for all the coordinates of a `rows` by `cols` grid along a space-filling
+/// curve, by sorting on the tile and the position along the curve within it.
+fn naive_curve_order(rows: usize, cols: usize, hilbert: bool) -> Vec<Coords> {
+    let side = rows.min(cols).next_power_of_two();
+    let key = |(i, j): Coords| {
+        let (tile, x, y) = if rows > cols {
+            (i / side, j, i % side)
+        } else {
+            (j / side, j % side, i)
+        };
+        let mut d = 0;
+        if hilbert {
+            // Straight from Wikipedia's `xy2d`, where `x` is the column and `y` is the row
+            let (mut x, mut y, mut s) = (x, y, side / 2);
+            while s > 0 {
+                let (rx, ry) = (((x & s) > 0) as usize, ((y & s) > 0) as usize);
+                d += s * s * ((3 * rx) ^ ry);
+                if ry == 0 {
+                    if rx == 1 {
+                        x = side - 1 - x;
+                        y = side - 1 - y;
+                    }
+                    std::mem::swap(&mut x, &mut y);
+                }
+                s /= 2;
+            }
+        } else {
+            for b in 0..side.trailing_zeros() {
+                d |= ((x >> b) & 1) << (2 * b) | ((y >> b) & 1) << (2 * b + 1);
+            }
+        }
+        (tile, d)
+    };
+    let mut coords: Vec<_> = (0..rows)
+        .flat_map(|i| (0..cols).map(move |j| (i, j)))
+        .collect();
+    coords.sort_by_key(|&c| key(c));
+    coords
+}
+
+fn gen_curve_iters<B: BlockShape>() {
+    let (h, w) = (B::HEIGHT, B::WIDTH);
+    for (rows, cols) in [
+        (1, 1),
+        (1, 5),
+        (4, 1),
+        (7, 19),
+        (h, w),
+        (h + 1, 2 * w),
+        (2 * h + 3, 3 * w + 1),
+    ] {
+        let data: Vec<_> = (0..(rows * cols)).collect();
+        let mut grid = BG::<_, B>::from_row_major(rows, cols, &data).unwrap();
+        for hilbert in [false, true] {
+            let expected: Vec<_> = naive_curve_order(rows, cols, hilbert)
+                .into_iter()
+                .map(|(i, j)| ((i, j), cols * i + j))
+                .collect();
+            if hilbert {
+                check_double_ended!(grid.hilbert_iter(), |&x| x, expected);
+                check_double_ended!(grid.hilbert_iter_mut(), |x: &mut usize| *x, expected);
+            } else {
+                check_double_ended!(grid.morton_iter(), |&x| x, expected);
+                check_double_ended!(grid.morton_iter_mut(), |x: &mut usize| *x, expected);
+            }
+        }
+        // Mutate while iterating
+        for (k, x) in grid.hilbert_iter_mut().enumerate() {
+            *x = k;
+        }
+        assert!(grid.hilbert_iter().copied().eq(0..(rows * cols)));
+    }
+    // Square power-of-two grids have a single tile, so the Hilbert curve never jumps
+    let side = h.max(w).next_power_of_two();
+    let grid = BG::<u8, B>::new(side, side).unwrap();
+    let coords: Vec<_> = grid.hilbert_iter().coords().map(|(c, _)| c).collect();
+    assert_eq!(coords.len(), side * side);
+    for pair in coords.windows(2) {
+        let ((i0, j0), (i1, j1)) = (pair[0], pair[1]);
+        assert_eq!(i0.max(i1) - i0.min(i1) + j0.max(j1) - j0.min(j1), 1);
+    }
+    // Square blocks are each visited in one go along the Morton curve
+    if h == w {
+        let grid = BG::<u8, B>::new(2 * h + 3, 3 * w).unwrap();
+        let mut blocks: Vec<_> = grid
+            .morton_iter()
+            .coords()
+            .map(|((i, j), _)| (i / h, j / w))
+            .collect();
+        blocks.dedup();
+        assert_eq!(blocks.len(), grid.blocks());
+    }
+}
+
+fn check_layout<B: BlockShape, L: Layout>(rows: usize, cols: usize) {
+    let data: Vec<_> = (0..(rows * cols)).collect();
+    let mut grid = BlockGrid::<_, B, L>::from_row_major(rows, cols, &data).unwrap();
+    assert!(grid.row_major_iter().eq(data.iter()));
+    for ((i, j), &x) in grid.row_major_iter().coords() {
+        assert_eq!(grid[(i, j)], x);
+        assert_eq!(x, cols * i + j);
+    }
+    let col_major: Vec<_> = (0..cols)
+        .flat_map(|j| (0..rows).map(move |i| cols * i + j))
+        .collect();
+    assert_eq!(grid.to_col_major_vec(), col_major);
+    let morton: Vec<_> = naive_curve_order(rows, cols, false)
+        .into_iter()
+        .map(|(i, j)| cols * i + j)
+        .collect();
+    assert!(grid.morton_iter().copied().eq(morton));
+    // Every padded coordinate has its own index in memory
+    let (pad_rows, pad_cols) = B::round_up_to_valid(rows, cols);
+    let blocks = (pad_rows / B::HEIGHT, pad_cols / B::WIDTH);
+    let mut inds: Vec<_> = (0..pad_rows)
+        .flat_map(|i| (0..pad_cols).map(move |j| L::offset::<B>(blocks, (i, j))))
+        .collect();
+    inds.sort_unstable();
+    assert!(inds.into_iter().eq(0..grid.raw().len()));
+    // Converting between layouts
+    let default = BG::<_, B>::from_row_major(rows, cols, &data).unwrap();
+    assert!(grid.clone().relayout::<Blocked>() == default);
+    assert!(default.clone().relayout::<L>() == grid);
+    assert!(grid.clone().relayout::<L>().raw() == grid.raw());
+    // Mutate while iterating
+    for (k, x) in grid.col_major_iter_mut().rev().enumerate() {
+        *x = k;
+    }
+    let n = rows * cols;
+    assert!(grid.col_major_iter().copied().eq((0..n).rev()));
+    grid[(rows - 1, cols - 1)] = n;
+    assert_eq!(grid.row_major_iter().next_back(), Some(&n));
+    *grid.get_mut((0, 0)).unwrap() = n + 1;
+    assert_eq!(grid.row_major_iter().next(), Some(&(n + 1)));
+}
+
+fn gen_layouts<B: BlockShape>() {
+    let (h, w) = (B::HEIGHT, B::WIDTH);
+    for (rows, cols) in [(1, 1), (1, 5), (4, 1), (h, w), (2 * h + 3, 3 * w + 1)] {
+        check_layout::<B, Blocked>(rows, cols);
+        check_layout::<B, Blocked<ColMajor>>(rows, cols);
+        check_layout::<B, Blocked<Morton, Morton>>(rows, cols);
+        check_layout::<B, Blocked<RowMajor, ColMajor>>(rows, cols);
+        check_layout::<B, RowMajor>(rows, cols);
+        check_layout::<B, ColMajor>(rows, cols);
+        check_layout::<B, Morton>(rows, cols);
+    }
+    // Blocks are contiguous, and in column-major order
+    let (rows, cols) = (2 * h + 3, 3 * w + 1);
+    let data: Vec<_> = (0..(rows * cols)).collect();
+    let grid = BlockGrid::<_, B, Blocked<ColMajor>>::from_row_major(rows, cols, &data).unwrap();
+    let row_blocks = grid.row_blocks();
+    for ((i, j), &x) in grid.row_major_iter().coords() {
+        let block = row_blocks * (j / w) + i / h;
+        assert_eq!(grid.raw()[B::AREA * block + w * (i % h) + j % w], x);
+    }
+    // Plain row-major, just with padding
+    let grid = BlockGrid::<_, B, RowMajor>::from_row_major(rows, cols, &data).unwrap();
+    let pad_cols = B::round_up_to_valid(rows, cols).1;
+    for ((i, j), &x) in grid.row_major_iter().coords() {
+        assert_eq!(grid.raw()[pad_cols * i + j], x);
+    }
+    // Square blocks in a square power-of-two grid are in the same order as the Morton curve
+    if h == w {
+        let grid = BlockGrid::<_, B, Blocked<Morton, Morton>>::from_row_major(
+            4 * h,
+            4 * w,
+            &(0..(16 * B::AREA)).collect::<Vec<_>>(),
+        )
+        .unwrap();
+        assert!(grid.raw().iter().eq(grid.morton_iter()));
+    }
+}
+
+/// Checks that `grid` matches the naive `model`, given as rows.
+fn check_model<B: BlockShape>(grid: &BG<usize, B>, model: &[Vec<usize>]) {
+    let (rows, cols) = (model.len(), model[0].len());
+    assert_eq!((grid.rows(), grid.cols()), (rows, cols));
+    assert_eq!(grid.col_blocks(), (cols + B::WIDTH - 1) / B::WIDTH);
+    assert_eq!(grid.raw().len(), grid.blocks() * B::AREA);
+    let data: Vec<_> = model.iter().flatten().copied().collect();
+    assert!(*grid == BG::<_, B>::from_row_major(rows, cols, &data).unwrap());
+    assert_eq!(grid.to_row_major_vec(), data);
+    for ((i, j), &x) in grid.each_iter().coords() {
+        assert_eq!(model[i][j], x);
+    }
+    for (k, block) in grid.block_iter().enumerate() {
+        let (b_row, b_col) = (k / grid.col_blocks(), k % grid.col_blocks());
+        assert_eq!(block.coords(), (b_row, b_col));
+    }
+}
+
+fn gen_resize<B: BlockShape>() {
+    let (h, w) = (B::HEIGHT, B::WIDTH);
+    let mut seed = 0x9e37_79b9_7f4a_7c15_u64;
+    for (rows, cols) in [(1, 1), (h, w), (2 * h + 1, w + 3)] {
+        let data: Vec<_> = (0..(rows * cols)).collect();
+        let mut grid = BG::<_, B>::from_row_major(rows, cols, &data).unwrap();
+        let mut model: Vec<Vec<_>> = data.chunks(cols).map(|r| r.to_vec()).collect();
+        for fill in 1000..1040 {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            let r = (seed >> 8) as usize;
+            let (row_blocks, col_blocks) = (grid.row_blocks(), grid.col_blocks());
+            let (rows, cols) = (model.len(), model[0].len());
+            match seed % 7 {
+                0 => {
+                    let (new_rows, new_cols) = (1 + r % (3 * h + 2), 1 + (r >> 16) % (3 * w + 2));
+                    grid.resize(new_rows, new_cols, fill).unwrap();
+                    model.resize(new_rows, vec![fill; cols]);
+                    for row in model.iter_mut() {
+                        row.resize(new_cols, fill);
+                    }
+                }
+                1 => {
+                    grid.push_block_row(fill);
+                    model.resize(rows + h, vec![fill; cols]);
+                }
+                2 => {
+                    grid.push_block_col(fill);
+                    for row in model.iter_mut() {
+                        row.resize(cols + w, fill);
+                    }
+                }
+                3 => {
+                    let at = r % (row_blocks + 1);
+                    grid.insert_block_row(at, fill);
+                    let i = (h * at).min(rows);
+                    model.splice(i..i, (0..h).map(|_| vec![fill; cols]));
+                }
+                4 => {
+                    let at = r % (col_blocks + 1);
+                    grid.insert_block_col(at, fill);
+                    let j = (w * at).min(cols);
+                    for row in model.iter_mut() {
+                        row.splice(j..j, (0..w).map(|_| fill));
+                    }
+                }
+                5 if row_blocks > 1 => {
+                    let at = r % row_blocks;
+                    grid.remove_block_row(at);
+                    model.drain((h * at)..(h * (at + 1)).min(rows));
+                }
+                6 if col_blocks > 1 => {
+                    let at = r % col_blocks;
+                    grid.remove_block_col(at);
+                    for row in model.iter_mut() {
+                        row.drain((w * at)..(w * (at + 1)).min(cols));
+                    }
+                }
+                _ => continue,
+            }
+            check_model(&grid, &model);
+        }
+    }
+    // Invalid sizes leave the grid as is
+    let mut grid = BG::<usize, B>::filled(3, 5, 7).unwrap();
+    assert_eq!(grid.resize(0, 5, 1), Err(Error::EmptyDimensions));
+    assert_eq!(grid.resize(usize::MAX, usize::MAX, 1), Err(Error::Overflow));
+    assert!(grid == BG::filled(3, 5, 7).unwrap());
+}
+
+fn gen_chunked<B: BlockShape>() {
+    let (h, w) = (B::HEIGHT as isize, B::WIDTH as isize);
+    let mut grid = ChunkedGrid::<usize, B>::filled(7);
+    let mut model = std::collections::BTreeMap::new();
+    assert_eq!(grid.bounds(), None);
+    let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+    for i in 0..200 {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        let row = (seed % (6 * h as u64)) as isize - 3 * h;
+        let col = ((seed >> 16) % (6 * w as u64)) as isize - 3 * w;
+        if seed % 3 == 0 {
+            grid[(row, col)] = i;
+        } else {
+            *grid.entry((row, col)) = i;
+        }
+        model.insert((row, col), i);
+    }
+    // Every element of an allocated block is either written, or the fill value
+    let blocks: std::collections::BTreeSet<_> = model
+        .keys()
+        .map(|&(row, col)| (row.div_euclid(h), col.div_euclid(w)))
+        .collect();
+    assert_eq!(grid.blocks(), blocks.len());
+    for row in (-4 * h)..(4 * h) {
+        for col in (-4 * w)..(4 * w) {
+            let expected = model.get(&(row, col)).copied().unwrap_or(7);
+            assert_eq!(grid[(row, col)], expected);
+            let allocated = blocks.contains(&(row.div_euclid(h), col.div_euclid(w)));
+            assert_eq!(grid.get((row, col)).is_some(), allocated);
+        }
+    }
+    // Blocks come in order, with their elements in row-major order
+    assert!(grid.block_iter().map(|(c, _)| c).eq(blocks.iter().copied()));
+    assert_eq!(grid.block_iter().len(), blocks.len());
+    for ((b_row, b_col), block) in grid.block_iter().rev() {
+        assert_eq!(block.coords(), (0, 0));
+        for s_row in 0..B::HEIGHT {
+            for s_col in 0..B::WIDTH {
+                let coords = (h * b_row + s_row as isize, w * b_col + s_col as isize);
+                assert_eq!(block[(s_row, s_col)], grid[coords]);
+            }
+        }
+    }
+    // Bounds cover every block, and nothing more
+    let (origin, (rows, cols)) = grid.bounds().unwrap();
+    let (min_row, max_row) = (
+        blocks.iter().map(|b| b.0).min(),
+        blocks.iter().map(|b| b.0).max(),
+    );
+    let (min_col, max_col) = (
+        blocks.iter().map(|b| b.1).min(),
+        blocks.iter().map(|b| b.1).max(),
+    );
+    assert_eq!(origin, (h * min_row.unwrap(), w * min_col.unwrap()));
+    assert_eq!(rows as isize, h * (max_row.unwrap() - min_row.unwrap() + 1));
+    assert_eq!(cols as isize, w * (max_col.unwrap() - min_col.unwrap() + 1));
+    // Round trips, both aligned and not
+    for &origin in &[origin, (origin.0 - 1, origin.1 + 3), (-h, -w)] {
+        let (rows, cols) = (rows + 2, cols + 1);
+        let dense = grid.to_block_grid(origin, (rows, cols)).unwrap();
+        for ((row, col), &x) in dense.each_iter().coords() {
+            let coords = (origin.0 + row as isize, origin.1 + col as isize);
+            assert_eq!(x, grid[coords]);
+        }
+        let back = ChunkedGrid::<usize, B>::from_block_grid(dense.clone(), origin, 7);
+        for row in (-4 * h)..(4 * h) {
+            for col in (-4 * w)..(4 * w) {
+                let inside = (0..rows as isize).contains(&(row - origin.0))
+                    && (0..cols as isize).contains(&(col - origin.1));
+                let expected = if inside { grid[(row, col)] } else { 7 };
+                assert_eq!(back[(row, col)], expected);
+            }
+        }
+        assert!(back.to_block_grid(origin, (rows, cols)).unwrap() == dense);
+    }
+    // Writing through blocks and removing them
+    let first = grid.block_iter().next().unwrap().0;
+    for (_, mut block) in grid.block_iter_mut() {
+        block[(0, 0)] = 1;
+    }
+    assert_eq!(grid[(h * first.0, w * first.1)], 1);
+    grid.block_mut(first).unwrap()[(B::HEIGHT - 1, 0)] = 2;
+    assert_eq!(grid.block(first).unwrap()[(B::HEIGHT - 1, 0)], 2);
+    assert!(grid.remove_block(first));
+    assert!(!grid.remove_block(first));
+    assert!(grid.block(first).is_none());
+    assert_eq!(grid[(h * first.0, w * first.1)], 7);
+    assert_eq!(grid.blocks(), blocks.len() - 1);
+    grid.clear();
+    assert_eq!((grid.blocks(), grid.bounds()), (0, None));
+}
+
+fn gen_transforms<B: BlockShape>() {
+    let (h, w) = (B::HEIGHT, B::WIDTH);
+    let square = h == w;
+    for &(rows, cols) in &[
+        (1, 1),
+        (h, w),
+        (2 * h, 3 * w),
+        (h + 1, 2 * w + 3),
+        (3 * h + 2, 1),
+    ] {
+        let data: Vec<_> = (0..(rows * cols)).collect();
+        let grid = BG::<_, B>::from_row_major(rows, cols, &data).unwrap();
+        let naive = |(r, c): Coords, f: &dyn Fn(usize, usize) -> usize| {
+            let out: Vec<_> = (0..(r * c)).map(|i| f(i / c, i % c)).collect();
+            BG::<_, B>::from_row_major(r, c, &out).unwrap()
+        };
+        let transposed = naive((cols, rows), &|i, j| data[cols * j + i]);
+        let rotated90 = naive((cols, rows), &|i, j| data[cols * (rows - 1 - j) + i]);
+        let rotated180 = naive((rows, cols), &|i, j| {
+            data[cols * (rows - 1 - i) + cols - 1 - j]
+        });
+        let rotated270 = naive((cols, rows), &|i, j| data[cols * j + cols - 1 - i]);
+        let flipped_h = naive((rows, cols), &|i, j| data[cols * i + cols - 1 - j]);
+        let flipped_v = naive((rows, cols), &|i, j| data[cols * (rows - 1 - i) + j]);
+        assert!(grid.transposed() == transposed);
+        assert!(grid.rotated90() == rotated90);
+        assert!(grid.rotated180() == rotated180);
+        assert!(grid.rotated270() == rotated270);
+        assert!(grid.flipped_horizontal() == flipped_h);
+        assert!(grid.flipped_vertical() == flipped_v);
+        assert!(grid.transposed().transposed() == grid);
+        let in_place = |f: fn(&mut BG<usize, B>)| {
+            let mut g = grid.clone();
+            f(&mut g);
+            g
+        };
+        assert!(in_place(BG::rotate180) == rotated180);
+        assert!(in_place(BG::flip_horizontal) == flipped_h);
+        assert!(in_place(BG::flip_vertical) == flipped_v);
+        if square {
+            let t = in_place(BG::transpose);
+            assert_eq!(
+                (t.rows(), t.cols(), t.col_blocks()),
+                (cols, rows, grid.row_blocks())
+            );
+            assert!(t == transposed);
+            assert!(in_place(BG::rotate90) == rotated90);
+            assert!(in_place(BG::rotate270) == rotated270);
+        }
+    }
+}
+
+fn gen_regions<B: BlockShape>() {
+    fn check<B: BlockShape, B2: BlockShape>() {
+        let (h, w) = (B::HEIGHT, B::WIDTH);
+        let (rows, cols) = (2 * h + 3, 3 * w + 1);
+        let (rows2, cols2) = (3 * B2::HEIGHT + 1, 2 * B2::WIDTH + 5);
+        let data: Vec<_> = (0..(rows * cols)).collect();
+        let data2: Vec<_> = (1000..(1000 + rows2 * cols2)).collect();
+        let mut seed = 0xdead_beef_cafe_f00d_u64;
+        let mut model = data.clone();
+        let mut model2 = data2.clone();
+        let mut grid = BG::<_, B>::from_row_major(rows, cols, &data).unwrap();
+        let mut grid2 = BG::<_, B2>::from_row_major(rows2, cols2, &data2).unwrap();
+        for i in 0..60 {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            let r = seed as usize;
+            let size = (r % (rows.min(rows2) + 1), (r >> 8) % (cols.min(cols2) + 1));
+            let mut origin = (
+                (r >> 16) % (rows - size.0 + 1),
+                (r >> 24) % (cols - size.1 + 1),
+            );
+            let mut origin2 = (
+                (r >> 32) % (rows2 - size.0 + 1),
+                (r >> 40) % (cols2 - size.1 + 1),
+            );
+            if i % 4 == 0 {
+                // Snap to the blocks to hit the whole-block path
+                origin = (origin.0 & !B::ROW_MASK, origin.1 & !B::COL_MASK);
+                origin2 = (origin2.0 & !B2::ROW_MASK, origin2.1 & !B2::COL_MASK);
+            }
+            let cells = (0..size.0).flat_map(|row| (0..size.1).map(move |col| (row, col)));
+            match seed % 4 {
+                0 => {
+                    grid.copy_region(&grid2, origin2, origin, size);
+                    for (row, col) in cells {
+                        let x = model2[cols2 * (origin2.0 + row) + origin2.1 + col];
+                        model[cols * (origin.0 + row) + origin.1 + col] = x;
+                    }
+                }
+                1 => {
+                    grid.fill_region(origin, size, i);
+                    for (row, col) in cells {
+                        model[cols * (origin.0 + row) + origin.1 + col] = i;
+                    }
+                }
+                2 => {
+                    grid.swap_regions(origin, &mut grid2, origin2, size);
+                    for (row, col) in cells {
+                        core::mem::swap(
+                            &mut model[cols * (origin.0 + row) + origin.1 + col],
+                            &mut model2[cols2 * (origin2.0 + row) + origin2.1 + col],
+                        );
+                    }
+                }
+                _ => {
+                    // Copy within the same block size, so whole blocks can line up
+                    let copy = grid.clone();
+                    let origin2 = (
+                        (r >> 32) % (rows - size.0 + 1),
+                        (r >> 40) % (cols - size.1 + 1),
+                    );
+                    let origin2 = if i % 4 == 0 {
+                        (origin2.0 & !B::ROW_MASK, origin2.1 & !B::COL_MASK)
+                    } else {
+                        origin2
+                    };
+                    grid.copy_region(&copy, origin2, origin, size);
+                    let old = model.clone();
+                    for (row, col) in cells {
+                        let x = old[cols * (origin2.0 + row) + origin2.1 + col];
+                        model[cols * (origin.0 + row) + origin.1 + col] = x;
+                    }
+                }
+            }
+            assert_eq!(grid.to_row_major_vec(), model);
+            assert_eq!(grid2.to_row_major_vec(), model2);
+        }
+    }
+    check::<B, B>();
+    check::<B, U2>();
+    check::<B, Rect<U4, U1>>();
+}
+
+fn gen_map<B: BlockShape>() {
+    fn check<B: BlockShape, L: Layout>() {
+        let (rows, cols) = (2 * B::HEIGHT + 1, B::WIDTH + 3);
+        let data: Vec<_> = (0..(rows * cols)).collect();
+        let grid = BlockGrid::<_, B, L>::from_row_major(rows, cols, &data).unwrap();
+        let expected = |f: &dyn Fn(usize) -> usize| {
+            let out: Vec<_> = data.iter().map(|&x| f(x)).collect();
+            BlockGrid::<_, B, L>::from_row_major(rows, cols, &out).unwrap()
+        };
+        let mapped = grid.clone().map(|x| 2 * x);
+        assert!(mapped == expected(&|x| 2 * x));
+        assert_eq!(mapped.raw().len(), grid.raw().len());
+        let mut calls = 0;
+        let with_coords = grid.clone().map_with_coords(|(row, col), x| {
+            calls += 1;
+            assert_eq!(x, cols * row + col);
+            (row, col)
+        });
+        assert_eq!(calls, rows * cols);
+        for (coords, &c) in with_coords.row_major_iter().coords() {
+            assert_eq!(coords, c);
+        }
+        let zipped = grid.clone().zip_map(&mapped, |x, &y| x + y);
+        assert!(zipped == expected(&|x| 3 * x));
+        let mut in_place = grid.clone();
+        in_place.map_in_place(|x| *x += 1);
+        assert!(in_place == expected(&|x| x + 1));
+        in_place.zip_with_mut(&mapped, |x, &y| *x += y);
+        assert!(in_place == expected(&|x| 3 * x + 1));
+        assert!(grid.clone().try_map(Ok::<_, ()>).unwrap() == grid);
+        let mut calls = 0;
+        let err = grid.try_map(|x| {
+            calls += 1;
+            if x == cols + 1 {
+                Err(x)
+            } else {
+                Ok(x)
+            }
+        });
+        assert_eq!(err.err(), Some(cols + 1));
+        assert!(calls < rows * cols);
+
+        // Padding that would make `f` fail is never given to it
+        let (rows, cols) = (3, 3);
+        let (pad_rows, pad_cols) = B::round_up_to_valid(rows, cols);
+        let zeros = vec![0; pad_rows * pad_cols];
+        let mut grid = BlockGrid::<i32, B, L>::from_raw_vec(rows, cols, zeros).unwrap();
+        grid.row_major_iter_mut().for_each(|x| *x = 2);
+        let mut negs = grid.clone();
+        negs.raw_mut().iter_mut().for_each(|x| *x = -1);
+        negs.row_major_iter_mut().for_each(|x| *x = 7);
+
+        let mapped = grid.clone().map(|x| 100 / x);
+        assert!(mapped.raw().iter().all(|&x| x == 50));
+        let mut calls = 0;
+        let mapped = grid.clone().map_with_coords(|(row, col), x| {
+            calls += 1;
+            assert!(row < rows && col < cols);
+            100 / x
+        });
+        assert_eq!(calls, rows * cols);
+        assert!(mapped.raw().iter().all(|&x| x == 50));
+        let mapped = negs.clone().try_map(u8::try_from).unwrap();
+        assert!(mapped.raw().iter().all(|&x| x == 7));
+        let zipped = negs.clone().zip_map(&grid, |x, &y| x / y);
+        assert!(zipped.raw().iter().all(|&x| x == 3));
+        negs.map_in_place(|x| *x = u8::try_from(*x).unwrap() as i32);
+        negs.zip_with_mut(&grid, |x, &y| *x /= y);
+        assert!(negs.row_major_iter().all(|&x| x == 3));
+
+        // Every element is dropped once, even if mapping stops early
+        let counter = Rc::new(());
+        let grid = BlockGrid::<_, B, L>::filled(rows, cols, counter.clone()).unwrap();
+        let mapped = grid.clone().map(|x| x);
+        assert_eq!(Rc::strong_count(&counter), 1 + 2 * mapped.raw().len());
+        drop(mapped);
+        let mut calls = 0;
+        let err = grid.try_map(|x| {
+            calls += 1;
+            if calls == 5 {
+                Err(x)
+            } else {
+                Ok(x)
+            }
+        });
+        assert_eq!(Rc::strong_count(&counter), 2);
+        drop(err);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+    check::<B, Blocked>();
+    check::<B, Blocked<Morton, ColMajor>>();
+    check::<B, ColMajor>();
+}
+
+fn gen_arithmetic<B: BlockShape>() {
+    fn check<B: BlockShape, L: Layout>() {
+        let (rows, cols) = (B::HEIGHT + 1, 2 * B::WIDTH);
+        let a: Vec<i64> = (0..(rows * cols) as i64).map(|x| 3 * x - 40).collect();
+        let b: Vec<i64> = (0..(rows * cols) as i64).map(|x| x % 7 + 1).collect();
+        let ga = BlockGrid::<_, B, L>::from_row_major(rows, cols, &a).unwrap();
+        let mut gb = BlockGrid::<_, B, L>::from_row_major(rows, cols, &b).unwrap();
+        let expected = |f: &dyn Fn(i64, i64) -> i64| {
+            let out: Vec<_> = a.iter().zip(&b).map(|(&x, &y)| f(x, y)).collect();
+            BlockGrid::<_, B, L>::from_row_major(rows, cols, &out).unwrap()
+        };
+        assert!(&ga + &gb == expected(&|x, y| x + y));
+        assert!(ga.clone() - gb.clone() == expected(&|x, y| x - y));
+        assert!(ga.clone() * &gb == expected(&|x, y| x * y));
+        assert!(&ga / &gb == expected(&|x, y| x / y));
+        assert!(&ga + 5 == expected(&|x, _| x + 5));
+        assert!(ga.clone() * -2 == expected(&|x, _| -2 * x));
+        assert!(-&ga == expected(&|x, _| -x));
+        let mut c = ga.clone();
+        c += &gb;
+        c -= 1;
+        c *= gb.clone();
+        c /= 2;
+        assert!(c == expected(&|x, y| (x + y - 1) * y / 2));
+        assert!(ga.checked_div(&gb).unwrap() == expected(&|x, y| x / y));
+        // Padding never takes part, so zeros there can't cause a panic
+        gb.raw_mut().iter_mut().for_each(|x| *x = 0);
+        for (_, x) in gb.row_major_iter_mut().coords() {
+            *x = 1;
+        }
+        assert!(&ga / &gb == ga);
+        // Reductions
+        assert_eq!(ga.sum(), a.iter().sum::<i64>());
+        assert_eq!((ga.min(), ga.argmin()), (-40, (0, 0)));
+        assert_eq!(
+            (ga.max(), ga.argmax()),
+            (a[a.len() - 1], (rows - 1, cols - 1))
+        );
+        let mut f = ga.clone().map(|x| x as f64);
+        assert_eq!(f.mean(), a.iter().sum::<i64>() as f64 / a.len() as f64);
+        f[(1, 0)] = f64::NAN;
+        f[(rows - 1, 1)] = -100.0;
+        f[(0, 1)] = 1e9;
+        assert_eq!((f.argmin(), f.argmax()), ((rows - 1, 1), (0, 1)));
+        // Ties go to the first in row-major order
+        let flat = BlockGrid::<u32, B, L>::filled(rows, cols, 3).unwrap();
+        assert_eq!((flat.argmin(), flat.argmax()), ((0, 0), (0, 0)));
+        assert_eq!(flat.sum(), 3 * (rows * cols) as u32);
+        let other = BlockGrid::<i64, B, L>::filled(rows + 1, cols, 1).unwrap();
+        let err = Error::DimensionsMismatch {
+            expected: (rows, cols),
+            actual: (rows + 1, cols),
+        };
+        assert_eq!(ga.checked_add(&other).err(), Some(err));
+        assert_eq!(ga.checked_sub(&other).err(), Some(err));
+        assert_eq!(ga.checked_mul(&other).err(), Some(err));
+    }
+    check::<B, Blocked>();
+    check::<B, Blocked<ColMajor, Morton>>();
+    check::<B, RowMajor>();
+}
+
+fn gen_matmul<B: BlockShape>() {
+    let (h, w) = (B::HEIGHT, B::WIDTH);
+    for &(m, k, n) in &[
+        (1, 1, 1),
+        (h, w, h),
+        (2 * h + 1, w + 3, 3),
+        (3, 2 * w + 1, 2 * h + 5),
+    ] {
+        let a: Vec<i64> = (0..(m * k) as i64).map(|x| x % 11 - 5).collect();
+        let b: Vec<i64> = (0..(k * n) as i64).map(|x| 7 - x % 13).collect();
+        let ga = BG::<_, B>::from_row_major(m, k, &a).unwrap();
+        let gb = BG::<_, B>::from_row_major(k, n, &b).unwrap();
+        let naive: Vec<_> = (0..(m * n))
+            .map(|i| {
+                (0..k)
+                    .map(|j| a[k * (i / n) + j] * b[n * j + i % n])
+                    .sum::<i64>()
+            })
+            .collect();
+        let c = BlockGrid::matmul(&ga, &gb);
+        assert_eq!((c.rows(), c.cols()), (m, n));
+        assert_eq!(c.to_row_major_vec(), naive);
+        let cf = ga
+            .clone()
+            .map(|x| x as f64)
+            .matmul(&gb.clone().map(|x| x as f64));
+        assert!(cf == c.clone().map(|x| x as f64));
+        assert!(ga.checked_matmul(&gb).unwrap() == c);
+        // Padding never takes part, even if it would overflow
+        let mut gb = gb;
+        gb.raw_mut().iter_mut().for_each(|x| *x = i64::MAX);
+        for ((row, col), x) in gb.row_major_iter_mut().coords() {
+            *x = b[n * row + col];
+        }
+        assert!(ga.matmul(&gb) == c);
+        let err = Error::DimensionsMismatch {
+            expected: (k, m),
+            actual: (m, m),
+        };
+        if k != m {
+            let sq = BG::<i64, B>::filled(m, m, 1).unwrap();
+            assert_eq!(ga.checked_matmul(&sq).err(), Some(err));
+        }
+    }
+}
+
 macro_rules! test_for {
     ($f: ident; $($B: ty),+) => {
         $(
@@ -337,74 +1996,446 @@ macro_rules! test_for {
 #[test]
 fn test_from_raw_vec() {
     test_for!(gen_from_raw_vec; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_from_raw_vec; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
 }
 
 #[test]
 fn test_filled() {
     test_for!(gen_filled; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_filled; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
 }
 
 #[test]
 fn test_from_row_major() {
     test_for!(gen_from_row_major; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_from_row_major; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
 }
 
 #[test]
 fn test_from_col_major() {
     test_for!(gen_from_col_major; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_from_col_major; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
 }
 
 #[test]
 fn test_constructor_invalid() {
     test_for!(gen_constructor_invalid; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_constructor_invalid; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
+}
+
+#[test]
+fn test_padded() {
+    test_for!(gen_padded; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_padded; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
 }
 
 #[test]
 fn test_get_and_get_mut() {
     test_for!(gen_get_and_get_mut; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_get_and_get_mut; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
 }
 
 #[test]
 fn test_block_size() {
     test_for!(gen_block_size; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_block_size; Shift<7>, Rect<Shift<9>, U2>);
+    test_for!(gen_block_size; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
 }
 
 #[test]
 fn test_contains() {
     test_for!(gen_contains; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_contains; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
 }
 
 #[test]
 fn test_each_iter() {
     test_for!(gen_each_iter; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_each_iter; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
 }
 
 #[test]
 fn test_each_iter_mut() {
     test_for!(gen_each_iter_mut; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_each_iter_mut; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
 }
 
 #[test]
 fn test_block_iter() {
     test_for!(gen_block_iter; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_block_iter; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
 }
 
 #[test]
 fn test_block_iter_mut() {
     test_for!(gen_block_iter_mut; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_block_iter_mut; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
 }
 
 #[test]
 fn test_row_major_iter() {
     test_for!(gen_row_major_iter; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_row_major_iter; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
 }
 
 #[test]
 fn test_row_major_iter_mut() {
     test_for!(gen_row_major_iter_mut; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_row_major_iter_mut; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
 }
 
 #[test]
 fn test_round_up_to_valid() {
     test_for!(gen_round_up_to_valid; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_round_up_to_valid; Shift<10>, Shift<15>, Rect<Shift<12>, U1>);
+    test_for!(gen_round_up_to_valid; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
+}
+
+#[test]
+fn test_dyn_matches_static() {
+    test_for!(gen_dyn_matches_static; U1, U2, U4, U8, U16, U32);
+}
+
+#[test]
+fn test_dyn_iters_mut() {
+    test_for!(gen_dyn_iters_mut; U1, U2, U4, U8, U16, U32);
+}
+
+#[test]
+fn test_dyn_invalid() {
+    for block_width in [0, 3, 6, 1 << (MAX_SHIFT + 1)] {
+        let err = Some(Error::InvalidBlockWidth { block_width });
+        assert_eq!(DynBlockGrid::filled(2, 2, block_width, 0).err(), err);
+        assert_eq!(
+            DynBlockGrid::from_row_major(1, 1, block_width, &[0]).err(),
+            err
+        );
+        assert_eq!(
+            DynBlockGrid::from_raw_vec(1, 1, block_width, vec![0]).err(),
+            err
+        );
+    }
+    assert_eq!(
+        DynBlockGrid::<u8>::new(0, 3, 4).err(),
+        Some(Error::EmptyDimensions)
+    );
+    assert_eq!(
+        DynBlockGrid::from_raw_vec(3, 3, 2, vec![0; 9]).err(),
+        Some(Error::LengthMismatch {
+            expected: 16,
+            actual: 9
+        })
+    );
+    assert_eq!(
+        DynBlockGrid::<u8>::new(usize::MAX, usize::MAX, 2).err(),
+        Some(Error::Overflow)
+    );
+    let grid = DynBlockGrid::filled(3, 3, 2, 0).unwrap();
+    assert!(BG::<_, U4>::try_from(grid).is_err());
+}
+
+#[test]
+fn test_dyn_eq_across_block_widths() {
+    use core::hash::{Hash, Hasher};
+    use std::collections::hash_map::DefaultHasher;
+
+    let hash = |grid: &DynBlockGrid<usize>| {
+        let mut hasher = DefaultHasher::new();
+        grid.hash(&mut hasher);
+        hasher.finish()
+    };
+    let data: Vec<_> = (0..(5 * 7)).collect();
+    let a = DynBlockGrid::from_row_major(5, 7, 2, &data).unwrap();
+    let b = DynBlockGrid::from_row_major(5, 7, 4, &data).unwrap();
+    assert_eq!(a, b);
+    assert_eq!(hash(&a), hash(&b));
+    let c = DynBlockGrid::from_row_major(7, 5, 4, &data).unwrap();
+    assert_ne!(a, c);
+}
+
+#[test]
+fn test_reblock() {
+    test_for!(gen_reblock; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_reblock; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
+}
+
+#[test]
+fn test_view() {
+    test_for!(gen_view; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_view; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
+}
+
+#[test]
+fn test_view_mut() {
+    test_for!(gen_view_mut; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_view_mut; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
+}
+
+#[test]
+#[should_panic(expected = "View out of bounds")]
+fn test_view_out_of_bounds() {
+    let grid = BG::<u8, U4>::new(5, 7).unwrap();
+    grid.view((1, 1), (4, 7));
+}
+
+#[test]
+fn test_split_mut() {
+    test_for!(gen_split_mut; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_split_mut; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
+}
+
+#[test]
+#[should_panic(expected = "Block row out of bounds")]
+fn test_split_out_of_bounds() {
+    let mut grid = BG::<u8, U4>::new(5, 7).unwrap();
+    grid.split_at_row_block_mut(3);
+}
+
+#[test]
+fn test_neighbors() {
+    test_for!(gen_neighbors; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_neighbors; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
+}
+
+#[test]
+#[should_panic(expected = "Window size must be odd")]
+fn test_window_even() {
+    let grid = Grid::filled(3, 3, 0).unwrap();
+    grid.window::<2>((1, 1), Boundary::Skip);
+}
+
+#[test]
+#[should_panic(expected = "Index out of bounds")]
+fn test_neighbors_out_of_bounds() {
+    let grid = BG::<_, U2>::filled(3, 3, 0).unwrap();
+    grid.neighbors4((3, 0), Boundary::Clamp);
+}
+
+#[test]
+fn test_stencil() {
+    test_for!(gen_stencil; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_stencil; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
+}
+
+#[test]
+#[should_panic(expected = "Grid dimensions must match")]
+fn test_stencil_mismatch() {
+    let grid = BG::<u32, U2>::new(3, 3).unwrap();
+    let mut out = BG::<u32, U2>::new(3, 4).unwrap();
+    grid.convolve(&mut out, &[[1]], Boundary::Skip);
+}
+
+#[test]
+fn test_separable() {
+    test_for!(gen_separable; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_separable; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
+}
+
+#[test]
+#[should_panic(expected = "Kernel length must be odd")]
+fn test_separable_even() {
+    let grid = BG::<i32, U2>::new(3, 3).unwrap();
+    let mut out = grid.clone();
+    grid.filter_separable(&mut out, &[1, 1], &[1], Boundary::Skip);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_gaussian_blur() {
+    let mut grid = BG::<f32, U4>::new(9, 11).unwrap();
+    grid[(4, 5)] = 1.0;
+    let mut out = grid.clone();
+    grid.gaussian_blur(&mut out, 1.0, Boundary::Constant(&0.0));
+    // Symmetric, peaked at the centre, and sums to one
+    let total: f32 = out.each_iter().sum();
+    assert!((total - 1.0).abs() < 1e-5);
+    assert_eq!(out[(3, 5)], out[(5, 5)]);
+    assert_eq!(out[(4, 4)], out[(4, 6)]);
+    assert!(out.each_iter().all(|&x| x <= out[(4, 5)]));
+    assert!(out[(4, 4)] > out[(3, 4)]);
+}
+
+#[test]
+fn test_summed_area() {
+    test_for!(gen_summed_area; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_summed_area; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
+}
+
+#[test]
+#[should_panic(expected = "Rectangle out of bounds")]
+fn test_rect_sum_out_of_bounds() {
+    let grid = BG::<u8, U2>::filled(3, 3, 1).unwrap();
+    grid.summed_area_table::<u32>().rect_sum((1, 0), (4, 2));
+}
+
+#[test]
+fn test_lines() {
+    test_for!(gen_lines; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_lines; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
+}
+
+#[test]
+#[should_panic(expected = "Column out of bounds")]
+fn test_col_out_of_bounds() {
+    let grid = BG::<u8, U4>::new(5, 7).unwrap();
+    grid.col(7);
+}
+
+#[test]
+fn test_double_ended() {
+    test_for!(gen_double_ended; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_double_ended; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
+}
+
+#[test]
+fn test_double_ended_dyn() {
+    test_for!(gen_double_ended_dyn; U1, U2, U4, U8, U16, U32);
+}
+
+#[test]
+fn test_double_ended_view() {
+    test_for!(gen_double_ended_view; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_double_ended_view; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
+}
+
+#[test]
+fn test_col_major_iter() {
+    test_for!(gen_col_major_iter; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_col_major_iter; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
+}
+
+#[test]
+fn test_curve_iters() {
+    test_for!(gen_curve_iters; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_curve_iters; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
+}
+
+#[test]
+fn test_layouts() {
+    test_for!(gen_layouts; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_layouts; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
+}
+
+#[test]
+fn test_morton_order() {
+    for rows in 1..=12 {
+        for cols in 1..=12 {
+            let n = rows * cols;
+            let mut coords: Vec<_> = (0..rows)
+                .flat_map(|i| (0..cols).map(move |j| (i, j)))
+                .collect();
+            coords.sort_by_key(|&c| Morton::index((rows, cols), c));
+            assert_eq!(coords, naive_curve_order(rows, cols, false));
+            let mut inds: Vec<_> = coords
+                .iter()
+                .map(|&c| Morton::index((rows, cols), c))
+                .collect();
+            inds.dedup();
+            assert!(inds.into_iter().eq(0..n));
+        }
+    }
+    // Full tiles interleave the bits of the coordinates, with rows in the higher bits
+    let side = 1 << 8;
+    assert_eq!(
+        Morton::index((side, side), (side - 1, side - 1)),
+        side * side - 1
+    );
+    assert_eq!(Morton::index((side, side), (side - 1, 0)), 0xAAAA);
+    assert_eq!(
+        Morton::index((side, 3 * side), (0, 2 * side + 3)),
+        2 * side * side + 5
+    );
+}
+
+#[test]
+fn test_resize() {
+    test_for!(gen_resize; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_resize; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
+}
+
+#[test]
+#[should_panic(expected = "Can't remove the only block column")]
+fn test_remove_only_block_col() {
+    let mut grid = BG::<u8, U4>::new(9, 3).unwrap();
+    grid.remove_block_col(0);
+}
+
+#[test]
+#[should_panic(expected = "Block row out of bounds")]
+fn test_insert_block_row_out_of_bounds() {
+    let mut grid = BG::<u8, U4>::new(9, 3).unwrap();
+    grid.insert_block_row(4, 0);
+}
+
+#[test]
+fn test_chunked() {
+    test_for!(gen_chunked; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_chunked; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
+}
+
+#[test]
+fn test_transforms() {
+    test_for!(gen_transforms; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_transforms; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
+}
+
+#[test]
+#[should_panic(expected = "Can't transpose non-square blocks in place")]
+fn test_transpose_rect_blocks() {
+    BG::<u8, Rect<U2, U4>>::filled(3, 3, 0).unwrap().transpose();
+}
+
+#[test]
+fn test_regions() {
+    test_for!(gen_regions; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_regions; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
+}
+
+#[test]
+#[should_panic(expected = "Region out of bounds")]
+fn test_fill_region_out_of_bounds() {
+    BG::<u8, U4>::filled(5, 5, 0)
+        .unwrap()
+        .fill_region((3, 0), (3, 1), 1);
+}
+
+#[test]
+fn test_map() {
+    test_for!(gen_map; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_map; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
+}
+
+#[test]
+#[should_panic(expected = "Grid dimensions must match")]
+fn test_zip_map_mismatch() {
+    let a = BG::<u8, U2>::filled(3, 4, 0).unwrap();
+    let b = BG::<u8, U2>::filled(4, 3, 0).unwrap();
+    a.zip_map(&b, |x, &y| x + y);
+}
+
+#[test]
+fn test_arithmetic() {
+    test_for!(gen_arithmetic; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_arithmetic; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
+}
+
+#[test]
+#[should_panic(expected = "Grid dimensions must match")]
+fn test_add_mismatch() {
+    let a = BG::<f32, U2>::filled(3, 4, 0.0).unwrap();
+    let b = BG::<f32, U2>::filled(3, 5, 0.0).unwrap();
+    let _ = a + b;
+}
+
+#[test]
+fn test_matmul() {
+    test_for!(gen_matmul; U1, U2, U4, U8, U16, U32);
+    test_for!(gen_matmul; Rect<U1, U4>, Rect<U8, U2>, Rect<U2, U16>, Rect<U32, U1>);
+}
+
+#[test]
+#[should_panic(expected = "Inner dimensions of a matrix product must match")]
+fn test_matmul_mismatch() {
+    let a = BG::<f32, U2>::filled(3, 4, 1.0).unwrap();
+    a.matmul(&a);
 }
@@ -56,6 +56,26 @@ fn bench_blur(c: &mut Criterion) {
         );
     });
 
+    g.bench_function("block_grid_window", |b| {
+        b.iter_batched_ref(
+            || out_bg.clone(),
+            |out_grid| {
+                blur_window(&in_bg, out_grid);
+            },
+            BatchSize::SmallInput,
+        );
+    });
+
+    g.bench_function("block_grid_stencil", |b| {
+        b.iter_batched_ref(
+            || out_bg.clone(),
+            |out_grid| {
+                blur_stencil(&in_bg, out_grid);
+            },
+            BatchSize::SmallInput,
+        );
+    });
+
     g.bench_function("block_grid_u1_index", |b| {
         b.iter_batched_ref(
             || out_bg_u1.clone(),
@@ -0,0 +1,75 @@
+extern crate block_grid;
+extern crate criterion;
+extern crate fastrand;
+extern crate tb_suite;
+
+use block_grid::{BlockGrid, Blocked, ColMajor, Layout, Morton, RowMajor, U8};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use tb_suite::blur::blur_by_index;
+
+type B = U8;
+
+const ROWS: usize = 256;
+const COLS: usize = 128;
+
+fn gen_grid<L: Layout>() -> BlockGrid<u8, B, L> {
+    fastrand::seed(1234);
+    let data: Vec<_> = (0..(ROWS * COLS)).map(|_| fastrand::u8(..)).collect();
+    BlockGrid::from_row_major(ROWS, COLS, &data).unwrap()
+}
+
+/// Runs the same kernels over a grid with layout `L`
+fn bench_layout<L: Layout>(c: &mut Criterion, name: &str) {
+    let in_bg = gen_grid::<L>();
+    let out_bg = in_bg.clone();
+
+    let mut g = c.benchmark_group(format!("Layout/{}", name));
+    g.bench_function("blur_index", |b| {
+        b.iter_batched_ref(
+            || out_bg.clone(),
+            |out_grid| {
+                blur_by_index(ROWS, COLS, &in_bg, out_grid);
+            },
+            BatchSize::SmallInput,
+        );
+    });
+
+    g.bench_function("row_major_sum", |b| {
+        b.iter(|| {
+            black_box(&in_bg)
+                .row_major_iter()
+                .map(|&x| x as u32)
+                .sum::<u32>()
+        });
+    });
+
+    g.bench_function("col_major_sum", |b| {
+        b.iter(|| {
+            black_box(&in_bg)
+                .col_major_iter()
+                .map(|&x| x as u32)
+                .sum::<u32>()
+        });
+    });
+
+    g.bench_function("morton_sum", |b| {
+        b.iter(|| {
+            black_box(&in_bg)
+                .morton_iter()
+                .map(|&x| x as u32)
+                .sum::<u32>()
+        });
+    });
+    g.finish()
+}
+
+fn bench_layouts(c: &mut Criterion) {
+    bench_layout::<Blocked>(c, "blocked");
+    bench_layout::<Blocked<ColMajor>>(c, "blocked_col_major");
+    bench_layout::<Blocked<Morton, Morton>>(c, "blocked_morton");
+    bench_layout::<RowMajor>(c, "row_major");
+    bench_layout::<Morton>(c, "morton");
+}
+
+criterion_group!(benches, bench_layouts);
+criterion_main!(benches);
@@ -55,9 +55,12 @@ fn indexing(c: &mut Criterion) {
         b.iter(|| {
             for bi in 0..grid.row_blocks() {
                 for bj in 0..grid.col_blocks() {
-                    for si in 0..B::WIDTH {
-                        for sj in 0..B::WIDTH {
-                            let (i, j) = (B::WIDTH * bi + si, B::WIDTH * bj + sj);
+                    for si in 0..<B as BlockDim>::WIDTH {
+                        for sj in 0..<B as BlockDim>::WIDTH {
+                            let (i, j) = (
+                                <B as BlockDim>::WIDTH * bi + si,
+                                <B as BlockDim>::WIDTH * bj + sj,
+                            );
                             black_box(grid[(i, j)]);
                         }
                     }
@@ -70,9 +73,12 @@ fn indexing(c: &mut Criterion) {
         b.iter(|| {
             for bi in 0..grid.row_blocks() {
                 for bj in 0..grid.col_blocks() {
-                    for si in 0..B::WIDTH {
-                        for sj in 0..B::WIDTH {
-                            let (i, j) = (B::WIDTH * bi + si, B::WIDTH * bj + sj);
+                    for si in 0..<B as BlockDim>::WIDTH {
+                        for sj in 0..<B as BlockDim>::WIDTH {
+                            let (i, j) = (
+                                <B as BlockDim>::WIDTH * bi + si,
+                                <B as BlockDim>::WIDTH * bj + sj,
+                            );
                             black_box(unsafe { grid.get_unchecked((i, j)) });
                         }
                     }
@@ -124,8 +130,8 @@ fn iterators(c: &mut Criterion) {
     g.bench_function("block_iter_index", |b| {
         b.iter(|| {
             for block in grid.block_iter() {
-                for i in 0..B::WIDTH {
-                    for j in 0..B::WIDTH {
+                for i in 0..<B as BlockDim>::WIDTH {
+                    for j in 0..<B as BlockDim>::WIDTH {
                         black_box(block[(i, j)]);
                     }
                 }
@@ -136,8 +142,8 @@ fn iterators(c: &mut Criterion) {
     g.bench_function("block_iter_get_unchecked", |b| {
         b.iter(|| {
             for block in grid.block_iter() {
-                for i in 0..B::WIDTH {
-                    for j in 0..B::WIDTH {
+                for i in 0..<B as BlockDim>::WIDTH {
+                    for j in 0..<B as BlockDim>::WIDTH {
                         black_box(unsafe { block.get_unchecked((i, j)) });
                     }
                 }
@@ -4,7 +4,7 @@ extern crate block_grid;
 use std::ops::{Index, IndexMut};
 
 use array2d::Array2D;
-use block_grid::{BlockDim, BlockGrid, CoordsIterator};
+use block_grid::{BlockGrid, BlockShape, Boundary, CoordsIterator};
 
 /// New pixel is average of 3x3 kernel
 fn get_new_pix<G>(img: &G, (i, j): (usize, usize)) -> u8
@@ -71,7 +71,7 @@ pub fn blur_array2d(img: &Array2D<u8>, out: &mut Array2D<u8>) {
     }
 }
 
-pub fn blur_blockgrid<B: BlockDim>(img: &BlockGrid<u8, B>, out: &mut BlockGrid<u8, B>) {
+pub fn blur_blockgrid<B: BlockShape>(img: &BlockGrid<u8, B>, out: &mut BlockGrid<u8, B>) {
     debug_assert_eq!(img.rows(), out.rows());
     debug_assert_eq!(img.cols(), out.cols());
     let (rows, cols) = (img.rows(), img.cols());
@@ -105,3 +105,52 @@ pub fn blur_blockgrid<B: BlockDim>(img: &BlockGrid<u8, B>, out: &mut BlockGrid<u
         }
     }
 }
+
+pub fn blur_window<B: BlockShape>(img: &BlockGrid<u8, B>, out: &mut BlockGrid<u8, B>) {
+    debug_assert_eq!(img.rows(), out.rows());
+    debug_assert_eq!(img.cols(), out.cols());
+    let (rows, cols) = (img.rows(), img.cols());
+    debug_assert!(rows >= 3 && cols >= 3);
+
+    // Iterate over each pixel
+    for ((i, j), &x) in img.each_iter().coords() {
+        // Copy perimeter
+        if i == 0 || j == 0 || i == rows - 1 || j == cols - 1 {
+            // SAFETY: Generated coordinates _should_ be valid
+            unsafe {
+                *out.get_unchecked_mut((i, j)) = x;
+            }
+        } else {
+            // Inner pixels are never at the edge, so the boundary doesn't matter
+            let tot: u32 = img
+                .window::<3>((i, j), Boundary::Skip)
+                .iter()
+                .flatten()
+                .flatten()
+                .map(|&&x| x as u32)
+                .sum();
+            out[(i, j)] = (tot / 9) as u8;
+        }
+    }
+}
+
+pub fn blur_stencil<B: BlockShape>(img: &BlockGrid<u8, B>, out: &mut BlockGrid<u8, B>) {
+    let (rows, cols) = (img.rows(), img.cols());
+    debug_assert!(rows >= 3 && cols >= 3);
+
+    // Any boundary works, as the perimeter is overwritten below
+    img.apply_stencil(out, Boundary::Clamp, |win: [[Option<&u8>; 3]; 3]| {
+        let tot: u32 = win.iter().flatten().flatten().map(|&&x| x as u32).sum();
+        (tot / 9) as u8
+    });
+
+    // Copy perimeter
+    for i in 0..rows {
+        out[(i, 0)] = img[(i, 0)];
+        out[(i, cols - 1)] = img[(i, cols - 1)];
+    }
+    for j in 0..cols {
+        out[(0, j)] = img[(0, j)];
+        out[(rows - 1, j)] = img[(rows - 1, j)];
+    }
+}
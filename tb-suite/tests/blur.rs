@@ -7,7 +7,7 @@ use array2d::Array2D;
 use block_grid::*;
 use tb_suite::blur::*;
 
-fn gen_test_index<B: BlockDim>(rows: usize, cols: usize) {
+fn gen_test_index<B: BlockShape>(rows: usize, cols: usize) {
     let mut in_bg = BlockGrid::<u8, B>::new(rows, cols).unwrap();
     let mut out_bg = in_bg.clone();
 
@@ -33,9 +33,11 @@ fn gen_test_index<B: BlockDim>(rows: usize, cols: usize) {
     }
 }
 
-fn gen_test_idiomatic<B: BlockDim>(rows: usize, cols: usize) {
+fn gen_test_idiomatic<B: BlockShape>(rows: usize, cols: usize) {
     let mut in_bg = BlockGrid::<u8, B>::new(rows, cols).unwrap();
     let mut out_bg = in_bg.clone();
+    let mut out_window = in_bg.clone();
+    let mut out_stencil = in_bg.clone();
     let mut out_index = in_bg.clone();
 
     let mut in_ar = Array2D::filled_with(0u8, rows, cols);
@@ -52,12 +54,16 @@ fn gen_test_idiomatic<B: BlockDim>(rows: usize, cols: usize) {
 
     blur_by_index(rows, cols, &in_bg, &mut out_index);
     blur_blockgrid(&in_bg, &mut out_bg);
+    blur_window(&in_bg, &mut out_window);
+    blur_stencil(&in_bg, &mut out_stencil);
     blur_array2d(&in_ar, &mut out_ar);
 
     for i in 0..rows {
         for j in 0..cols {
             let x = out_index[(i, j)];
             assert_eq!(out_bg[(i, j)], x);
+            assert_eq!(out_window[(i, j)], x);
+            assert_eq!(out_stencil[(i, j)], x);
             assert_eq!(out_ar[(i, j)], x);
         }
     }
@@ -69,6 +75,8 @@ fn test_blur_by_index() {
     gen_test_index::<U2>(30, 30);
     gen_test_index::<U8>(16, 40);
     gen_test_index::<U32>(96, 64);
+    gen_test_index::<U8>(27, 45);
+    gen_test_index::<Rect<U2, U16>>(27, 45);
 }
 
 #[test]
@@ -77,4 +85,6 @@ fn test_blur_idiomatic() {
     gen_test_idiomatic::<U2>(30, 30);
     gen_test_idiomatic::<U8>(16, 40);
     gen_test_idiomatic::<U32>(96, 64);
+    gen_test_idiomatic::<U8>(27, 45);
+    gen_test_idiomatic::<Rect<U2, U16>>(27, 45);
 }
@@ -0,0 +1,87 @@
+extern crate block_grid;
+extern crate rayon;
+
+use std::collections::HashSet;
+
+use block_grid::*;
+use rayon::prelude::*;
+
+#[allow(clippy::upper_case_acronyms)]
+type BG<T, B> = BlockGrid<T, B>;
+
+fn gen_par_iters<B: BlockShape + Send + Sync>(rows: usize, cols: usize) {
+    let data: Vec<_> = (0..(rows * cols)).collect();
+    let grid = BG::<_, B>::from_row_major(rows, cols, &data).unwrap();
+
+    // Row-major is indexed, so the order is kept
+    let v: Vec<_> = grid.par_row_major_iter().copied().collect();
+    assert_eq!(v, data);
+    let v: Vec<_> = grid.par_row_major_iter().coords().collect();
+    assert!(v.into_iter().eq(grid.row_major_iter().coords()));
+
+    // Blocks are also indexed
+    assert_eq!(grid.par_block_iter().len(), grid.blocks());
+    let v: Vec<_> = grid.par_block_iter().coords().collect();
+    let seq: Vec<_> = grid.block_iter().coords().collect();
+    assert_eq!(v.len(), seq.len());
+    for ((c, block), (seq_c, seq_block)) in v.into_iter().zip(seq) {
+        assert_eq!((c, block.coords()), (seq_c, seq_block.coords()));
+        assert_eq!(
+            (block.rows(), block.cols()),
+            (seq_block.rows(), seq_block.cols())
+        );
+        assert_eq!(block.raw(), seq_block.raw());
+    }
+
+    // Elements are in any order, but there's exactly one of each and no padding
+    let v: HashSet<_> = grid.par_each_iter().coords().collect();
+    assert_eq!(v.len(), rows * cols);
+    assert!(v.iter().all(|&((i, j), &x)| x == cols * i + j));
+    assert_eq!(grid.par_each_iter().count(), rows * cols);
+    assert_eq!(grid.par_each_iter().sum::<usize>(), data.iter().sum());
+}
+
+fn gen_par_iters_mut<B: BlockShape + Send + Sync>(rows: usize, cols: usize) {
+    let mut grid = BG::<_, B>::filled(rows, cols, (0, 0)).unwrap();
+    grid.par_each_iter_mut().coords().for_each(|(c, x)| *x = c);
+    assert!(grid.each_iter().coords().all(|(c, &x)| c == x));
+    grid.par_each_iter_mut().for_each(|x| x.0 += 1);
+    assert!(grid
+        .each_iter()
+        .coords()
+        .all(|((i, j), &x)| (i + 1, j) == x));
+    // Padding isn't touched
+    let padding = grid.raw().iter().filter(|&&x| x == (0, 0)).count();
+    assert_eq!(padding, grid.raw().len() - rows * cols);
+
+    grid.par_block_iter_mut()
+        .coords()
+        .for_each(|(c, mut block)| {
+            assert_eq!(c, block.coords());
+            let (si, sj) = block.starts_at();
+            for i in 0..block.rows() {
+                for j in 0..block.cols() {
+                    block[(i, j)] = (si + i, sj + j);
+                }
+            }
+        });
+    assert!(grid.each_iter().coords().all(|(c, &x)| c == x));
+}
+
+#[test]
+fn test_par_iters() {
+    gen_par_iters::<U1>(7, 13);
+    gen_par_iters::<U2>(8, 6);
+    gen_par_iters::<U4>(27, 45);
+    gen_par_iters::<U8>(64, 64);
+    gen_par_iters::<Rect<U2, U16>>(27, 45);
+}
+
+#[test]
+fn test_par_iters_mut() {
+    gen_par_iters_mut::<U1>(7, 13);
+    gen_par_iters_mut::<U2>(8, 6);
+    gen_par_iters_mut::<U4>(27, 45);
+    gen_par_iters_mut::<U8>(64, 64);
+    gen_par_iters_mut::<Rect<U2, U16>>(27, 45);
+}
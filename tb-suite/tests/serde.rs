@@ -34,6 +34,74 @@ fn test_serdes_u8() {
     assert!(serde_json::from_str::<BG<u8, U16>>(&s).is_err());
 }
 
+#[test]
+fn test_serdes_padded() {
+    let data: Vec<_> = (0..(5 * 7)).collect();
+    let bg = BG::<u16, U4>::from_row_major(5, 7, &data).unwrap();
+    let s = serde_json::to_string(&bg).unwrap();
+    let ds = serde_json::from_str::<BG<u16, U4>>(&s).unwrap();
+    assert_eq!(ds, bg);
+    assert_eq!((ds.rows(), ds.cols()), (5, 7));
+    assert!(ds.row_major_iter().copied().eq(0..(5 * 7)));
+    assert!(serde_json::from_str::<BG<u16, U2>>(&s).is_err());
+}
+
+#[test]
+fn test_serdes_rect() {
+    let data: Vec<_> = (0..(5 * 7)).collect();
+    let bg = BG::<u16, Rect<U2, U4>>::from_row_major(5, 7, &data).unwrap();
+    let s = serde_json::to_string(&bg).unwrap();
+    let ds = serde_json::from_str::<BG<u16, Rect<U2, U4>>>(&s).unwrap();
+    assert_eq!(ds, bg);
+    assert!(serde_json::from_str::<BG<u16, U4>>(&s).is_err());
+    assert!(serde_json::from_str::<BG<u16, Rect<U4, U4>>>(&s).is_err());
+
+    // Square blocks are compatible with the original format
+    let bg = BG::<u16, U4>::from_row_major(5, 7, &data).unwrap();
+    let s = serde_json::to_string(&bg).unwrap();
+    assert!(!s.contains("bh"));
+    let ds = serde_json::from_str::<BG<u16, Rect<U4, U4>>>(&s).unwrap();
+    assert!(ds.row_major_iter().eq(bg.row_major_iter()));
+    assert!(serde_json::from_str::<BG<u16, Rect<U2, U4>>>(&s).is_err());
+}
+
+#[test]
+fn test_serdes_dyn() {
+    let data: Vec<_> = (0..(5 * 7)).collect();
+    let bg = BG::<u16, U4>::from_row_major(5, 7, &data).unwrap();
+    let s = serde_json::to_string(&bg).unwrap();
+    // Any square block width is accepted
+    let ds = serde_json::from_str::<DynBlockGrid<u16>>(&s).unwrap();
+    assert_eq!(ds.block_width(), 4);
+    assert!(ds.row_major_iter().eq(bg.row_major_iter()));
+    // Same format both ways
+    assert_eq!(serde_json::to_string(&ds).unwrap(), s);
+    let ds = serde_json::from_str::<BG<u16, U4>>(&serde_json::to_string(&ds).unwrap()).unwrap();
+    assert_eq!(ds, bg);
+
+    let bg = BG::<u16, Rect<U2, U4>>::from_row_major(5, 7, &data).unwrap();
+    let s = serde_json::to_string(&bg).unwrap();
+    let err = serde_json::from_str::<DynBlockGrid<u16>>(&s).unwrap_err();
+    assert_eq!(err.to_string(), "Expected a block height of 4, but found 2");
+    let s = r#"{"rows":1,"cols":1,"b":3,"buf":[0,0,0,0,0,0,0,0,0]}"#;
+    assert!(serde_json::from_str::<DynBlockGrid<u16>>(s).is_err());
+}
+
+#[test]
+fn test_serdes_layout() {
+    let data: Vec<_> = (0..(5 * 7)).collect();
+    let bg = BG::<u16, U4>::from_row_major(5, 7, &data).unwrap();
+    let morton =
+        BlockGrid::<u16, U4, Blocked<Morton, Morton>>::from_row_major(5, 7, &data).unwrap();
+    // The memory is always written in the default layout
+    let s = serde_json::to_string(&morton).unwrap();
+    assert_eq!(s, serde_json::to_string(&bg).unwrap());
+    let ds = serde_json::from_str::<BlockGrid<u16, U4, RowMajor>>(&s).unwrap();
+    assert!(ds.row_major_iter().eq(bg.row_major_iter()));
+    let ds = serde_json::from_str::<BlockGrid<u16, U4, Blocked<Morton, Morton>>>(&s).unwrap();
+    assert_eq!(ds, morton);
+}
+
 #[test]
 fn test_serdes_i64() {
     let data: Vec<_> = repeat_with(|| fastrand::i64(..)).take(8 * 8).collect();
@@ -57,6 +125,28 @@ fn test_serdes_f32() {
     assert!(serde_json::from_str::<BG<i64, U4>>(&s).is_err());
 }
 
+#[test]
+fn test_serdes_invalid() {
+    let bg = BG::<u8, U2>::filled(4, 8, 7).unwrap();
+    let s = serde_json::to_string(&bg).unwrap();
+    let e = serde_json::from_str::<BG<u8, U4>>(&s).unwrap_err();
+    let msg = Error::BlockWidthMismatch {
+        expected: 4,
+        actual: 2,
+    }
+    .to_string();
+    assert!(e.to_string().contains(&msg));
+
+    let s = s.replace("\"rows\":4", "\"rows\":5");
+    let e = serde_json::from_str::<BG<u8, U2>>(&s).unwrap_err();
+    let msg = Error::LengthMismatch {
+        expected: 6 * 8,
+        actual: 4 * 8,
+    }
+    .to_string();
+    assert!(e.to_string().contains(&msg));
+}
+
 #[test]
 fn test_serdes_rgb() {
     #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]